@@ -50,10 +50,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let gen_config = GenerationConfig::default()
         .with_model(models::GRANITE_4_H_SMALL);
 
-    match (&client).generate_text_stream(prompt, &gen_config, |chunk| {
-        print!("{}", chunk);
-        std::io::Write::flush(&mut std::io::stdout()).unwrap();
-    }).await {
+    match client.generate_text_stream_flushed(prompt, &gen_config, std::io::stdout()).await {
         Ok(result) => {
             println!("\n---");
             println!("\n✅ Generation completed!");
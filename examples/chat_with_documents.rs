@@ -88,8 +88,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     match client.chat_with_docs(&agent.agent_id, &thread_id, request).await {
         Ok(response) => {
             println!("{}", response.message);
-            if let Some(docs) = response.documents_used {
-                println!("Documents used: {:?}", docs);
+            for doc in response.citations() {
+                println!("Source: {} ({}, relevance {:.2})", doc.title, doc.document_id, doc.relevance_score);
             }
             if let Some(confidence) = response.confidence {
                 println!("Confidence: {:.2}%", confidence * 100.0);
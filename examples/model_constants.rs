@@ -8,7 +8,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = WatsonxConfig::from_env()?;
 
     // Create client
-    let mut client = WatsonxClient::new(config)?;
+    let client = WatsonxClient::new(config)?;
 
     // Connect to WatsonX
     println!("Connecting to WatsonX...");
@@ -126,12 +126,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let message1 = "Hello! What can you help me with?";
     println!("You: {}", message1);
 
-    match client.send_message(&agent.agent_id, message1, Some(thread_id.clone())).await {
-        Ok((response, new_thread_id)) => {
-            println!("🤖 Agent: {}", response);
-            if let Some(tid) = new_thread_id {
+    match client.send_message(&agent.agent_id, message1, Some(thread_id.clone()), None).await {
+        Ok(result) => {
+            println!("🤖 Agent: {}", result.answer);
+            if let Some(tid) = result.thread_id {
                 println!("   Thread ID: {}", tid);
             }
+            if let Some(run_id) = result.run_id {
+                println!("   Run ID: {}", run_id);
+            }
+            for call in &result.tool_calls {
+                println!("   Tool call: {} ({})", call.tool_name, call.id);
+            }
+            if let Some(ms) = result.duration_ms {
+                println!("   Duration: {} ms", ms);
+            }
         }
         Err(e) => println!("❌ Error: {}", e),
     }
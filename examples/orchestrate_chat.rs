@@ -62,10 +62,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("You: {}", message1);
     
     let mut thread_id = None;
-    match client.send_message(&agent.agent_id, message1, thread_id).await {
-        Ok((response, new_thread_id)) => {
-            thread_id = new_thread_id;
-            println!("\n🤖 Agent: {}", response);
+    match client.send_message(&agent.agent_id, message1, thread_id, None).await {
+        Ok(result) => {
+            thread_id = result.thread_id;
+            println!("\n🤖 Agent: {}", result.answer);
             if let Some(ref tid) = thread_id {
                 println!("   (Thread ID: {})", tid);
             }
@@ -81,10 +81,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let message2 = "What services do you provide?";
     println!("You: {}", message2);
     
-    match client.send_message(&agent.agent_id, message2, thread_id.clone()).await {
-        Ok((response, new_thread_id)) => {
-            thread_id = new_thread_id;
-            println!("\n🤖 Agent: {}", response);
+    match client.send_message(&agent.agent_id, message2, thread_id.clone(), None).await {
+        Ok(result) => {
+            thread_id = result.thread_id;
+            println!("\n🤖 Agent: {}", result.answer);
             if let Some(ref tid) = thread_id {
                 println!("   (Thread ID: {})", tid);
             }
@@ -23,12 +23,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Streaming response:");
     println!("---");
 
-    let result = (&client)
-        .generate_text_stream(prompt, &gen_config, |chunk| {
-            // Print each chunk as it arrives
-            print!("{}", chunk);
-            std::io::Write::flush(&mut std::io::stdout()).unwrap();
-        })
+    // Each chunk is written to stdout and flushed as it arrives
+    let result = client
+        .generate_text_stream_flushed(prompt, &gen_config, std::io::stdout())
         .await?;
 
     println!("\n---");
@@ -0,0 +1,194 @@
+//! Pluggable access token providers
+//!
+//! By default `WatsonxClient` exchanges its API key for an IAM token. A `TokenProvider`
+//! replaces that step, e.g. `TokenExchangeProvider` for federated identity where an OIDC
+//! token from an external IdP (Azure AD, Okta, ...) is exchanged for an IBM Cloud token.
+
+use crate::error::{Error, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Future returned by `TokenProvider::fetch_token`
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>>;
+
+/// Grant type for RFC 8693 token exchange
+const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+
+/// Token type of the OIDC subject token
+const JWT_TOKEN_TYPE: &str = "urn:ietf:params:oauth:token-type:jwt";
+
+/// Source of IAM access tokens for `WatsonxClient`
+pub trait TokenProvider: Send + Sync {
+    /// Fetch a fresh IAM access token
+    ///
+    /// `client` is the HTTP client of the `WatsonxClient`, `iam_url` the configured IAM
+    /// host and `timeout` the configured connect timeout.
+    fn fetch_token<'a>(&'a self, client: &'a Client, iam_url: &'a str, timeout: Duration) -> TokenFuture<'a>;
+}
+
+/// Exchanges an OIDC token from an external identity provider for an IAM token
+///
+/// If `subject_token` is empty, one is first requested from `oidc_token_endpoint` with
+/// the OAuth client credentials grant using `client_id` and `client_secret`.
+#[derive(Clone)]
+pub struct TokenExchangeProvider {
+    /// Token endpoint of the external identity provider
+    pub oidc_token_endpoint: String,
+    /// OAuth client ID registered with the identity provider
+    pub client_id: String,
+    /// OAuth client secret registered with the identity provider
+    pub client_secret: String,
+    /// OIDC token to exchange (fetched from the IdP when empty)
+    pub subject_token: String,
+}
+
+#[derive(Deserialize)]
+struct OAuthTokenResponse {
+    access_token: Option<String>,
+    id_token: Option<String>,
+}
+
+impl TokenExchangeProvider {
+    /// Create a provider for the given identity provider and client credentials
+    pub fn new(
+        oidc_token_endpoint: impl Into<String>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            oidc_token_endpoint: oidc_token_endpoint.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            subject_token: String::new(),
+        }
+    }
+
+    /// Use an OIDC token already obtained from the identity provider
+    pub fn with_subject_token(mut self, subject_token: impl Into<String>) -> Self {
+        self.subject_token = subject_token.into();
+        self
+    }
+
+    /// Form fields for exchanging `subject_token` at the IAM token endpoint
+    fn exchange_form(subject_token: &str) -> [(&'static str, &str); 3] {
+        [
+            ("grant_type", TOKEN_EXCHANGE_GRANT_TYPE),
+            ("subject_token", subject_token),
+            ("subject_token_type", JWT_TOKEN_TYPE),
+        ]
+    }
+
+    /// Request an OIDC token from the identity provider with the client credentials grant
+    async fn fetch_subject_token(&self, client: &Client, timeout: Duration) -> Result<String> {
+        let response = client
+            .post(&self.oidc_token_endpoint)
+            .timeout(timeout)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[("grant_type", "client_credentials"), ("scope", "openid")])
+            .send()
+            .await
+            .map_err(|e| Error::Network(format!("OIDC token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "No error details available".to_string());
+            return Err(Error::Authentication(format!(
+                "Failed to obtain OIDC token from {} (HTTP {}): {}",
+                self.oidc_token_endpoint, status, error_text
+            )));
+        }
+
+        let token: OAuthTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(format!("Failed to parse OIDC token response: {}", e)))?;
+
+        token.id_token.or(token.access_token).ok_or_else(|| {
+            Error::Authentication("OIDC token response contained no token".to_string())
+        })
+    }
+
+    /// Exchange the subject token for an IAM access token
+    async fn exchange(&self, client: &Client, iam_url: &str, timeout: Duration) -> Result<String> {
+        let subject_token = if self.subject_token.trim().is_empty() {
+            self.fetch_subject_token(client, timeout).await?
+        } else {
+            self.subject_token.clone()
+        };
+
+        let url = format!("https://{}/identity/token", iam_url);
+        let response = client
+            .post(&url)
+            .timeout(timeout)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .form(&Self::exchange_form(&subject_token))
+            .send()
+            .await
+            .map_err(|e| Error::Network(format!("IAM token exchange request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "No error details available".to_string());
+            return Err(Error::Authentication(format!(
+                "Failed to exchange OIDC token with IAM service (HTTP {}): {}",
+                status, error_text
+            )));
+        }
+
+        let token: OAuthTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(format!("Failed to parse IAM token response: {}", e)))?;
+
+        token.access_token.ok_or_else(|| {
+            Error::Authentication("IAM token exchange response contained no access token".to_string())
+        })
+    }
+}
+
+impl TokenProvider for TokenExchangeProvider {
+    fn fetch_token<'a>(&'a self, client: &'a Client, iam_url: &'a str, timeout: Duration) -> TokenFuture<'a> {
+        Box::pin(self.exchange(client, iam_url, timeout))
+    }
+}
+
+impl fmt::Debug for TokenExchangeProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenExchangeProvider")
+            .field("oidc_token_endpoint", &self.oidc_token_endpoint)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"<redacted>")
+            .field("subject_token", &"<redacted>")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_exchange_provider() {
+        let provider = TokenExchangeProvider::new("https://idp.example.com/oauth2/token", "client", "secret")
+            .with_subject_token("eyJ.token");
+        assert_eq!(provider.subject_token, "eyJ.token");
+
+        let form = TokenExchangeProvider::exchange_form(&provider.subject_token);
+        assert_eq!(form[0], ("grant_type", "urn:ietf:params:oauth:grant-type:token-exchange"));
+        assert_eq!(form[1], ("subject_token", "eyJ.token"));
+
+        let debug = format!("{:?}", provider);
+        assert!(!debug.contains("secret\""));
+        assert!(!debug.contains("eyJ.token"));
+    }
+}
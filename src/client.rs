@@ -3,21 +3,52 @@
 use crate::config::WatsonxConfig;
 use crate::error::{Error, Result};
 use crate::models::*;
+use crate::sse::ByteChunks;
+use crate::stream::{ChatChunk, GenerationChunk, WatsonxStream};
+use crate::auth::{TokenExchangeProvider, TokenProvider};
 use crate::types::*;
 use futures::future::join_all;
-use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use futures::{Stream, StreamExt};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io::Write;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+/// Number of chunks buffered by `generate_text_stream_channel` before the sender waits
+const STREAM_CHANNEL_CAPACITY: usize = 64;
+
+/// Refresh the IAM token when less than this much of its lifetime remains
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
 /// WatsonX AI client for interacting with IBM WatsonX services
+///
+/// Cloning is cheap: the underlying HTTP client shares its connection pool.
+#[derive(Clone)]
 pub struct WatsonxClient {
     config: WatsonxConfig,
-    access_token: Option<String>,
+    /// Token from `connect()`, shared by clones of this client
+    access_token: Arc<RwLock<Option<String>>>,
     client: Client,
     current_model: String,
+    token_provider: Option<Arc<dyn TokenProvider>>,
+    successful_endpoint: Arc<RwLock<Option<String>>>,
+    /// Rate limit budget from the most recent successful response
+    rate_limit: Arc<RwLock<Option<RateLimitState>>>,
+    /// When the IAM token expires, if the token endpoint reported it
+    token_expiry: Arc<RwLock<Option<Instant>>>,
+    /// Serializes token requests so concurrent callers share one refresh
+    token_refresh: Arc<Mutex<()>>,
+    /// Retry policy applied to text generation requests
+    retry_config: Option<RetryConfig>,
 }
 
 #[derive(Serialize)]
@@ -29,17 +60,78 @@ struct TokenRequest {
 #[derive(Deserialize)]
 struct TokenResponse {
     access_token: String,
+    /// Token lifetime in seconds
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// An access token and, when known, the time it expires
+struct IssuedToken {
+    token: String,
+    expires_at: Option<Instant>,
 }
 
 #[derive(Serialize)]
 struct GenerationParams {
-    decoding_method: String,
+    decoding_method: DecodingMethod,
     max_new_tokens: u32,
     min_new_tokens: u32,
-    top_k: u32,
-    top_p: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
     repetition_penalty: f32,
     stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    return_options: Option<ReturnOptions>,
+}
+
+/// Extra response fields requested from the generation API
+#[derive(Serialize)]
+struct ReturnOptions {
+    generated_tokens: bool,
+    input_tokens: bool,
+    token_logprobs: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_n_tokens: Option<u32>,
+}
+
+impl GenerationParams {
+    /// Build request parameters from a generation config
+    ///
+    /// Sampling parameters are only sent with `DecodingMethod::Sample`; greedy decoding
+    /// ignores them.
+    fn from_config(config: &GenerationConfig, min_new_tokens: u32) -> Self {
+        let decoding_method = config.effective_decoding_method();
+        let sampling = decoding_method == DecodingMethod::Sample;
+        Self {
+            decoding_method,
+            max_new_tokens: config.max_tokens,
+            min_new_tokens,
+            top_k: sampling.then(|| config.top_k.unwrap_or(50)),
+            top_p: sampling.then(|| config.top_p.unwrap_or(1.0)),
+            temperature: match decoding_method {
+                DecodingMethod::Sample => config.temperature,
+                DecodingMethod::Greedy => None,
+            },
+            repetition_penalty: config.repetition_penalty.unwrap_or(1.1),
+            stop_sequences: config.stop_sequences.clone(),
+            random_seed: match decoding_method {
+                DecodingMethod::Sample => config.seed,
+                DecodingMethod::Greedy => None,
+            },
+            return_options: config.return_logprobs.map(|top_k| ReturnOptions {
+                generated_tokens: true,
+                input_tokens: true,
+                token_logprobs: true,
+                top_n_tokens: (top_k > 0).then_some(top_k),
+            }),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -50,9 +142,129 @@ struct GenerationRequest {
     project_id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 struct GenerationResults {
     generated_text: String,
+    input_token_count: Option<u32>,
+    generated_token_count: Option<u32>,
+    #[serde(default)]
+    generated_tokens: Option<Vec<GeneratedToken>>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+/// A generated token as returned with `return_options.generated_tokens`
+#[derive(Clone, Deserialize)]
+struct GeneratedToken {
+    text: String,
+    logprob: Option<f32>,
+    #[serde(default)]
+    top_tokens: Option<Vec<TopToken>>,
+}
+
+#[derive(Clone, Deserialize)]
+struct TopToken {
+    text: String,
+    logprob: Option<f32>,
+}
+
+impl GeneratedToken {
+    fn into_token_logprob(self) -> TokenLogprob {
+        TokenLogprob {
+            token: self.text,
+            logprob: self.logprob.unwrap_or(f32::NEG_INFINITY),
+            top_logprobs: self.top_tokens.map(|tokens| {
+                tokens
+                    .into_iter()
+                    .map(|t| (t.text, t.logprob.unwrap_or(f32::NEG_INFINITY)))
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl From<GenerationResults> for RawGenerationData {
+    fn from(results: GenerationResults) -> Self {
+        Self {
+            text: results.generated_text,
+            stop_reason: results.stop_reason,
+            input_tokens: results.input_token_count,
+            output_tokens: results.generated_token_count,
+            token_logprobs: results
+                .generated_tokens
+                .map(|tokens| tokens.into_iter().map(GeneratedToken::into_token_logprob).collect()),
+            truncated: false,
+            retry_stats: None,
+        }
+    }
+}
+
+/// Generated text and metadata, as collected from either the streaming or the
+/// non-streaming generation endpoint
+#[derive(Default)]
+struct RawGenerationData {
+    text: String,
+    stop_reason: Option<String>,
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+    token_logprobs: Option<Vec<TokenLogprob>>,
+    /// Collection stopped at the generation timeout
+    truncated: bool,
+    retry_stats: Option<RetryStats>,
+}
+
+impl RawGenerationData {
+    /// Add a streamed chunk, keeping the latest reported token counts and stop reason
+    fn push(&mut self, chunk: &GenerationChunk) {
+        self.record(chunk.stop_reason.as_deref(), chunk.input_token_count, chunk.generated_token_count);
+        self.text.push_str(&chunk.text);
+    }
+
+    /// Add a streamed chunk like `push`, dropping its text if it repeats the whole answer
+    ///
+    /// Returns whether the chunk's text was kept; see `is_duplicate_chunk`.
+    fn push_deduplicated(&mut self, chunk: &GenerationChunk, dedup_window: usize) -> bool {
+        self.record(chunk.stop_reason.as_deref(), chunk.input_token_count, chunk.generated_token_count);
+        if is_duplicate_chunk(&self.text, &chunk.text, dedup_window) {
+            return false;
+        }
+        self.text.push_str(&chunk.text);
+        true
+    }
+
+    /// Keep the metadata reported by a streamed chunk, ignoring fields it leaves out
+    fn record(&mut self, stop_reason: Option<&str>, input_tokens: Option<u32>, output_tokens: Option<u32>) {
+        if let Some(reason) = stop_reason.filter(|reason| StopReason::from_api(reason).is_some()) {
+            self.stop_reason = Some(reason.to_string());
+        }
+        self.input_tokens = input_tokens.or(self.input_tokens);
+        self.output_tokens = output_tokens.or(self.output_tokens);
+    }
+
+    /// Fail with `Error::EmptyGeneration` if the model produced no text
+    fn non_empty(self, model_id: &str, prompt_length: usize) -> Result<Self> {
+        if self.text.trim().is_empty() {
+            return Err(Error::EmptyGeneration {
+                model_id: model_id.to_string(),
+                prompt_length,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Convert into a `GenerationResult` with token usage populated
+    fn into_generation_result(self, model_id: String) -> GenerationResult {
+        let mut result = GenerationResult::new(self.text, model_id);
+        if self.input_tokens.is_some() || self.output_tokens.is_some() {
+            result = result.with_token_counts(self.input_tokens, self.output_tokens);
+        }
+        if let Some(logprobs) = self.token_logprobs {
+            result = result.with_token_logprobs(logprobs);
+        }
+        result.truncated = self.truncated;
+        result.retry_stats = self.retry_stats;
+        result.with_stop_reason(self.stop_reason.as_deref().and_then(StopReason::from_api))
+    }
 }
 
 #[derive(Deserialize)]
@@ -60,6 +272,108 @@ struct GenerationData {
     results: Vec<GenerationResults>,
 }
 
+/// Most texts the embeddings endpoint accepts in one request
+const EMBEDDING_BATCH_SIZE: usize = 10;
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    model_id: String,
+    #[serde(default)]
+    model_version: Option<String>,
+    results: Vec<EmbeddingData>,
+    #[serde(default)]
+    input_token_count: u32,
+    #[serde(default)]
+    created_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    input: Option<EmbeddingInput>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingInput {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct TokenizationResponse {
+    result: TokenizationResult,
+}
+
+#[derive(Deserialize)]
+struct RerankResponse {
+    model_id: String,
+    results: Vec<RerankData>,
+    #[serde(default)]
+    input_token_count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct RerankData {
+    index: usize,
+    score: f32,
+}
+
+impl RerankResponse {
+    /// Attach the document text to each result, sorted by descending relevance
+    fn into_rerank_result(self, documents: &[&str]) -> Result<RerankResult> {
+        let mut ranked = self
+            .results
+            .into_iter()
+            .map(|data| {
+                let text = documents.get(data.index).ok_or_else(|| {
+                    Error::Api(format!(
+                        "Rerank result index {} is out of range for {} documents",
+                        data.index,
+                        documents.len()
+                    ))
+                })?;
+                Ok(RankedDocument {
+                    index: data.index,
+                    text: text.to_string(),
+                    relevance_score: data.score,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ranked.sort_by(|a, b| b.relevance_score.total_cmp(&a.relevance_score));
+
+        Ok(RerankResult {
+            results: ranked,
+            model_id: self.model_id,
+            input_token_count: self.input_token_count,
+        })
+    }
+}
+
+impl EmbeddingResponse {
+    /// Append this batch to `result`, checking it has one embedding per input
+    fn append_to(self, result: &mut EmbeddingResult, batch_len: usize) -> Result<()> {
+        if self.results.len() != batch_len {
+            return Err(Error::Api(format!(
+                "Embeddings response has {} results for {} inputs",
+                self.results.len(),
+                batch_len
+            )));
+        }
+
+        for data in self.results {
+            if let Some(texts) = result.input_texts.as_mut() {
+                texts.push(data.input.map(|input| input.text).unwrap_or_default());
+            }
+            result.embeddings.push(data.embedding);
+        }
+        result.input_token_counts.push(self.input_token_count);
+        result.model_id = self.model_id;
+        result.model_version = self.model_version.or(result.model_version.take());
+        result.created_at = self.created_at.or(result.created_at.take());
+        Ok(())
+    }
+}
+
 #[derive(Deserialize)]
 struct ModelSpec {
     model_id: String,
@@ -70,6 +384,21 @@ struct ModelSpec {
     long_description: Option<String>,
     functions: Option<Vec<Function>>,
     lifecycle: Option<Vec<Lifecycle>>,
+    model_limits: Option<ModelLimits>,
+    versions: Option<Vec<ModelVersion>>,
+    min_shot_size: Option<u32>,
+    number_params: Option<String>,
+    training_parameters: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ModelLimits {
+    max_sequence_length: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ModelVersion {
+    version: String,
 }
 
 #[derive(Deserialize)]
@@ -86,6 +415,370 @@ struct Lifecycle {
 #[derive(Deserialize)]
 struct ModelsResponse {
     resources: Vec<ModelSpec>,
+    #[serde(default)]
+    total_count: Option<u32>,
+    #[serde(default)]
+    next: Option<PageLink>,
+}
+
+#[derive(Deserialize)]
+struct PageLink {
+    href: String,
+}
+
+impl ModelsResponse {
+    /// Convert the specs to `ModelInfo`, reading the next page cursor from the `next` link
+    fn into_page(self) -> ModelPage {
+        let models: Vec<crate::types::ModelInfo> = self.resources.into_iter().map(Into::into).collect();
+        ModelPage {
+            total_count: self.total_count.unwrap_or(models.len() as u32),
+            next_cursor: self.next.and_then(|link| page_cursor(&link.href)),
+            models,
+        }
+    }
+}
+
+/// Extract the `start` query parameter from a (possibly relative) page link
+fn page_cursor(href: &str) -> Option<String> {
+    let base = reqwest::Url::parse("https://localhost/").ok()?;
+    base.join(href)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == "start")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Error for a failed generation request, keeping the HTTP status for callers to match on
+///
+/// 429 responses become `Error::RateLimit`; every other status becomes `Error::HttpStatus`.
+fn generation_status_error(status: reqwest::StatusCode, error_text: &str, model_id: &str) -> Error {
+    let message = format!(
+        "WatsonX API request failed (HTTP {}): {}. Verify your model ID '{}' is correct and your project has access to it.",
+        status, error_text, model_id
+    );
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Error::RateLimit(message)
+    } else {
+        Error::HttpStatus {
+            status: status.as_u16(),
+            message,
+        }
+    }
+}
+
+/// Percent-encode `segment` for use as a single URL path segment, including any `/`
+pub(crate) fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+impl From<ModelSpec> for crate::types::ModelInfo {
+    fn from(spec: ModelSpec) -> Self {
+        let supported_tasks = spec.functions
+            .map(|functions| functions.into_iter().map(|f| f.id).collect());
+
+        let available = spec.lifecycle
+            .and_then(|lifecycle| {
+                lifecycle.iter()
+                    .find(|l| l.id == "available")
+                    .map(|_| true)
+            });
+
+        Self {
+            model_id: spec.model_id,
+            name: spec.label,
+            description: spec.long_description.or(spec.short_description),
+            provider: spec.provider,
+            // The latest version is listed last
+            version: spec.versions.and_then(|versions| versions.into_iter().last().map(|v| v.version)),
+            supported_tasks,
+            max_context_length: spec.model_limits.and_then(|limits| limits.max_sequence_length),
+            available,
+            min_shot_size: spec.min_shot_size,
+            number_params: spec.number_params,
+            training_parameters: spec.training_parameters,
+        }
+    }
+}
+
+/// Send each generation chunk to `sender`, returning the accumulated result
+async fn forward_generation_chunks(
+    chunks: ByteChunks,
+    model_id: String,
+    prompt_length: usize,
+    max_line_bytes: usize,
+    request_id: String,
+    sender: mpsc::Sender<String>,
+) -> Result<GenerationResult> {
+    let mut stream = WatsonxStream::<GenerationChunk>::from_chunks(chunks).with_max_line_bytes(max_line_bytes);
+    let mut output = RawGenerationData::default();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        output.push(&chunk);
+        // A closed receiver only means nobody is listening; keep building the result
+        let _ = sender.send(chunk.text).await;
+    }
+
+    Ok(output
+        .non_empty(&model_id, prompt_length)?
+        .into_generation_result(model_id)
+        .with_request_id(request_id))
+}
+
+/// Map decoded chunks to their text, skipping chunks without text
+fn text_deltas<T>(
+    chunks: WatsonxStream<T>,
+    text: fn(T) -> String,
+) -> impl Stream<Item = Result<String>> + Send + Unpin
+where
+    WatsonxStream<T>: Stream<Item = Result<T>> + Send,
+{
+    chunks.filter_map(move |chunk| {
+        futures::future::ready(match chunk.map(text) {
+            Ok(text) if text.is_empty() => None,
+            chunk => Some(chunk),
+        })
+    })
+}
+
+/// Collect a generation stream into cleaned-up text, stopping at `deadline`
+///
+/// If `deadline` passes mid-stream, `config.on_timeout` decides whether the text received
+/// so far is returned (marked truncated) or discarded with `Error::Timeout`.
+async fn collect_generation_stream(
+    mut stream: ByteChunks,
+    config: &GenerationConfig,
+    max_line_bytes: usize,
+    prompt_length: usize,
+    deadline: tokio::time::Instant,
+) -> Result<RawGenerationData> {
+    let timed_out = || Error::Timeout("Request timed out".to_string());
+
+    let mut output = RawGenerationData::default();
+    let window = config.stream_dedup_window;
+    let mut buffer = String::new();
+
+    // Process stream chunks in real-time
+    loop {
+        let chunk = match tokio::time::timeout_at(deadline, stream.next()).await {
+            Ok(Some(chunk_result)) => chunk_result?,
+            Ok(None) => break,
+            Err(_) if config.on_timeout == TimeoutBehavior::ReturnPartial && !output.text.trim().is_empty() => {
+                output.truncated = true;
+                break;
+            }
+            Err(_) => return Err(timed_out()),
+        };
+        let text = String::from_utf8_lossy(&chunk);
+        buffer.push_str(&text);
+
+        // Process complete lines from buffer
+        while let Some(newline_pos) = buffer.find('\n') {
+            if newline_pos + 1 > max_line_bytes {
+                return Err(crate::sse::line_too_large(max_line_bytes));
+            }
+            let line = buffer[..newline_pos].to_string();
+            buffer = buffer[newline_pos + 1..].to_string();
+            append_generation_line(&mut output, &line, window);
+        }
+
+        if buffer.len() > max_line_bytes {
+            return Err(crate::sse::line_too_large(max_line_bytes));
+        }
+    }
+
+    // Process any remaining data in buffer
+    if !buffer.is_empty() {
+        append_generation_line(&mut output, &buffer, window);
+    }
+
+    let mut output = output.non_empty(&config.model_id, prompt_length)?;
+
+    // Clean up the response
+    let mut cleaned_answer = output.text.trim().to_string();
+
+    if cleaned_answer.starts_with("Answer:") {
+        cleaned_answer = cleaned_answer
+            .strip_prefix("Answer:")
+            .unwrap_or(&cleaned_answer)
+            .trim()
+            .to_string();
+    }
+
+    if let Some(query_pos) = cleaned_answer.find("Query:") {
+        cleaned_answer = cleaned_answer[..query_pos].trim().to_string();
+    }
+
+    output.text = cleaned_answer
+        .lines()
+        .next()
+        .unwrap_or(&cleaned_answer)
+        .trim()
+        .to_string();
+
+    Ok(output)
+}
+
+/// Build a prompt asking for JSON that follows `schema`
+fn json_prompt(prompt: &str, schema: &serde_json::Value) -> String {
+    let schema = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+    format!(
+        "{}\n\nRespond only with JSON that conforms to this JSON Schema, without any explanation:\n{}\n\nExample of the expected shape: a single JSON value, e.g. {{\"field\": \"value\"}} for an object schema.\n\nJSON:\n",
+        prompt.trim(),
+        schema
+    )
+}
+
+/// Extract the JSON value from model output, skipping code fences and surrounding text
+fn extract_json(output: &str) -> &str {
+    let start = output.find(['{', '[']);
+    let end = output.rfind(['}', ']']);
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => &output[start..=end],
+        _ => output.trim(),
+    }
+}
+
+/// Parse model output as `T`, checking the schema's top-level required fields
+fn parse_json_output<T: DeserializeOwned>(output: &str, schema: &serde_json::Value) -> std::result::Result<T, String> {
+    let value: serde_json::Value = serde_json::from_str(extract_json(output)).map_err(|e| e.to_string())?;
+
+    if let (Some(required), Some(object)) = (
+        schema.get("required").and_then(|r| r.as_array()),
+        value.as_object(),
+    ) {
+        let missing: Vec<&str> = required
+            .iter()
+            .filter_map(|field| field.as_str())
+            .filter(|field| !object.contains_key(*field))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("missing required fields: {}", missing.join(", ")));
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Build a few-shot prompt asking for code in `language` that implements `spec`
+fn code_prompt(spec: &str, language: &ProgrammingLanguage, config: &CodeGenConfig) -> String {
+    let name = language.name();
+    let tag = language.fence_tags().remove(0);
+    let mut requirements = vec![if config.include_comments {
+        "Add concise comments explaining non-obvious logic.".to_string()
+    } else {
+        "Do not add comments.".to_string()
+    }];
+    if config.include_tests {
+        requirements.push("Include unit tests for the code.".to_string());
+    }
+    if let Some(max_functions) = config.max_functions {
+        requirements.push(format!("Write at most {} functions.", max_functions.max(1)));
+    }
+
+    let mut prompt = format!(
+        "You are an expert {} programmer. Write {} code that implements the specification.\n{}\nRespond with a single ```{} code block and no explanation.\n\n",
+        name,
+        name,
+        requirements.join("\n"),
+        tag
+    );
+    if let Some(example) = language.example() {
+        prompt.push_str(&format!(
+            "Specification: Add two integers.\n```{}\n{}\n```\n\n",
+            tag, example
+        ));
+    }
+    prompt.push_str(&format!("Specification: {}\n```{}\n", spec.trim(), tag));
+    prompt
+}
+
+/// Split the code block out of model output, returning its info string and code
+///
+/// The prompt opens the fence, so text before the first fence is the body of that block and
+/// anything after its closing fence (usage notes, further blocks) is dropped. Only output that
+/// starts with a fence is treated as opening a new block; an unterminated one runs to the end.
+fn extract_code_block(output: &str) -> (Option<&str>, &str) {
+    let output = output.trim();
+    let Some(after) = output.strip_prefix("```") else {
+        let code = output.find("```").map_or(output, |close| &output[..close]);
+        return (None, code.trim());
+    };
+
+    let (tag, body) = after.split_once('\n').unwrap_or((after, ""));
+    let code = body.find("```").map_or(body, |close| &body[..close]);
+    let tag = tag.trim();
+    (Some(tag).filter(|tag| !tag.is_empty()), code.trim())
+}
+
+/// Confidence that `code` is written in `language`, from its fence tag or typical tokens
+fn code_language_confidence(tag: Option<&str>, code: &str, language: &ProgrammingLanguage) -> f32 {
+    if let Some(tag) = tag {
+        let tag = tag.to_lowercase();
+        return if language.fence_tags().contains(&tag) { 1.0 } else { 0.2 };
+    }
+
+    let markers = language.markers();
+    if markers.is_empty() {
+        return 0.5;
+    }
+    let code = code.to_lowercase();
+    let hits = markers
+        .iter()
+        .filter(|marker| code.contains(&marker.to_lowercase()))
+        .count();
+    (hits as f32 / 3.0).min(1.0) * 0.9
+}
+
+/// Chunks shorter than this are never treated as a resent answer, since a short
+/// opening token ("ha", "!") can legitimately repeat
+const DEDUP_MIN_CHUNK_CHARS: usize = 4;
+
+/// Append the generated text carried by one SSE line to `output`, skipping duplicates
+///
+/// Token counts and the stop reason are recorded when the line reports them.
+fn append_generation_line(output: &mut RawGenerationData, line: &str, dedup_window: usize) {
+    let trimmed = line.trim();
+    let Some(json_data) = trimmed.strip_prefix("data:").map(str::trim) else {
+        return;
+    };
+    if json_data.is_empty() || json_data == "[DONE]" {
+        return;
+    }
+
+    match serde_json::from_str::<GenerationData>(json_data) {
+        Ok(data) => {
+            let Some(result) = data.results.first() else {
+                return;
+            };
+            if !is_duplicate_chunk(&output.text, &result.generated_text, dedup_window) {
+                output.text.push_str(&result.generated_text);
+            }
+            output.record(
+                result.stop_reason.as_deref(),
+                result.input_token_count,
+                result.generated_token_count,
+            );
+        }
+        Err(e) => {
+            eprintln!("Warning: Failed to parse SSE data: {}", e);
+        }
+    }
+}
+
+/// Check whether a streamed chunk resends the whole answer so far
+///
+/// Some streams repeat the full generated text in their final chunk. Only that exact
+/// pattern is dropped, since any other repeated text may be legitimate output.
+/// A window of 0 disables deduplication.
+fn is_duplicate_chunk(answer: &str, chunk: &str, window: usize) -> bool {
+    window != 0 && chunk.chars().count() >= DEDUP_MIN_CHUNK_CHARS && chunk == answer
 }
 
 impl WatsonxClient {
@@ -93,20 +786,42 @@ impl WatsonxClient {
     pub fn new(config: WatsonxConfig) -> Result<Self> {
         config.validate()?;
         
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_secs))
-            .danger_accept_invalid_certs(true)
-            .build()
-            .map_err(|e| Error::Network(format!(
+        // Generation requests use the client-wide timeout; token requests override it
+        let mut builder = Client::builder()
+            .connect_timeout(config.connect_timeout())
+            .timeout(config.generate_timeout());
+
+        builder = match &config.pinned_certificate {
+            Some(cert_der) => {
+                let certificate = reqwest::Certificate::from_der(cert_der).map_err(|e| {
+                    Error::Configuration(format!("Invalid pinned certificate: {}", e))
+                })?;
+                builder
+                    .add_root_certificate(certificate)
+                    .tls_built_in_root_certs(!config.pinned_certificate_only)
+            }
+            None => builder.danger_accept_invalid_certs(true),
+        };
+
+        let client = builder.build().map_err(|e| match config.pinned_certificate {
+            Some(_) => Error::Configuration(format!("Invalid pinned certificate: {}", e)),
+            None => Error::Network(format!(
                 "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
                 e
-            )))?;
+            )),
+        })?;
 
         Ok(Self {
             config,
-            access_token: None,
+            access_token: Arc::new(RwLock::new(None)),
             client,
             current_model: DEFAULT_MODEL.to_string(),
+            token_provider: None,
+            successful_endpoint: Arc::new(RwLock::new(None)),
+            rate_limit: Arc::new(RwLock::new(None)),
+            token_expiry: Arc::new(RwLock::new(None)),
+            token_refresh: Arc::new(Mutex::new(())),
+            retry_config: None,
         })
     }
 
@@ -128,10 +843,131 @@ impl WatsonxClient {
     }
 
     /// Connect to WatsonX and authenticate
-    pub async fn connect(&mut self) -> Result<()> {
+    ///
+    /// Takes `&self`, so a client shared through `Arc` (or cloned) can reconnect; clones
+    /// see the new token. IAM tokens are refreshed automatically shortly before they expire.
+    pub async fn connect(&self) -> Result<()> {
+        let issued = self.obtain_access_token().await?;
+        self.store_token(issued);
+        Ok(())
+    }
+
+    /// Current access token from `connect()`, if any
+    fn get_token(&self) -> Option<String> {
+        self.access_token.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Store the access token used for subsequent requests
+    fn set_token(&self, token: String) {
+        *self.access_token.write().unwrap_or_else(|e| e.into_inner()) = Some(token);
+    }
+
+    /// Store a freshly issued token together with its expiry
+    fn store_token(&self, issued: IssuedToken) {
+        self.set_token(issued.token);
+        *self.token_expiry.write().unwrap_or_else(|e| e.into_inner()) = issued.expires_at;
+    }
+
+    /// Current token, unless it is missing or within `TOKEN_REFRESH_MARGIN` of expiring
+    fn valid_token(&self) -> Option<String> {
+        let expiry = *self.token_expiry.read().unwrap_or_else(|e| e.into_inner());
+        let expiring = expiry.is_some_and(|expiry| expiry.saturating_duration_since(Instant::now()) < TOKEN_REFRESH_MARGIN);
+        self.get_token().filter(|_| !expiring)
+    }
+
+    /// Exchange the configured credentials for an access token
+    ///
+    /// Tokens from a custom `TokenProvider` carry no expiry and are not refreshed.
+    async fn obtain_access_token(&self) -> Result<IssuedToken> {
+        match &self.token_provider {
+            Some(provider) => {
+                let token = provider
+                    .fetch_token(&self.client, &self.config.iam_url, self.config.connect_timeout())
+                    .await?;
+                Ok(IssuedToken { token, expires_at: None })
+            }
+            None => self.fetch_access_token(&self.config.api_key).await,
+        }
+    }
+
+    /// Access token for API requests, refreshed if it is about to expire
+    ///
+    /// Uses the token from `connect()`, requesting a new one when fewer than
+    /// `TOKEN_REFRESH_MARGIN` remain. Without a token, connects on first use when
+    /// `WatsonxConfig::lazy_connect` is set. Concurrent callers share a single token request.
+    async fn ensure_token_valid(&self) -> Result<String> {
+        if let Some(token) = self.valid_token() {
+            return Ok(token);
+        }
+        if self.get_token().is_none() && !self.config.lazy_connect {
+            return Err(Error::Authentication(
+                "Not authenticated. Call connect() first.".to_string(),
+            ));
+        }
+
+        let _refresh = self.token_refresh.lock().await;
+        // Another caller may have refreshed the token while we waited
+        if let Some(token) = self.valid_token() {
+            return Ok(token);
+        }
+        let issued = self.obtain_access_token().await?;
+        let token = issued.token.clone();
+        self.store_token(issued);
+        Ok(token)
+    }
+
+    /// Authenticate by exchanging an OIDC token from an external identity provider
+    ///
+    /// `connect()` then obtains the IAM token through `provider` instead of the API key.
+    pub fn with_oidc_provider(self, provider: TokenExchangeProvider) -> Self {
+        self.with_token_provider(provider)
+    }
+
+    /// Retry failed text generation requests with exponential backoff
+    ///
+    /// Only errors for which `Error::is_retryable()` is true are retried. The attempts made
+    /// are reported in `GenerationResult::retry_stats`.
+    pub fn with_retry(mut self, config: RetryConfig) -> Self {
+        self.retry_config = Some(config);
+        self
+    }
+
+    /// Authenticate with a custom token provider instead of the API key
+    pub fn with_token_provider(mut self, provider: impl TokenProvider + 'static) -> Self {
+        self.token_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Replace the API key and re-authenticate without creating a new client
+    ///
+    /// The new key is only stored once it has been exchanged for an access token. If
+    /// authentication with the new key fails, the previous key and access token stay in
+    /// use and `Error::Authentication` is returned.
+    pub async fn rotate_credentials(&mut self, new_api_key: String) -> Result<()> {
+        if new_api_key.trim().is_empty() {
+            return Err(Error::Authentication("New API key cannot be empty".to_string()));
+        }
+
+        let issued = self
+            .fetch_access_token(&new_api_key)
+            .await
+            .map_err(|e| match e {
+                Error::Authentication(_) => e,
+                other => Error::Authentication(format!(
+                    "Failed to authenticate with the new API key: {}",
+                    other
+                )),
+            })?;
+        self.config.api_key = new_api_key;
+        self.store_token(issued);
+        Ok(())
+    }
+
+    /// Exchange an API key for an IAM access token
+    async fn fetch_access_token(&self, api_key: &str) -> Result<IssuedToken> {
         let token_request = TokenRequest {
             grant_type: "urn:ibm:params:oauth:grant-type:apikey".to_string(),
-            apikey: self.config.api_key.clone(),
+            apikey: api_key.to_string(),
         };
 
         let url = format!("https://{}/identity/token", self.config.iam_url);
@@ -139,6 +975,7 @@ impl WatsonxClient {
         let response = self
             .client
             .post(&url)
+            .timeout(self.config.connect_timeout())
             .header("Content-Type", "application/x-www-form-urlencoded")
             .form(&token_request)
             .send()
@@ -168,8 +1005,12 @@ impl WatsonxClient {
                 e
             )))?;
 
-        self.access_token = Some(token_response.access_token);
-        Ok(())
+        Ok(IssuedToken {
+            token: token_response.access_token,
+            expires_at: token_response
+                .expires_in
+                .map(|expires_in| Instant::now() + Duration::from_secs(expires_in)),
+        })
     }
 
     /// Generate text using the current model
@@ -181,6 +1022,18 @@ impl WatsonxClient {
         self.generate_with_config(prompt, &config).await
     }
 
+    /// Generate text for `query` after the few-shot examples in `context`
+    ///
+    /// The prompt is rendered with `GenerationContext::render` and sent with `generate_with_config`.
+    pub async fn generate_with_context(
+        &self,
+        query: &str,
+        context: &GenerationContext,
+        config: &GenerationConfig,
+    ) -> Result<GenerationResult> {
+        self.generate_with_config(&context.render(query), config).await
+    }
+
     /// Generate text with custom configuration
     pub async fn generate_with_config(
         &self,
@@ -190,14 +1043,12 @@ impl WatsonxClient {
         let _start_time = Instant::now();
         let request_id = Uuid::new_v4().to_string();
 
-        let generation_future = self.perform_text_stream_generation(prompt, config, &request_id);
-
-        let text = match timeout(config.timeout, generation_future).await {
-            Ok(result) => result?,
-            Err(_) => return Err(Error::Timeout("Request timed out".to_string())),
-        };
+        let data = self
+            .perform_text_stream_generation(prompt, config, self.retry_config.as_ref())
+            .await?;
 
-        Ok(GenerationResult::new(text, config.model_id.clone())
+        Ok(data
+            .into_generation_result(config.model_id.clone())
             .with_request_id(request_id))
     }
 
@@ -210,184 +1061,707 @@ impl WatsonxClient {
         let _start_time = Instant::now();
         let request_id = Uuid::new_v4().to_string();
 
-        let generation_future = self.perform_text_generation(prompt, config, &request_id);
-
-        let text = match timeout(config.timeout, generation_future).await {
-            Ok(result) => result?,
-            Err(_) => return Err(Error::Timeout("Request timed out".to_string())),
-        };
+        let data = self.perform_text_generation(prompt, config, &request_id).await?;
 
-        Ok(GenerationResult::new(text, config.model_id.clone())
+        Ok(data
+            .into_generation_result(config.model_id.clone())
             .with_request_id(request_id))
     }
 
-    /// Generate text with streaming callback for real-time output
-    pub async fn generate_text_stream<F>(
+    /// Generate a value of type `T` as JSON following `schema`
+    ///
+    /// Describes the schema in the prompt, parses the model output as JSON and checks the
+    /// schema's top-level `required` fields. Invalid output is sent back to the model with
+    /// the parse error, up to 3 times. See `generate_json_with_config` to change that.
+    pub async fn generate_json<T: DeserializeOwned>(
         &self,
         prompt: &str,
+        schema: &serde_json::Value,
         config: &GenerationConfig,
-        callback: F,
-    ) -> Result<GenerationResult>
-    where
-        F: Fn(&str) + Send + Sync,
-    {
-        let request_id = Uuid::new_v4().to_string();
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication(
-                "Not authenticated. Call connect() first to obtain an access token.".to_string(),
-            )
-        })?;
+    ) -> Result<T> {
+        let json_config = JsonGenerationConfig::new(schema.clone());
+        self.generate_json_with_config(prompt, &json_config, config).await
+    }
 
-        let params = GenerationParams {
-            decoding_method: "greedy".to_string(),
-            max_new_tokens: config.max_tokens,
-            min_new_tokens: 1,
-            top_k: config.top_k.unwrap_or(50),
-            top_p: config.top_p.unwrap_or(1.0),
-            repetition_penalty: config.repetition_penalty.unwrap_or(1.1),
-            stop_sequences: config.stop_sequences.clone(),
-        };
+    /// Generate a value of type `T` as JSON, with explicit schema and repair settings
+    pub async fn generate_json_with_config<T: DeserializeOwned>(
+        &self,
+        prompt: &str,
+        json_config: &JsonGenerationConfig,
+        config: &GenerationConfig,
+    ) -> Result<T> {
+        let instruction = json_prompt(prompt, &json_config.schema);
+        let mut request = instruction.clone();
+
+        for attempt in 0..=json_config.repair_attempts {
+            let output = self.generate_text(&request, config).await?.text;
+            let error = match parse_json_output(&output, &json_config.schema) {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            if attempt == json_config.repair_attempts {
+                return Err(Error::Serialization(format!(
+                    "Failed to generate valid JSON after {} attempts: {}",
+                    attempt + 1,
+                    error
+                )));
+            }
+            request = format!(
+                "{}\n\nYour previous response was:\n{}\n\nIt is not valid: {}\nRespond with the corrected JSON only.\n",
+                instruction,
+                output.trim(),
+                error
+            );
+        }
 
-        let request_body = GenerationRequest {
-            input: prompt.to_string(),
-            parameters: params,
-            model_id: config.model_id.clone(),
-            project_id: self.config.project_id.clone(),
-        };
+        unreachable!("the last attempt always returns")
+    }
 
-        let url = format!(
-            "{}/ml/v1/text/generation_stream?version={}",
-            self.config.api_url, self.config.api_version
+    /// Generate text and return the full, unprocessed JSON response body
+    ///
+    /// Useful for debugging and for inspecting fields the SDK does not surface yet.
+    pub async fn generate_raw(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+    ) -> Result<serde_json::Value> {
+        let access_token = self.ensure_token_valid().await?;
+
+        let generation_future = Self::perform_text_generation_raw(
+            &self.client,
+            &access_token,
+            &self.config.project_id,
+            &self.config.api_url,
+            &self.config.api_version,
+            prompt,
+            config,
         );
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", access_token))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| Error::Network(format!(
-                "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
-                e
-            )))?;
+        match timeout(config.timeout, generation_future).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout("Request timed out".to_string())),
+        }
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(Error::Api(format!(
-                "WatsonX API request failed (HTTP {}): {}. Verify your model ID '{}' is correct and your project has access to it.",
-                status, error_text, config.model_id
-            )));
+    /// Generate text with automatic failover to alternative models
+    ///
+    /// This is the high-availability pattern: the primary configuration is tried first,
+    /// and if its model is unavailable or rate-limited (`Error::RateLimit`,
+    /// `Error::ModelNotFound`, or an `Error::HttpStatus` for HTTP 503) each fallback
+    /// configuration is tried in sequence. Any other error is returned immediately.
+    ///
+    /// The returned `GenerationResult::model_id` identifies which model produced the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, GenerationConfig, models::models};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let primary = GenerationConfig::default().with_model(models::GRANITE_4_H_SMALL);
+    /// let fallbacks = [GenerationConfig::default().with_model(models::GRANITE_3_3_8B_INSTRUCT)];
+    ///
+    /// let result = client.generate_with_fallback("Hello", &primary, &fallbacks).await?;
+    /// println!("[{}] {}", result.model_id, result.text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_with_fallback(
+        &self,
+        prompt: &str,
+        primary_config: &GenerationConfig,
+        fallback_configs: &[GenerationConfig],
+    ) -> Result<GenerationResult> {
+        let mut last_error = match self.generate_with_config(prompt, primary_config).await {
+            Ok(result) => return Ok(result),
+            Err(e) => e,
+        };
+
+        for config in fallback_configs {
+            if !Self::should_fall_back(&last_error) {
+                break;
+            }
+            match self.generate_with_config(prompt, config).await {
+                Ok(result) => return Ok(result),
+                Err(e) => last_error = e,
+            }
         }
 
-        let mut answer = String::new();
-        
-        // Use bytes_stream for true streaming - process chunks as they arrive
-        let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
-
-        // Process stream chunks in real-time
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| Error::Network(e.to_string()))?;
-            let text = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&text);
-
-            // Process complete lines from buffer
-            while let Some(newline_pos) = buffer.find('\n') {
-                let line = buffer[..newline_pos].to_string();
-                buffer = buffer[newline_pos + 1..].to_string();
-
-                let trimmed = line.trim();
-                if trimmed.is_empty() || trimmed.starts_with("id:") || trimmed.starts_with("event:") {
-                    continue;
-                }
+        Err(last_error)
+    }
 
-                if trimmed.starts_with("data:") {
-                    let json_data = if trimmed.starts_with("data: ") {
-                        &trimmed[6..]
-                    } else {
-                        &trimmed[5..]
-                    };
+    /// Generate text, retrying retryable failures up to `retry_config.max_attempts` times
+    ///
+    /// Between attempts the client backs off exponentially from `retry_config.retry_delay`, or
+    /// waits until the rate limit window resets when the last response reported no requests
+    /// remaining. `retry_config` replaces any policy set with `with_retry` for this call.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use watsonx_rs::{GenerationConfig, RetryConfig, WatsonxClient, WatsonxConfig};
+    /// # async fn example() -> watsonx_rs::Result<()> {
+    /// # let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// let retry = RetryConfig::new(5);
+    /// let result = client
+    ///     .generate_with_retry("Hello", &GenerationConfig::default(), &retry)
+    ///     .await?;
+    /// println!("{}", result.text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_with_retry(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        retry_config: &RetryConfig,
+    ) -> Result<GenerationResult> {
+        let request_id = Uuid::new_v4().to_string();
+        let data = self
+            .perform_text_stream_generation(prompt, config, Some(retry_config))
+            .await?;
 
-                    if json_data.trim().is_empty() || json_data.trim() == "[DONE]" {
-                        continue;
-                    }
+        Ok(data
+            .into_generation_result(config.model_id.clone())
+            .with_request_id(request_id))
+    }
 
-                    match serde_json::from_str::<GenerationData>(json_data) {
-                        Ok(data) => {
-                            if let Some(result) = data.results.first() {
-                                let generated_text = &result.generated_text;
-                                answer.push_str(generated_text);
-                                // Call the callback immediately with the new chunk
-                                callback(generated_text);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: Failed to parse SSE data: {}", e);
-                        }
-                    }
-                }
+    /// Generate text several times and keep the highest quality response
+    ///
+    /// Calls `generate_text` up to `retry_config.max_attempts` times, scoring each response
+    /// with `assess_quality`, and stops as soon as one reaches `retry_config.quality_threshold`.
+    /// If none does, the best scoring response is returned with `threshold_met` unset. Errors
+    /// are returned immediately; use `with_retry` to retry failed requests.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use watsonx_rs::{GenerationConfig, RetryConfig, WatsonxClient, WatsonxConfig};
+    /// # async fn example() -> watsonx_rs::Result<()> {
+    /// # let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// let retry = RetryConfig::new(3).with_quality_threshold(0.8);
+    /// let best = client
+    ///     .generate_best_of("Explain ownership in Rust", &GenerationConfig::default(), &retry)
+    ///     .await?;
+    /// println!("{} (score {:.2}, {} attempts)", best.result.text, best.quality_score, best.all_attempts.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_best_of(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        retry_config: &RetryConfig,
+    ) -> Result<BestGenerationResult> {
+        let mut all_attempts = Vec::new();
+        let mut best: Option<(GenerationResult, f32)> = None;
+
+        for attempt_number in 1..=retry_config.max_attempts.max(1) {
+            let start = Instant::now();
+            let result = self.generate_text(prompt, config).await?;
+            let score = self.assess_quality(&result.text, prompt);
+            all_attempts.push(
+                GenerationAttempt::new(prompt.to_string(), result.text.clone(), attempt_number)
+                    .with_quality_score(score)
+                    .with_duration(start.elapsed()),
+            );
+
+            if best.as_ref().is_none_or(|(_, best_score)| score > *best_score) {
+                best = Some((result, score));
+            }
+            if score >= retry_config.quality_threshold {
+                break;
             }
         }
 
-        // Process any remaining data in buffer
-        if !buffer.is_empty() {
-            let trimmed = buffer.trim();
-            if trimmed.starts_with("data:") {
-                let json_data = if trimmed.starts_with("data: ") {
-                    &trimmed[6..]
-                } else {
-                    &trimmed[5..]
-                };
+        let (result, quality_score) =
+            best.ok_or_else(|| Error::InvalidInput("max_attempts must be at least 1".to_string()))?;
+        Ok(BestGenerationResult {
+            result,
+            quality_score,
+            threshold_met: quality_score >= retry_config.quality_threshold,
+            all_attempts,
+        })
+    }
 
-                if !json_data.trim().is_empty() && json_data.trim() != "[DONE]" {
-                    if let Ok(data) = serde_json::from_str::<GenerationData>(json_data) {
-                        if let Some(result) = data.results.first() {
-                            let generated_text = &result.generated_text;
-                            answer.push_str(generated_text);
-                            callback(generated_text);
-                        }
-                    }
-                }
+    /// Generate code in `language` that implements `spec`
+    ///
+    /// The prompt shows the model a short example in the same language, and the code block
+    /// is extracted from its answer with markdown fences removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{CodeGenConfig, ProgrammingLanguage, WatsonxClient, WatsonxConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// let config = CodeGenConfig::new().with_tests(true).with_max_functions(2);
+    /// let generated = client
+    ///     .generate_code("Parse a semantic version string", ProgrammingLanguage::Rust, &config)
+    ///     .await?;
+    /// println!("{}", generated.code);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_code(
+        &self,
+        spec: &str,
+        language: ProgrammingLanguage,
+        config: &CodeGenConfig,
+    ) -> Result<CodeGenResult> {
+        let max_tokens = if config.include_tests { 2048 } else { 1024 };
+        let generation_config = GenerationConfig::default()
+            .with_decoding_method(DecodingMethod::Greedy)
+            .with_max_tokens(max_tokens);
+        let result = self
+            .generate_text(&code_prompt(spec, &language, config), &generation_config)
+            .await?;
+
+        let (tag, code) = extract_code_block(&result.text);
+        Ok(CodeGenResult {
+            generated: result.text.clone(),
+            code: code.to_string(),
+            language_confidence: code_language_confidence(tag, code, &language),
+            result,
+        })
+    }
+
+    /// Run `operation`, retrying it as `retry_config` allows
+    ///
+    /// Without a retry config the operation runs once and no stats are reported. Otherwise
+    /// only retryable errors are retried, waiting `RetryConfig::backoff_delay` between
+    /// attempts, or until the rate limit window resets when it is exhausted.
+    async fn execute_with_retry<F, Fut, T>(
+        &self,
+        retry_config: Option<&RetryConfig>,
+        mut operation: F,
+    ) -> Result<(T, Option<RetryStats>)>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let Some(retry_config) = retry_config else {
+            return operation().await.map(|value| (value, None));
+        };
+
+        let mut stats = RetryStats::default();
+        loop {
+            stats.attempts += 1;
+            let error = match operation().await {
+                Ok(value) => return Ok((value, Some(stats))),
+                Err(e) => e,
+            };
+            if stats.attempts >= retry_config.max_attempts || !error.is_retryable() {
+                return Err(error);
             }
+
+            let delay = match self.rate_limit_state() {
+                Some(state) if state.is_exhausted() => state.time_until_reset(),
+                _ => retry_config.backoff_delay(stats.attempts),
+            };
+            stats.total_wait += delay;
+            tokio::time::sleep(delay).await;
         }
+    }
 
-        if answer.trim().is_empty() {
-            return Err(Error::Api(
-                "Received empty response from WatsonX API. The model may have generated no output, or the response format was unexpected. Try adjusting your prompt or parameters.".to_string(),
-            ));
+    /// Check whether an error indicates the model is unavailable and a fallback should be tried
+    fn should_fall_back(error: &Error) -> bool {
+        match error {
+            Error::RateLimit(_) | Error::ModelNotFound(_) => true,
+            Error::HttpStatus { status, .. } => *status == 503,
+            _ => false,
         }
+    }
 
-        Ok(GenerationResult::new(answer, config.model_id.clone())
+    /// Generate text with streaming callback for real-time output
+    pub async fn generate_text_stream<F>(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        callback: F,
+    ) -> Result<GenerationResult>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        let request_id = Uuid::new_v4().to_string();
+        let chunks = self.send_text_stream_request(prompt, config, 1).await?;
+
+        // Read the response through a bounded buffer so a slow callback pauses reading
+        let limits = self.config.sse_buffer.clone().with_max_line_bytes(self.sse_line_limit(config));
+        let output = crate::sse::parse_bounded_sse_chunks(
+            chunks,
+            config.stream_buffer_size,
+            Some(|chunk: &str| callback(chunk)),
+            &limits,
+        )
+        .await?;
+
+        if output.text.trim().is_empty() {
+            return Err(Error::EmptyGeneration {
+                model_id: config.model_id.clone(),
+                prompt_length: prompt.len(),
+            });
+        }
+
+        Ok(GenerationResult::new(output.text, config.model_id.clone())
+            .with_request_id(request_id)
+            .with_stop_reason(output.stop_reason))
+    }
+
+    /// Generate text, writing and flushing each chunk to `writer` as it arrives
+    ///
+    /// The common case is streaming to a terminal with `std::io::stdout()`, where
+    /// flushing per chunk keeps output from sitting in the line buffer. A write error
+    /// stops generation and is returned as `Error::Io`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, GenerationConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let result = client
+    ///     .generate_text_stream_flushed("Write a haiku", &GenerationConfig::default(), std::io::stdout())
+    ///     .await?;
+    /// println!("\n{:?}", result.stop_reason);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_text_stream_flushed<W: Write + Send>(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        mut writer: W,
+    ) -> Result<GenerationResult> {
+        let request_id = Uuid::new_v4().to_string();
+        let chunks = self.send_text_stream_request(prompt, config, 1).await?;
+        let mut stream =
+            WatsonxStream::<GenerationChunk>::from_chunks(chunks).with_max_line_bytes(self.sse_line_limit(config));
+        let mut output = RawGenerationData::default();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if !output.push_deduplicated(&chunk, config.stream_dedup_window) {
+                continue;
+            }
+            writer
+                .write_all(chunk.text.as_bytes())
+                .and_then(|()| writer.flush())
+                .map_err(|e| Error::Io(format!("Failed to write generated text: {}", e)))?;
+        }
+
+        Ok(output
+            .non_empty(&config.model_id, prompt.len())?
+            .into_generation_result(config.model_id.clone())
             .with_request_id(request_id))
     }
 
-    /// Perform text generation request using streaming endpoint
-    async fn perform_text_stream_generation(
+    /// Generate text with a streaming callback, stopping early if `cancel` fires
+    ///
+    /// Behaves like `generate_text_stream` until the token is cancelled; then the in-flight
+    /// response is dropped, closing the connection, and `Error::Cancelled` is returned.
+    /// Chunks already passed to `callback` are not retracted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{CancellationToken, WatsonxClient, WatsonxConfig, GenerationConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let cancel = CancellationToken::new();
+    /// let deadline = cancel.clone();
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+    ///     deadline.cancel();
+    /// });
+    ///
+    /// let result = client
+    ///     .generate_text_stream_cancellable(
+    ///         "Tell me a long story",
+    ///         &GenerationConfig::default(),
+    ///         |chunk| print!("{}", chunk),
+    ///         cancel,
+    ///     )
+    ///     .await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_text_stream_cancellable<F>(
         &self,
         prompt: &str,
         config: &GenerationConfig,
-        _request_id: &str,
-    ) -> Result<String> {
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Call connect() first.".to_string())
-        })?;
+        callback: F,
+        cancel: CancellationToken,
+    ) -> Result<GenerationResult>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(Error::Cancelled(
+                "Text generation stream was cancelled".to_string(),
+            )),
+            result = self.generate_text_stream(prompt, config, callback) => result,
+        }
+    }
 
-        let params = GenerationParams {
-            decoding_method: "greedy".to_string(),
-            max_new_tokens: config.max_tokens,
-            min_new_tokens: 5,
-            top_k: config.top_k.unwrap_or(50),
-            top_p: config.top_p.unwrap_or(1.0),
-            repetition_penalty: config.repetition_penalty.unwrap_or(1.1),
-            stop_sequences: config.stop_sequences.clone(),
-        };
+    /// Generate text as a `Stream` of chunks
+    ///
+    /// Unlike `generate_text_stream`, which takes a callback, the returned `WatsonxStream`
+    /// works with `futures::StreamExt` and `tokio_stream::StreamExt` combinators such as
+    /// `timeout` and `take_while`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures::StreamExt;
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, GenerationConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let mut stream = client
+    ///     .generate_stream_tokio("Write a haiku about Rust", &GenerationConfig::default())
+    ///     .await?;
+    /// while let Some(chunk) = stream.next().await {
+    ///     print!("{}", chunk?.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_stream_tokio(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+    ) -> Result<WatsonxStream<GenerationChunk>> {
+        let chunks = self.send_text_stream_request(prompt, config, 5).await?;
+        Ok(WatsonxStream::from_chunks(chunks).with_max_line_bytes(self.sse_line_limit(config)))
+    }
+
+    /// Generate text as a `Stream` of text chunks
+    ///
+    /// The stream counterpart of `generate_text_stream`: each item is the text of one SSE
+    /// event as it arrives, so concatenating every item gives the full generated text.
+    /// Events without text are skipped. Use `generate_stream_tokio` to also see token counts
+    /// and the stop reason.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures::StreamExt;
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, GenerationConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let stream = client
+    ///     .generate_stream("Write a haiku about Rust", &GenerationConfig::default())
+    ///     .await?;
+    /// let text: Vec<String> = stream.filter_map(|chunk| async { chunk.ok() }).collect().await;
+    /// println!("{}", text.concat());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_stream(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+    ) -> Result<impl Stream<Item = Result<String>> + Send + Unpin + use<>> {
+        let chunks = self.send_text_stream_request(prompt, config, 1).await?;
+        let chunks =
+            WatsonxStream::<GenerationChunk>::from_chunks(chunks).with_max_line_bytes(self.sse_line_limit(config));
+        Ok(text_deltas(chunks, |chunk| chunk.text))
+    }
+
+    /// Generate text in a background task, delivering chunks over a channel
+    ///
+    /// Returns the receiving end of the chunk channel and a handle to the background task.
+    /// Receive chunks with `recv().await` until it returns `None`, then await the handle to
+    /// get the final `GenerationResult` (including token counts when reported). Dropping the
+    /// receiver early does not cancel generation; the task still completes the result.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, GenerationConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let (mut chunks, handle) = client
+    ///     .generate_text_stream_channel("Tell me a story", &GenerationConfig::default())
+    ///     .await?;
+    /// while let Some(chunk) = chunks.recv().await {
+    ///     print!("{}", chunk);
+    /// }
+    /// let result = handle.await??;
+    /// println!("\nTokens: {:?}", result.tokens_used);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_text_stream_channel(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+    ) -> Result<(mpsc::Receiver<String>, JoinHandle<Result<GenerationResult>>)> {
+        let request_id = Uuid::new_v4().to_string();
+        let chunks = self.send_text_stream_request(prompt, config, 5).await?;
+        let model_id = config.model_id.clone();
+        let (sender, receiver) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+
+        let handle = tokio::spawn(forward_generation_chunks(
+            chunks,
+            model_id,
+            prompt.len(),
+            self.sse_line_limit(config),
+            request_id,
+            sender,
+        ));
+
+        Ok((receiver, handle))
+    }
+
+    /// Generate text, sending each chunk to `sender` as it arrives
+    ///
+    /// Unlike the `generate_text_stream` callback, sending awaits, so a bounded channel
+    /// applies backpressure to the stream. Returns the final `GenerationResult` once every
+    /// chunk has been sent. A closed receiver does not cancel generation.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use tokio::sync::mpsc;
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, GenerationConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let (sender, mut receiver) = mpsc::channel(32);
+    /// let consumer = tokio::spawn(async move {
+    ///     while let Some(chunk) = receiver.recv().await {
+    ///         print!("{}", chunk);
+    ///     }
+    /// });
+    /// let result = client
+    ///     .generate_text_channel("Tell me a story", &GenerationConfig::default(), sender)
+    ///     .await?;
+    /// consumer.await?;
+    /// println!("\nStop reason: {:?}", result.stop_reason);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_text_channel(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        sender: mpsc::Sender<String>,
+    ) -> Result<GenerationResult> {
+        let request_id = Uuid::new_v4().to_string();
+        let chunks = self.send_text_stream_request(prompt, config, 5).await?;
+        forward_generation_chunks(
+            chunks,
+            config.model_id.clone(),
+            prompt.len(),
+            self.sse_line_limit(config),
+            request_id,
+            sender,
+        )
+        .await
+    }
+
+    /// Create a bounded chunk channel and the generation future that feeds it
+    ///
+    /// Nothing is sent until the returned future is polled, so drive it alongside the
+    /// receiver (for example with `tokio::join!`). Unlike `generate_text_stream_channel`,
+    /// no background task is spawned.
+    pub fn generate_text_bounded_channel<'a>(
+        &'a self,
+        prompt: &'a str,
+        config: &'a GenerationConfig,
+        buffer_size: usize,
+    ) -> (
+        mpsc::Receiver<String>,
+        impl Future<Output = Result<GenerationResult>> + Send + 'a,
+    ) {
+        let (sender, receiver) = mpsc::channel(buffer_size.max(1));
+        (receiver, self.generate_text_channel(prompt, config, sender))
+    }
+
+    /// Run `generate_text` on a background task
+    ///
+    /// The returned handle can be awaited for the result or dropped to let the generation
+    /// finish in the background. Must be called from within a Tokio runtime.
+    pub fn spawn_generate(
+        &self,
+        prompt: impl Into<String>,
+        config: GenerationConfig,
+    ) -> JoinHandle<Result<GenerationResult>> {
+        let client = self.clone();
+        let prompt = prompt.into();
+        tokio::spawn(async move { client.generate_text(&prompt, &config).await })
+    }
+
+    /// Run a batch of generations on background tasks, delivering each result as it completes
+    ///
+    /// Results arrive in completion order, not request order; use `BatchRequest::id` to
+    /// match them up. The channel closes once every request has finished. Must be called
+    /// from within a Tokio runtime.
+    pub fn spawn_generate_batch_stream(
+        &self,
+        requests: Vec<BatchRequest>,
+        default_config: &GenerationConfig,
+    ) -> mpsc::Receiver<BatchItemResult> {
+        let (sender, receiver) = mpsc::channel(requests.len().max(1));
+        let client = Arc::new(self.clone());
+
+        for request in requests {
+            let client = Arc::clone(&client);
+            let sender = sender.clone();
+            let config = request.config.unwrap_or_else(|| default_config.clone());
+
+            tokio::spawn(async move {
+                let item = match client.generate_text(&request.prompt, &config).await {
+                    Ok(result) => BatchItemResult::success(request.id, request.prompt, result),
+                    Err(error) => BatchItemResult::failure(request.id, request.prompt, error),
+                };
+                // A closed receiver means the caller stopped listening
+                let _ = sender.send(item).await;
+            });
+        }
+
+        receiver
+    }
+
+    /// Send a streaming text generation request and return the response body chunks
+    ///
+    /// With `first_token_timeout` set, fails with `Error::Timeout` unless the first chunk
+    /// arrives within that time of dispatch.
+    async fn send_text_stream_request(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        min_new_tokens: u32,
+    ) -> Result<ByteChunks> {
+        let access_token = self.ensure_token_valid().await?;
+
+        config.validate()?;
+        let api_version = config.api_version(&self.config.api_version)?;
+        let params = GenerationParams::from_config(config, min_new_tokens);
 
         let request_body = GenerationRequest {
             input: prompt.to_string(),
@@ -398,161 +1772,146 @@ impl WatsonxClient {
 
         let url = format!(
             "{}/ml/v1/text/generation_stream?version={}",
-            self.config.api_url, self.config.api_version
+            self.config.api_url, api_version
         );
 
-        let response = self
+        let deadline = config
+            .first_token_timeout
+            .map(|first_token_timeout| tokio::time::Instant::now() + first_token_timeout);
+        let request = self
             .client
             .post(&url)
             .header("Accept", "application/json")
             .header("Content-Type", "application/json")
             .header("Authorization", format!("Bearer {}", access_token))
             .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| Error::Network(format!(
-                "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
-                e
-            )))?;
+            .send();
+        let response = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, request)
+                .await
+                .map_err(|_| crate::sse::no_tokens_received())?,
+            None => request.await,
+        }
+        .map_err(|e| Error::Network(format!(
+            "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
+            e
+        )))?;
 
+        self.record_rate_limit(response.headers());
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(Error::Api(format!(
-                "WatsonX API request failed (HTTP {}): {}. Verify your model ID '{}' is correct and your project has access to it.",
-                status, error_text, config.model_id
-            )));
+            return Err(generation_status_error(status, &error_text, &config.model_id));
         }
 
-        let mut answer = String::new();
-        
-        // Use bytes_stream for true streaming - process chunks as they arrive
-        let mut stream = response.bytes_stream();
-        let mut buffer = String::new();
-
-        // Process stream chunks in real-time
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| Error::Network(e.to_string()))?;
-            let text = String::from_utf8_lossy(&chunk);
-            buffer.push_str(&text);
-
-            // Process complete lines from buffer
-            while let Some(newline_pos) = buffer.find('\n') {
-                let line = buffer[..newline_pos].to_string();
-                buffer = buffer[newline_pos + 1..].to_string();
-
-                let trimmed = line.trim();
-                if trimmed.is_empty() || trimmed.starts_with("id:") || trimmed.starts_with("event:") {
-                    continue;
-                }
-
-                if trimmed.starts_with("data:") {
-                    let json_data = if trimmed.starts_with("data: ") {
-                        &trimmed[6..]
-                    } else {
-                        &trimmed[5..]
-                    };
-
-                    if json_data.trim().is_empty() || json_data.trim() == "[DONE]" {
-                        continue;
-                    }
-
-                    match serde_json::from_str::<GenerationData>(json_data) {
-                        Ok(data) => {
-                            if let Some(result) = data.results.first() {
-                                let generated_text = &result.generated_text;
-                                answer.push_str(generated_text);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: Failed to parse SSE data: {}", e);
-                        }
-                    }
-                }
-            }
+        let chunks = crate::sse::response_chunks(response);
+        match deadline {
+            Some(deadline) => crate::sse::first_chunk_by(chunks, deadline).await,
+            None => Ok(chunks),
         }
+    }
 
-        // Process any remaining data in buffer
-        if !buffer.is_empty() {
-            let trimmed = buffer.trim();
-            if trimmed.starts_with("data:") {
-                let json_data = if trimmed.starts_with("data: ") {
-                    &trimmed[6..]
-                } else {
-                    &trimmed[5..]
-                };
-
-                if !json_data.trim().is_empty() && json_data.trim() != "[DONE]" {
-                    if let Ok(data) = serde_json::from_str::<GenerationData>(json_data) {
-                        if let Some(result) = data.results.first() {
-                            let generated_text = &result.generated_text;
-                            answer.push_str(generated_text);
-                        }
-                    }
-                }
-            }
-        }
-
-        if answer.trim().is_empty() {
-            return Err(Error::Api(
-                "Received empty response from WatsonX API. The model may have generated no output, or the response format was unexpected. Try adjusting your prompt or parameters.".to_string(),
-            ));
-        }
-
-        // Clean up the response
-        let mut cleaned_answer = answer.trim().to_string();
-
-        if cleaned_answer.starts_with("Answer:") {
-            cleaned_answer = cleaned_answer
-                .strip_prefix("Answer:")
-                .unwrap_or(&cleaned_answer)
-                .trim()
-                .to_string();
-        }
-
-        if let Some(query_pos) = cleaned_answer.find("Query:") {
-            cleaned_answer = cleaned_answer[..query_pos].trim().to_string();
-        }
-
-        let final_answer = cleaned_answer
-            .lines()
-            .next()
-            .unwrap_or(&cleaned_answer)
-            .trim()
-            .to_string();
-
-        Ok(final_answer)
+    /// Perform text generation request using streaming endpoint
+    ///
+    /// Each attempt has `config.timeout` to finish.
+    async fn perform_text_stream_generation(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+        retry_config: Option<&RetryConfig>,
+    ) -> Result<RawGenerationData> {
+        let (mut data, retry_stats) = self
+            .execute_with_retry(retry_config, || async {
+                let deadline = tokio::time::Instant::now() + config.timeout;
+                let stream = tokio::time::timeout_at(deadline, self.send_text_stream_request(prompt, config, 5))
+                    .await
+                    .map_err(|_| Error::Timeout("Request timed out".to_string()))??;
+
+                collect_generation_stream(stream, config, self.sse_line_limit(config), prompt.len(), deadline).await
+            })
+            .await?;
+        data.retry_stats = retry_stats;
+        Ok(data)
     }
 
     /// Perform text generation request using standard endpoint
+    ///
+    /// Each attempt has `config.timeout` to finish.
     async fn perform_text_generation(
         &self,
         prompt: &str,
         config: &GenerationConfig,
         _request_id: &str,
-    ) -> Result<String> {
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Call connect() first.".to_string())
-        })?;
+    ) -> Result<RawGenerationData> {
+        let (mut data, retry_stats) = self
+            .execute_with_retry(self.retry_config.as_ref(), || async {
+                let generation = async {
+                    let access_token = self.ensure_token_valid().await?;
+
+                    Self::perform_text_generation_internal(
+                        &self.client,
+                        &access_token,
+                        &self.config.project_id,
+                        &self.config.api_url,
+                        &self.config.api_version,
+                        prompt,
+                        config,
+                    )
+                    .await
+                };
 
-        Self::perform_text_generation_internal(
-            &self.client,
-            access_token,
-            &self.config.project_id,
-            &self.config.api_url,
-            &self.config.api_version,
+                match timeout(config.timeout, generation).await {
+                    Ok(result) => result,
+                    Err(_) => Err(Error::Timeout("Request timed out".to_string())),
+                }
+            })
+            .await?;
+        data.retry_stats = retry_stats;
+        Ok(data)
+    }
+
+    /// Internal method for text generation that can be called from spawned tasks
+    /// This allows true parallelism by not requiring &self
+    async fn perform_text_generation_internal(
+        client: &Client,
+        access_token: &str,
+        project_id: &str,
+        api_url: &str,
+        api_version: &str,
+        prompt: &str,
+        config: &GenerationConfig,
+    ) -> Result<RawGenerationData> {
+        let body = Self::perform_text_generation_raw(
+            client,
+            access_token,
+            project_id,
+            api_url,
+            api_version,
             prompt,
             config,
         )
-        .await
+        .await?;
+
+        let generation_data: GenerationData = serde_json::from_value(body)
+            .map_err(|e| Error::Serialization(format!(
+                "Failed to parse JSON response: {}. The API response format may have changed. Please report this issue.",
+                e
+            )))?;
+
+        match generation_data.results.into_iter().next() {
+            Some(result) => RawGenerationData::from(result).non_empty(&config.model_id, prompt.len()),
+            None => Err(Error::EmptyGeneration {
+                model_id: config.model_id.clone(),
+                prompt_length: prompt.len(),
+            }),
+        }
     }
 
-    /// Internal method for text generation that can be called from spawned tasks
-    /// This allows true parallelism by not requiring &self
-    async fn perform_text_generation_internal(
+    /// Send a text generation request and return the unparsed JSON response body
+    async fn perform_text_generation_raw(
         client: &Client,
         access_token: &str,
         project_id: &str,
@@ -560,16 +1919,10 @@ impl WatsonxClient {
         api_version: &str,
         prompt: &str,
         config: &GenerationConfig,
-    ) -> Result<String> {
-        let params = GenerationParams {
-            decoding_method: "greedy".to_string(),
-            max_new_tokens: config.max_tokens,
-            min_new_tokens: 5,
-            top_k: config.top_k.unwrap_or(50),
-            top_p: config.top_p.unwrap_or(1.0),
-            repetition_penalty: config.repetition_penalty.unwrap_or(1.1),
-            stop_sequences: config.stop_sequences.clone(),
-        };
+    ) -> Result<serde_json::Value> {
+        config.validate()?;
+        let api_version = config.api_version(api_version)?;
+        let params = GenerationParams::from_config(config, 5);
 
         let request_body = GenerationRequest {
             input: prompt.to_string(),
@@ -603,46 +1956,77 @@ impl WatsonxClient {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(Error::Api(format!(
-                "WatsonX API request failed (HTTP {}): {}. Verify your model ID '{}' is correct and your project has access to it.",
-                status, error_text, config.model_id
-            )));
+            return Err(generation_status_error(status, &error_text, &config.model_id));
         }
 
-        // Parse the complete JSON response
-        let generation_data: GenerationData = response
+        response
             .json()
             .await
             .map_err(|e| Error::Serialization(format!(
                 "Failed to parse JSON response: {}. The API response format may have changed. Please report this issue.",
                 e
-            )))?;
-
-        if let Some(result) = generation_data.results.first() {
-            Ok(result.generated_text.clone())
-        } else {
-            Err(Error::Api(
-                "No generation results returned from API. The model may not have generated any output. Try adjusting your prompt or parameters.".to_string(),
-            ))
-        }
+            )))
     }
 
     /// List available foundation models
+    ///
+    /// Returns the first page of results; use `list_all_models` to fetch every page.
     pub async fn list_models(&self) -> Result<Vec<crate::types::ModelInfo>> {
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication(
-                "Not authenticated. Call connect() first to obtain an access token.".to_string(),
-            )
-        })?;
+        Ok(self.list_models_page(None, None).await?.models)
+    }
 
-        let url = format!(
-            "{}/ml/v1/foundation_model_specs?version={}",
-            self.config.api_url, self.config.api_version
-        );
+    /// List one page of foundation models
+    ///
+    /// Pass the `next_cursor` of the previous page as `start` to continue. `limit` caps the
+    /// page size (the API default is 100).
+    pub async fn list_models_page(&self, start: Option<&str>, limit: Option<u32>) -> Result<ModelPage> {
+        self.fetch_models_page(start, limit, None).await
+    }
+
+    /// List every available foundation model, following page cursors
+    pub async fn list_all_models(&self) -> Result<Vec<crate::types::ModelInfo>> {
+        self.collect_model_pages(None).await
+    }
+
+    /// Fetch every page of models matching the server-side part of `filter`
+    async fn collect_model_pages(&self, filter: Option<&ModelFilter>) -> Result<Vec<crate::types::ModelInfo>> {
+        let mut models = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self.fetch_models_page(cursor.as_deref(), None, filter).await?;
+            models.extend(page.models);
+            match page.next_cursor {
+                Some(next) if cursor.as_ref() != Some(&next) => cursor = Some(next),
+                _ => return Ok(models),
+            }
+        }
+    }
+
+    /// Fetch one page of model specs, passing `filter` as the `filters` query parameter
+    async fn fetch_models_page(
+        &self,
+        start: Option<&str>,
+        limit: Option<u32>,
+        filter: Option<&ModelFilter>,
+    ) -> Result<ModelPage> {
+        let access_token = self.ensure_token_valid().await?;
+
+        let url = format!("{}/ml/v1/foundation_model_specs", self.config.api_url);
+        let mut query = vec![("version", self.config.api_version.clone())];
+        if let Some(start) = start {
+            query.push(("start", start.to_string()));
+        }
+        if let Some(limit) = limit {
+            query.push(("limit", limit.to_string()));
+        }
+        if let Some(filters) = filter.and_then(ModelFilter::query_filters) {
+            query.push(("filters", filters));
+        }
 
         let response = self
             .client
             .get(&url)
+            .query(&query)
             .header("Accept", "application/json")
             .header("Authorization", format!("Bearer {}", access_token))
             .send()
@@ -652,6 +2036,7 @@ impl WatsonxClient {
                 e
             )))?;
 
+        self.record_rate_limit(response.headers());
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response
@@ -675,86 +2060,257 @@ impl WatsonxClient {
         let models_response: ModelsResponse = serde_json::from_str(&response_text)
             .map_err(|e| Error::Serialization(format!("Failed to parse models response: {}", e)))?;
 
-        let model_infos = models_response
-            .resources
-            .into_iter()
-            .map(|spec| {
-                let supported_tasks = spec.functions
-                    .map(|functions| functions.into_iter().map(|f| f.id).collect());
-                
-                let available = spec.lifecycle
-                    .and_then(|lifecycle| {
-                        lifecycle.iter()
-                            .find(|l| l.id == "available")
-                            .map(|_| true)
-                    });
-
-                crate::types::ModelInfo {
-                    model_id: spec.model_id,
-                    name: spec.label,
-                    description: spec.long_description.or(spec.short_description),
-                    provider: spec.provider,
-                    version: None, // Not available in API response
-                    supported_tasks,
-                    max_context_length: None, // Not available in API response
-                    available,
-                }
-            })
-            .collect();
-
-        Ok(model_infos)
+        Ok(models_response.into_page())
     }
 
-    /// Assess the quality of generated text
-    pub fn assess_quality(&self, text: &str, _prompt: &str) -> f32 {
-        let mut score = 0.0;
-        let mut max_score = 0.0;
+    /// Fetch the specification of a single foundation model
+    ///
+    /// Returns `Error::ModelNotFound` if the API does not know `model_id`.
+    pub async fn get_model_info(&self, model_id: &str) -> Result<crate::types::ModelInfo> {
+        let access_token = self.ensure_token_valid().await?;
 
-        // Check if result is not empty and reasonable length
-        max_score += 0.3;
-        let trimmed = text.trim();
-        if !trimmed.is_empty() && trimmed.len() > 8 && trimmed.len() < 200 {
-            score += 0.3;
-        }
+        let url = format!(
+            "{}/ml/v1/foundation_model_specs/{}?version={}",
+            self.config.api_url,
+            encode_path_segment(model_id),
+            self.config.api_version
+        );
 
-        // Check for common patterns
-        max_score += 0.2;
-        let common_patterns = [
-            "the", "and", "or", "but", "in", "on", "at", "to", "for", "of", "with", "by",
-        ];
-        if common_patterns.iter().any(|pattern| text.to_lowercase().contains(pattern)) {
-            score += 0.2;
+        let response = self
+            .client
+            .get(&url)
+            .header("Accept", "application/json")
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+            .await
+            .map_err(|e| Error::Network(format!(
+                "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
+                e
+            )))?;
+
+        self.record_rate_limit(response.headers());
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(Error::ModelNotFound(model_id.to_string()));
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to fetch model {} (HTTP {}): {}",
+                model_id, status, error_text
+            )));
         }
 
-        // Check if it doesn't contain obvious errors
-        max_score += 0.2;
-        let error_indicators = ["error", "failed", "invalid", "unknown", "not found"];
-        if !error_indicators
-            .iter()
-            .any(|indicator| text.to_lowercase().contains(indicator))
-        {
-            score += 0.2;
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| Error::Network(format!(
+                "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
+                e
+            )))?;
+
+        let spec: ModelSpec = serde_json::from_str(&response_text)
+            .map_err(|e| Error::Serialization(format!("Failed to parse model spec: {}", e)))?;
+
+        Ok(spec.into())
+    }
+
+    /// List available foundation models matching a filter
+    ///
+    /// Provider, task and availability are sent to the API as filters, and every page is
+    /// fetched. The filter is also applied client-side, which is how `search` is matched.
+    pub async fn list_models_filtered(&self, filter: &ModelFilter) -> Result<Vec<crate::types::ModelInfo>> {
+        let models = self.collect_model_pages(Some(filter)).await?;
+        Ok(models.into_iter().filter(|model| filter.matches(model)).collect())
+    }
+
+    /// List available foundation models sorted by the given field
+    pub async fn list_models_sorted(&self, sort_by: ModelSortField) -> Result<Vec<crate::types::ModelInfo>> {
+        let mut models = self.list_models().await?;
+        sort_by.sort(&mut models);
+        Ok(models)
+    }
+
+    /// Embed `texts` with the embeddings endpoint
+    ///
+    /// The endpoint accepts at most 10 texts per request, so larger inputs are split into
+    /// several requests and the embeddings concatenated in input order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use watsonx_rs::{EmbeddingConfig, WatsonxClient, WatsonxConfig};
+    /// # async fn example() -> watsonx_rs::Result<()> {
+    /// # let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// let result = client
+    ///     .embed(&["first document", "second document"], &EmbeddingConfig::default())
+    ///     .await?;
+    /// assert_eq!(result.embeddings.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn embed(&self, texts: &[&str], config: &EmbeddingConfig) -> Result<EmbeddingResult> {
+        let mut result = EmbeddingResult {
+            model_id: config.model_id.clone(),
+            input_texts: config
+                .return_options
+                .as_ref()
+                .filter(|options| options.input_text)
+                .map(|_| Vec::with_capacity(texts.len())),
+            ..Default::default()
+        };
+
+        for batch in texts.chunks(EMBEDDING_BATCH_SIZE) {
+            self.perform_embedding_request(batch, config)
+                .await?
+                .append_to(&mut result, batch.len())?;
         }
 
-        // Check for proper sentence structure
-        max_score += 0.15;
-        let sentence_count = text.split('.').filter(|s| !s.trim().is_empty()).count();
-        if sentence_count > 0 {
-            score += 0.15;
+        Ok(result)
+    }
+
+    /// Embed a single text, returning its embedding vector
+    pub async fn embed_single(&self, text: &str, config: &EmbeddingConfig) -> Result<Vec<f32>> {
+        self.embed(&[text], config)
+            .await?
+            .embeddings
+            .pop()
+            .ok_or_else(|| Error::Api("No embedding in response".to_string()))
+    }
+
+    /// Send one request of at most `EMBEDDING_BATCH_SIZE` texts to the embeddings endpoint
+    async fn perform_embedding_request(&self, texts: &[&str], config: &EmbeddingConfig) -> Result<EmbeddingResponse> {
+        let mut request_body = serde_json::json!({
+            "inputs": texts,
+            "model_id": config.model_id,
+            "project_id": self.config.project_id,
+        });
+        let mut parameters = serde_json::Map::new();
+        if let Some(tokens) = config.truncate_input_tokens {
+            parameters.insert("truncate_input_tokens".to_string(), serde_json::json!(tokens));
         }
+        if let Some(options) = &config.return_options {
+            parameters.insert("return_options".to_string(), serde_json::json!(options));
+        }
+        if !parameters.is_empty() {
+            request_body["parameters"] = serde_json::Value::Object(parameters);
+        }
+
+        self.post_text_endpoint("embeddings", &request_body).await
+    }
 
-        // Check for reasonable word count
-        max_score += 0.15;
-        let word_count = text.split_whitespace().count();
-        if word_count > 3 && word_count < 100 {
-            score += 0.15;
+    /// Rerank `documents` by relevance to `query` with a cross-encoder model
+    ///
+    /// Results are sorted from most to least relevant. Each `RankedDocument` keeps the index
+    /// of the document in `documents`.
+    pub async fn rerank(&self, query: &str, documents: &[&str], config: &RerankConfig) -> Result<RerankResult> {
+        let mut parameters = serde_json::json!({ "return_options": { "inputs": false } });
+        if let Some(top_n) = config.top_n {
+            parameters["return_options"]["top_n"] = serde_json::json!(top_n);
         }
+        if let Some(tokens) = config.truncate_input_tokens {
+            parameters["truncate_input_tokens"] = serde_json::json!(tokens);
+        }
+        let request_body = serde_json::json!({
+            "model_id": config.model_id,
+            "project_id": self.config.project_id,
+            "query": query,
+            "inputs": documents.iter().map(|text| serde_json::json!({ "text": text })).collect::<Vec<_>>(),
+            "parameters": parameters,
+        });
+
+        let response: RerankResponse = self.post_text_endpoint("rerank", &request_body).await?;
+        response.into_rerank_result(documents)
+    }
+
+    /// Tokenize `text` with the tokenizer of `model_id`
+    pub async fn tokenize(&self, text: &str, model_id: &str) -> Result<TokenizationResult> {
+        let request_body = serde_json::json!({
+            "model_id": model_id,
+            "project_id": self.config.project_id,
+            "input": text,
+            "parameters": { "return_tokens": true, "return_token_ids": true },
+        });
+
+        let response: TokenizationResponse = self.post_text_endpoint("tokenization", &request_body).await?;
+        Ok(response.result)
+    }
+
+    /// Check whether `text` is at most `max_tokens` tokens long for `model_id`
+    ///
+    /// Useful for checking a prompt against the model's context window before sending it.
+    pub async fn is_within_context(&self, text: &str, model_id: &str, max_tokens: u32) -> Result<bool> {
+        Ok(self.tokenize(text, model_id).await?.token_count <= max_tokens)
+    }
+
+    /// POST `body` to `/ml/v1/text/{endpoint}` and parse the JSON response
+    async fn post_text_endpoint<T: DeserializeOwned>(&self, endpoint: &str, body: &serde_json::Value) -> Result<T> {
+        let access_token = self.ensure_token_valid().await?;
+
+        let url = format!(
+            "{}/ml/v1/text/{}?version={}",
+            self.config.api_url, endpoint, self.config.api_version
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", access_token))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| Error::Network(format!(
+                "Network request failed: {}. Check your internet connection and verify the API endpoint URL is correct.",
+                e
+            )))?;
 
-        if max_score > 0.0 {
-            score / max_score
-        } else {
-            0.0
+        self.record_rate_limit(response.headers());
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Text {} request failed (HTTP {}): {}",
+                endpoint, status, error_text
+            )));
         }
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(format!("Failed to parse {} response: {}", endpoint, e)))
+    }
+
+    /// Assess the quality of generated text
+    ///
+    /// A fast offline heuristic based on length, word count and error keywords.
+    /// Use [`assess_quality_llm`](Self::assess_quality_llm) for a model-based judgement.
+    pub fn assess_quality(&self, text: &str, _prompt: &str) -> f32 {
+        crate::quality::heuristic_score(text)
+    }
+
+    /// Assess the quality of generated text using a model as judge
+    ///
+    /// Asks the judge model (a small fast model such as Granite 8B works well) to rate
+    /// `text` as an answer to `prompt` on a 0-10 scale. The rating is scaled to 0.0-1.0
+    /// so it can be compared with [`assess_quality`](Self::assess_quality).
+    pub async fn assess_quality_llm(
+        &self,
+        text: &str,
+        prompt: &str,
+        judge_config: &GenerationConfig,
+    ) -> Result<f32> {
+        let judge_prompt = crate::quality::judge_prompt(text, prompt);
+        let result = self.generate_text(&judge_prompt, judge_config).await?;
+        let rating = crate::quality::parse_judge_score(&result.text)?;
+        Ok(rating / 10.0)
     }
 
     /// Generate text for multiple prompts concurrently and collect all results
@@ -780,7 +2336,7 @@ impl WatsonxClient {
     /// 
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let config = WatsonxConfig::from_env()?;
-    /// let mut client = WatsonxClient::new(config)?;
+    /// let client = WatsonxClient::new(config)?;
     /// client.connect().await?;
     /// 
     /// let default_config = GenerationConfig::default()
@@ -818,14 +2374,11 @@ impl WatsonxClient {
         let start_time = Instant::now();
 
         // Check authentication before spawning tasks
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Call connect() first.".to_string())
-        })?;
+        let access_token = self.ensure_token_valid().await?;
 
         // Clone necessary parts for spawning tasks
         // reqwest::Client is designed to be cloned (uses connection pooling internally)
         let http_client = self.client.clone();
-        let access_token = access_token.clone();
         let project_id = self.config.project_id.clone();
         let api_url = self.config.api_url.clone();
         let api_version = self.config.api_version.clone();
@@ -859,8 +2412,8 @@ impl WatsonxClient {
                     ).await;
                     
                     match result {
-                        Ok(text) => {
-                            let gen_result = GenerationResult::new(text, config.model_id.clone());
+                        Ok(results) => {
+                            let gen_result = results.into_generation_result(config.model_id.clone());
                             BatchItemResult::success(id, prompt, gen_result)
                         }
                         Err(error) => BatchItemResult::failure(id, prompt, error),
@@ -910,7 +2463,7 @@ impl WatsonxClient {
     /// 
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let config = WatsonxConfig::from_env()?;
-    /// let mut client = WatsonxClient::new(config)?;
+    /// let client = WatsonxClient::new(config)?;
     /// client.connect().await?;
     /// 
     /// let gen_config = GenerationConfig::default()
@@ -967,7 +2520,7 @@ impl WatsonxClient {
     /// 
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let config = WatsonxConfig::from_env()?;
-    /// let mut client = WatsonxClient::new(config)?;
+    /// let client = WatsonxClient::new(config)?;
     /// client.connect().await?;
     /// 
     /// let chat_config = ChatCompletionConfig::default()
@@ -989,9 +2542,138 @@ impl WatsonxClient {
         config: &ChatCompletionConfig,
     ) -> Result<ChatCompletionResult> {
         let request_id = Uuid::new_v4().to_string();
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Call connect() first.".to_string())
-        })?;
+        let completion_data = self.perform_chat_completion_raw(messages, config).await?;
+
+        // Parse response - handle different response formats
+        let raw_choices = completion_data["choices"]
+            .as_array()
+            .filter(|choices| !choices.is_empty())
+            .ok_or_else(|| Error::Api("No choices in response".to_string()))?;
+
+        let choices = raw_choices
+            .iter()
+            .enumerate()
+            .map(|(position, choice)| {
+                let content = choice["message"]["content"]
+                    .as_str()
+                    .ok_or_else(|| Error::Api("No message content in response".to_string()))?;
+                Ok(ChatCompletionChoice {
+                    message: ChatMessage::assistant(content),
+                    finish_reason: choice["finish_reason"].as_str().map(str::to_string),
+                    index: choice["index"].as_u64().map_or(position as u32, |i| i as u32),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut result = ChatCompletionResult::new(choices[0].message.clone(), config.model_id.clone())
+            .with_request_id(request_id)
+            .with_choices(choices);
+
+        // Extract token usage if available
+        if let Some(usage) = completion_data.get("usage") {
+            if let Some(prompt_tokens) = usage["prompt_tokens"].as_u64() {
+                if let Some(completion_tokens) = usage["completion_tokens"].as_u64() {
+                    if let Some(total_tokens) = usage["total_tokens"].as_u64() {
+                        result = result.with_tokens(
+                            prompt_tokens as u32,
+                            completion_tokens as u32,
+                            total_tokens as u32,
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Create a chat completion and return the raw choice objects from the API response
+    ///
+    /// No parsing or post-processing is applied, which makes this useful for debugging.
+    pub async fn chat_completion_raw(
+        &self,
+        messages: Vec<ChatMessage>,
+        config: &ChatCompletionConfig,
+    ) -> Result<Vec<serde_json::Value>> {
+        let mut completion_data = self.perform_chat_completion_raw(messages, config).await?;
+
+        match completion_data["choices"].take() {
+            serde_json::Value::Array(choices) => Ok(choices),
+            _ => Err(Error::Api("No choices in response".to_string())),
+        }
+    }
+
+    /// Run chat completions for several conversations concurrently
+    ///
+    /// Each conversation runs on its own Tokio task. Results are returned in input order,
+    /// and each conversation succeeds or fails independently.
+    pub async fn chat_completion_batch(
+        &self,
+        conversations: Vec<Vec<ChatMessage>>,
+        config: &ChatCompletionConfig,
+    ) -> Result<Vec<Result<ChatCompletionResult>>> {
+        let requests = conversations
+            .into_iter()
+            .map(ChatCompletionBatchRequest::new)
+            .collect();
+
+        self.chat_completion_batch_with_progress(requests, config, |_, _| {})
+            .await
+    }
+
+    /// Run chat completions concurrently, reporting progress as each one finishes
+    ///
+    /// `progress` is called with the number of completed conversations and the total after
+    /// each completion, in completion order. Requests without a `config_override` use
+    /// `default_config`. Results are returned in input order.
+    pub async fn chat_completion_batch_with_progress<F>(
+        &self,
+        requests: Vec<ChatCompletionBatchRequest>,
+        default_config: &ChatCompletionConfig,
+        mut progress: F,
+    ) -> Result<Vec<Result<ChatCompletionResult>>>
+    where
+        F: FnMut(usize, usize),
+    {
+        // Check authentication before spawning tasks
+        self.ensure_token_valid().await?;
+
+        let total = requests.len();
+        let mut tasks: FuturesUnordered<_> = requests
+            .into_iter()
+            .enumerate()
+            .map(|(index, request)| {
+                let client = self.clone();
+                let config = request
+                    .config_override
+                    .unwrap_or_else(|| default_config.clone());
+                let task = tokio::spawn(async move {
+                    client.chat_completion(request.messages, &config).await
+                });
+                async move { (index, task.await) }
+            })
+            .collect();
+
+        let mut results: Vec<Option<Result<ChatCompletionResult>>> = (0..total).map(|_| None).collect();
+        let mut completed = 0;
+        while let Some((index, task_result)) = tasks.next().await {
+            results[index] = Some(task_result.unwrap_or_else(|e| {
+                Err(Error::Network(format!("Task join error: {}", e)))
+            }));
+            completed += 1;
+            progress(completed, total);
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Send a chat completion request and return the unparsed JSON response body
+    async fn perform_chat_completion_raw(
+        &self,
+        messages: Vec<ChatMessage>,
+        config: &ChatCompletionConfig,
+    ) -> Result<serde_json::Value> {
+        let access_token = self.ensure_token_valid().await?;
 
         // Build request body
         let mut request_body = serde_json::json!({
@@ -1016,12 +2698,13 @@ impl WatsonxClient {
         if let Some(repetition_penalty) = config.repetition_penalty {
             request_body["repetition_penalty"] = serde_json::Value::Number(serde_json::Number::from_f64(repetition_penalty as f64).unwrap());
         }
+        if let Some(n) = config.n {
+            request_body["n"] = serde_json::Value::Number(serde_json::Number::from(n));
+        }
 
-        // Try both possible endpoints
-        let endpoints = vec![
-            format!("{}/ml/gateway/v1/chat/completions", self.config.api_url),
-            format!("{}/ml/v1/chat/completions?version={}", self.config.api_url, self.config.api_version),
-        ];
+        // Try both possible endpoints, starting with the one that worked last time
+        let api_version = config.api_version(&self.config.api_version)?;
+        let endpoints = self.chat_completion_endpoints(api_version);
 
         let mut last_error = None;
         for url in endpoints {
@@ -1037,51 +2720,18 @@ impl WatsonxClient {
 
             match response {
                 Ok(resp) if resp.status().is_success() => {
-                    let completion_data: serde_json::Value = resp
+                    self.cache_endpoint(&url);
+                    self.record_rate_limit(resp.headers());
+                    return resp
                         .json()
                         .await
                         .map_err(|e| Error::Serialization(format!(
-                "Failed to parse JSON response: {}. The API response format may have changed. Please report this issue.",
-                e
-            )))?;
-
-                    // Parse response - handle different response formats
-                    let choice = completion_data["choices"]
-                        .as_array()
-                        .and_then(|choices| choices.first())
-                        .ok_or_else(|| Error::Api("No choices in response".to_string()))?;
-
-                    let message_content = choice["message"]["content"]
-                        .as_str()
-                        .ok_or_else(|| Error::Api("No message content in response".to_string()))?;
-
-                    let message = ChatMessage::assistant(message_content);
-                    let mut result = ChatCompletionResult::new(message, config.model_id.clone())
-                        .with_request_id(request_id.clone());
-
-                    // Extract token usage if available
-                    if let Some(usage) = completion_data.get("usage") {
-                        if let Some(prompt_tokens) = usage["prompt_tokens"].as_u64() {
-                            if let Some(completion_tokens) = usage["completion_tokens"].as_u64() {
-                                if let Some(total_tokens) = usage["total_tokens"].as_u64() {
-                                    result = result.with_tokens(
-                                        prompt_tokens as u32,
-                                        completion_tokens as u32,
-                                        total_tokens as u32,
-                                    );
-                                }
-                            }
-                        }
-                    }
-
-                    // Extract finish reason if available
-                    if let Some(reason) = choice["finish_reason"].as_str() {
-                        result = result.with_finish_reason(reason);
-                    }
-
-                    return Ok(result);
+                            "Failed to parse JSON response: {}. The API response format may have changed. Please report this issue.",
+                            e
+                        )));
                 }
                 Ok(resp) => {
+                    self.record_rate_limit(resp.headers());
                     let status = resp.status();
                     let error_text = resp
                         .text()
@@ -1107,6 +2757,52 @@ impl WatsonxClient {
         }))
     }
 
+    /// Forget the chat completion endpoint discovered by earlier calls
+    ///
+    /// The next chat completion probes the gateway endpoint first again. Clones of this
+    /// client share the cache.
+    pub fn clear_endpoint_cache(&self) {
+        *self.successful_endpoint.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Rate limit budget reported by the most recent successful response
+    ///
+    /// Returns `None` until a response carries an `X-Ratelimit-Remaining` header. Clones of
+    /// this client share the state.
+    pub fn rate_limit_state(&self) -> Option<RateLimitState> {
+        *self.rate_limit.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Maximum SSE line size for a text generation request
+    fn sse_line_limit(&self, config: &GenerationConfig) -> usize {
+        config.max_sse_line_bytes.unwrap_or(self.config.sse_buffer.max_line_bytes)
+    }
+
+    /// Store the rate limit headers of a response, if present, including rate-limited ones
+    fn record_rate_limit(&self, headers: &reqwest::header::HeaderMap) {
+        if let Some(state) = RateLimitState::from_headers(headers) {
+            *self.rate_limit.write().unwrap_or_else(|e| e.into_inner()) = Some(state);
+        }
+    }
+
+    /// Record the chat completion endpoint that answered successfully
+    fn cache_endpoint(&self, url: &str) {
+        *self.successful_endpoint.write().unwrap_or_else(|e| e.into_inner()) = Some(url.to_string());
+    }
+
+    /// Candidate chat completion endpoints, with the cached one (if any) moved to the front
+    fn chat_completion_endpoints(&self, api_version: &str) -> Vec<String> {
+        let mut endpoints = vec![
+            format!("{}/ml/gateway/v1/chat/completions", self.config.api_url),
+            format!("{}/ml/v1/chat/completions?version={}", self.config.api_url, api_version),
+        ];
+        let cached = self.successful_endpoint.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(position) = cached.as_ref().and_then(|url| endpoints.iter().position(|e| e == url)) {
+            endpoints[..=position].rotate_right(1);
+        }
+        endpoints
+    }
+
     /// Create a chat completion with streaming callback for real-time output
     /// 
     /// This method uses the WatsonX AI chat completion streaming endpoint to generate
@@ -1130,7 +2826,7 @@ impl WatsonxClient {
     /// 
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let config = WatsonxConfig::from_env()?;
-    /// let mut client = WatsonxClient::new(config)?;
+    /// let client = WatsonxClient::new(config)?;
     /// client.connect().await?;
     /// 
     /// let chat_config = ChatCompletionConfig::default()
@@ -1154,15 +2850,75 @@ impl WatsonxClient {
         &self,
         messages: Vec<ChatMessage>,
         config: &ChatCompletionConfig,
-        callback: F,
-    ) -> Result<ChatCompletionResult>
-    where
-        F: Fn(&str) + Send + Sync,
-    {
-        let request_id = Uuid::new_v4().to_string();
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Call connect() first.".to_string())
-        })?;
+        callback: F,
+    ) -> Result<ChatCompletionResult>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        let request_id = Uuid::new_v4().to_string();
+        let response = self.send_chat_stream_request(&messages, config).await?;
+        let answer = crate::sse::parse_sse_stream_with_limits(
+            response,
+            Some(|chunk: &str| callback(chunk)),
+            &self.config.sse_buffer,
+        )
+        .await?;
+
+        if answer.trim().is_empty() {
+            return Err(Error::Api("Empty response from chat completion API".to_string()));
+        }
+
+        let message = ChatMessage::assistant(&answer);
+        Ok(ChatCompletionResult::new(message, config.model_id.clone())
+            .with_request_id(request_id))
+    }
+
+    /// Create a chat completion as a `Stream` of content deltas
+    ///
+    /// Unlike `chat_completion_stream`, which takes a callback, each item of the returned
+    /// stream is one text delta, so it can be collected, forwarded to a channel, or combined
+    /// with other `futures::StreamExt` adapters. Events without content are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use futures::StreamExt;
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig, ChatMessage, ChatCompletionConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let messages = vec![ChatMessage::user("Explain async/await in Rust.")];
+    /// let mut stream = client
+    ///     .chat_completion_stream_async(messages, &ChatCompletionConfig::default())
+    ///     .await?;
+    /// while let Some(delta) = stream.next().await {
+    ///     print!("{}", delta?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn chat_completion_stream_async(
+        &self,
+        messages: Vec<ChatMessage>,
+        config: &ChatCompletionConfig,
+    ) -> Result<impl Stream<Item = Result<String>> + Send + Unpin + use<>> {
+        let response = self.send_chat_stream_request(&messages, config).await?;
+        let chunks = WatsonxStream::<ChatChunk>::from_response(response)
+            .with_max_line_bytes(self.config.sse_buffer.max_line_bytes);
+        Ok(text_deltas(chunks, |chunk| chunk.content))
+    }
+
+    /// Send a streaming chat completion request, returning the successful response
+    ///
+    /// Tries each chat completion endpoint in turn, starting with the one that worked last time.
+    async fn send_chat_stream_request(
+        &self,
+        messages: &[ChatMessage],
+        config: &ChatCompletionConfig,
+    ) -> Result<reqwest::Response> {
+        let access_token = self.ensure_token_valid().await?;
 
         // Build request body
         let mut request_body = serde_json::json!({
@@ -1189,11 +2945,9 @@ impl WatsonxClient {
             request_body["repetition_penalty"] = serde_json::Value::Number(serde_json::Number::from_f64(repetition_penalty as f64).unwrap());
         }
 
-        // Try both possible endpoints
-        let endpoints = vec![
-            format!("{}/ml/gateway/v1/chat/completions", self.config.api_url),
-            format!("{}/ml/v1/chat/completions?version={}", self.config.api_url, self.config.api_version),
-        ];
+        // Try both possible endpoints, starting with the one that worked last time
+        let api_version = config.api_version(&self.config.api_version)?;
+        let endpoints = self.chat_completion_endpoints(api_version);
 
         let mut last_error = None;
         for url in endpoints {
@@ -1211,101 +2965,12 @@ impl WatsonxClient {
 
             match response {
                 Ok(resp) if resp.status().is_success() => {
-                    let mut answer = String::new();
-                    let mut stream = resp.bytes_stream();
-                    let mut buffer = String::new();
-
-                    // Process stream chunks in real-time
-                    while let Some(chunk_result) = stream.next().await {
-                        let chunk = chunk_result.map_err(|e| Error::Network(e.to_string()))?;
-                        let text = String::from_utf8_lossy(&chunk);
-                        buffer.push_str(&text);
-
-                        // Process complete lines from buffer
-                        while let Some(newline_pos) = buffer.find('\n') {
-                            let line = buffer[..newline_pos].to_string();
-                            buffer = buffer[newline_pos + 1..].to_string();
-
-                            let trimmed = line.trim();
-                            if trimmed.is_empty() || trimmed.starts_with("id:") || trimmed.starts_with("event:") {
-                                continue;
-                            }
-
-                            if trimmed.starts_with("data:") {
-                                let json_data = if trimmed.starts_with("data: ") {
-                                    &trimmed[6..]
-                                } else {
-                                    &trimmed[5..]
-                                };
-
-                                if json_data.trim().is_empty() || json_data.trim() == "[DONE]" {
-                                    continue;
-                                }
-
-                                match serde_json::from_str::<serde_json::Value>(json_data) {
-                                    Ok(data) => {
-                                        // Extract content from delta or message
-                                        if let Some(choices) = data.get("choices").and_then(|c| c.as_array()) {
-                                            if let Some(choice) = choices.first() {
-                                                if let Some(delta) = choice.get("delta") {
-                                                    if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
-                                                        answer.push_str(content);
-                                                        callback(content);
-                                                    }
-                                                } else if let Some(message) = choice.get("message") {
-                                                    if let Some(content) = message.get("content").and_then(|c| c.as_str()) {
-                                                        answer.push_str(content);
-                                                        callback(content);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(_) => {
-                                        // Ignore parse errors for individual chunks
-                                        continue;
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    // Process any remaining data in buffer
-                    if !buffer.is_empty() {
-                        let trimmed = buffer.trim();
-                        if trimmed.starts_with("data:") {
-                            let json_data = if trimmed.starts_with("data: ") {
-                                &trimmed[6..]
-                            } else {
-                                &trimmed[5..]
-                            };
-
-                            if !json_data.trim().is_empty() && json_data.trim() != "[DONE]" {
-                                if let Ok(data) = serde_json::from_str::<serde_json::Value>(json_data) {
-                                    if let Some(choices) = data.get("choices").and_then(|c| c.as_array()) {
-                                        if let Some(choice) = choices.first() {
-                                            if let Some(delta) = choice.get("delta") {
-                                                if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
-                                                    answer.push_str(content);
-                                                    callback(content);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    if answer.trim().is_empty() {
-                        return Err(Error::Api("Empty response from chat completion API".to_string()));
-                    }
-
-                    let message = ChatMessage::assistant(&answer);
-                    return Ok(ChatCompletionResult::new(message, config.model_id.clone())
-                        .with_request_id(request_id));
+                    self.cache_endpoint(&url);
+                    self.record_rate_limit(resp.headers());
+                    return Ok(resp);
                 }
                 Ok(resp) => {
+                    self.record_rate_limit(resp.headers());
                     let status = resp.status();
                     let error_text = resp
                         .text()
@@ -1332,13 +2997,33 @@ impl WatsonxClient {
     }
 }
 
+#[cfg(test)]
+impl WatsonxClient {
+    /// Client for a local `MockServer`, already holding an access token
+    ///
+    /// Bypasses the https-only `api_url` validation, which a loopback server cannot satisfy.
+    pub(crate) fn for_mock_server(url: &str) -> Self {
+        let config = WatsonxConfig::new(
+            "test_key".to_string(),
+            "12345678-1234-1234-1234-123456789abc".to_string(),
+        );
+        let mut client = Self::new(config).unwrap();
+        client.config.api_url = url.to_string();
+        *client.access_token.write().unwrap() = Some("test-token".to_string());
+        client
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
+
+    const TEST_PROJECT_ID: &str = "12345678-1234-1234-1234-123456789abc";
 
     #[test]
     fn test_quality_assessment() {
-        let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
         let client = WatsonxClient::new(config).unwrap();
 
         let good_text = "This is a well-formed sentence with proper grammar.";
@@ -1352,20 +3037,1014 @@ mod tests {
 
     #[test]
     fn test_config_validation() {
-        let config = WatsonxConfig::new("".to_string(), "test_project".to_string());
+        let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
         assert!(config.validate().is_err());
 
-        let config = WatsonxConfig::new("test_key".to_string(), "".to_string());
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        assert!(config.validate().is_ok());
+
+        let config = WatsonxConfig {
+            api_key: String::new(),
+            api_url: "http://localhost".to_string(),
+            generate_timeout_secs: 0,
+            ..WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+        };
+        let fields: Vec<String> = config.validation_errors().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, vec!["api_key", "api_url", "generate_timeout_secs"]);
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "Configuration error: api_key cannot be empty; api_url must begin with https://; generate_timeout_secs must be between 1 and 3600"
+        );
+    }
+
+    #[test]
+    fn test_config_builder_validation() {
+        let config = WatsonxConfig::builder("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_generate_timeout(30)
+            .build()
+            .unwrap();
+        assert_eq!(config.generate_timeout_secs, 30);
+
+        let err = WatsonxConfig::builder(String::new(), TEST_PROJECT_ID.to_string())
+            .with_api_url("http://localhost".to_string())
+            .with_generate_timeout(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Configuration error: api_key cannot be empty; api_url must begin with https://; generate_timeout_secs must be between 1 and 3600"
+        );
+
+        assert!(WatsonxConfig::builder("test_key".to_string(), String::new()).build().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "api_key cannot be empty")]
+    fn test_config_new_rejects_empty_api_key() {
+        WatsonxConfig::new(String::new(), TEST_PROJECT_ID.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "generate_timeout_secs must be between 1 and 3600")]
+    fn test_config_rejects_zero_timeout() {
+        WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string()).with_generate_timeout(0);
+    }
+
+    #[test]
+    fn test_generation_logprobs_request_and_response() {
+        let config = GenerationConfig::default().with_return_logprobs(2);
+        let params = serde_json::to_value(GenerationParams::from_config(&config, 1)).unwrap();
+        assert_eq!(params["return_options"]["token_logprobs"], true);
+        assert_eq!(params["return_options"]["top_n_tokens"], 2);
+
+        let params = serde_json::to_value(GenerationParams::from_config(&GenerationConfig::default(), 1)).unwrap();
+        assert!(params.get("return_options").is_none());
+
+        let data: GenerationData = serde_json::from_value(serde_json::json!({
+            "results": [{
+                "generated_text": "Hi",
+                "generated_token_count": 1,
+                "input_token_count": 3,
+                "generated_tokens": [
+                    {"text": "Hi", "logprob": -0.1, "top_tokens": [{"text": "Hi", "logprob": -0.1}, {"text": "Hey", "logprob": -2.5}]}
+                ]
+            }]
+        }))
+        .unwrap();
+        let result = RawGenerationData::from(data.results[0].clone()).into_generation_result("model".to_string());
+        let logprobs = result.token_logprobs.unwrap();
+        assert_eq!(logprobs[0].token, "Hi");
+        assert_eq!(logprobs[0].logprob, -0.1);
+        assert_eq!(logprobs[0].top_logprobs.as_ref().unwrap()[1], ("Hey".to_string(), -2.5));
+    }
+
+    #[test]
+    fn test_parse_json_output() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Person {
+            name: String,
+            age: u32,
+        }
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+            "required": ["name", "age"]
+        });
+
+        let person: Person = parse_json_output("```json\n{\"name\": \"Ada\", \"age\": 36}\n```", &schema).unwrap();
+        assert_eq!(person, Person { name: "Ada".to_string(), age: 36 });
+
+        let err = parse_json_output::<serde_json::Value>(r#"{"name": "Ada"}"#, &schema).unwrap_err();
+        assert_eq!(err, "missing required fields: age");
+
+        assert!(parse_json_output::<Person>("Sorry, I can't help with that.", &schema).is_err());
+
+        let prompt = json_prompt("Extract the person.", &schema);
+        assert!(prompt.starts_with("Extract the person."));
+        assert!(prompt.contains("\"required\""));
+    }
+
+    #[test]
+    fn test_temperature_sends_sample_decoding() {
+        let config = GenerationConfig::default().with_temperature(0.7);
+        let params = serde_json::to_value(GenerationParams::from_config(&config, 1)).unwrap();
+        assert_eq!(params["decoding_method"], "sample");
+        assert_eq!(params["temperature"].as_f64().unwrap() as f32, 0.7);
+
+        let config = GenerationConfig::default()
+            .with_decoding_method(DecodingMethod::Sample)
+            .with_top_k(20)
+            .with_top_p(0.9);
+        let params = serde_json::to_value(GenerationParams::from_config(&config, 1)).unwrap();
+        assert_eq!(params["top_k"], 20);
+        assert_eq!(params["top_p"].as_f64().unwrap() as f32, 0.9);
+
+        let params = serde_json::to_value(GenerationParams::from_config(&GenerationConfig::default(), 1)).unwrap();
+        assert_eq!(params["decoding_method"], "greedy");
+        assert!(params.get("temperature").is_none());
+        assert!(params.get("top_k").is_none());
+        assert!(params.get("top_p").is_none());
+    }
+
+    #[test]
+    fn test_generation_seed() {
+        let config = GenerationConfig::deterministic("ibm/granite-3-3-8b-instruct", 42);
+        assert_eq!(config.decoding_method, DecodingMethod::Sample);
+        let params = serde_json::to_value(GenerationParams::from_config(&config, 1)).unwrap();
+        assert_eq!(params["random_seed"], 42);
+        assert_eq!(params["decoding_method"], "sample");
+
+        let config = GenerationConfig::default().with_seed(7);
+        let params = serde_json::to_value(GenerationParams::from_config(&config, 1)).unwrap();
+        assert!(params.get("random_seed").is_none());
+    }
+
+    #[test]
+    fn test_generation_stop_reason() {
+        let parse = |reason: &str| {
+            let data: GenerationData = serde_json::from_value(serde_json::json!({
+                "results": [{"generated_text": "Hi", "stop_reason": reason}]
+            }))
+            .unwrap();
+            RawGenerationData::from(data.results[0].clone()).into_generation_result("model".to_string())
+        };
+
+        let result = parse("max_tokens");
+        assert_eq!(result.stop_reason, Some(StopReason::MaxTokens));
+        assert!(result.was_truncated());
+
+        let result = parse("eos_token");
+        assert_eq!(result.stop_reason, Some(StopReason::EosToken));
+        assert!(!result.was_truncated());
+
+        assert_eq!(parse("token_limit").stop_reason, Some(StopReason::Unknown("token_limit".to_string())));
+        assert!(parse("not_finished").stop_reason.is_none());
+
+        let mut output = RawGenerationData::default();
+        let line = r#"data: {"results":[{"generated_text":"Hi","generated_token_count":1,"stop_reason":"stop_sequence"}]}"#;
+        append_generation_line(&mut output, line, 64);
+        let line = r#"data: {"results":[{"generated_text":"","stop_reason":"not_finished"}]}"#;
+        append_generation_line(&mut output, line, 64);
+        let result = output.into_generation_result("model".to_string());
+        assert_eq!(result.stop_reason, Some(StopReason::StopSequence));
+        assert_eq!(result.output_tokens, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_credentials_keeps_old_credentials_on_failure() {
+        let config = WatsonxConfig::new("old_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_iam_url("127.0.0.1:1".to_string());
+        let mut client = WatsonxClient::new(config).unwrap();
+        client.set_token("old_token".to_string());
+
+        assert!(matches!(
+            client.rotate_credentials("  ".to_string()).await,
+            Err(Error::Authentication(_))
+        ));
+        assert!(matches!(
+            client.rotate_credentials("new_key".to_string()).await,
+            Err(Error::Authentication(_))
+        ));
+        assert_eq!(client.config.api_key, "old_key");
+        assert_eq!(client.get_token().as_deref(), Some("old_token"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion_batch() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_api_url("https://127.0.0.1:1".to_string());
+        let client = WatsonxClient::new(config).unwrap();
+        let conversations = vec![vec![ChatMessage::user("one")], vec![ChatMessage::user("two")]];
+
+        assert!(matches!(
+            client.chat_completion_batch(conversations.clone(), &ChatCompletionConfig::default()).await,
+            Err(Error::Authentication(_))
+        ));
+
+        // Nothing listens on the API URL, so each conversation fails on its own
+        client.set_token("token".to_string());
+        let requests = conversations.into_iter().map(ChatCompletionBatchRequest::new).collect();
+        let mut progress = Vec::new();
+        let results = client
+            .chat_completion_batch_with_progress(requests, &ChatCompletionConfig::default(), |done, total| {
+                progress.push((done, total))
+            })
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| matches!(r, Err(Error::Network(_)))));
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_generate_requires_authentication() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        let client = WatsonxClient::new(config).unwrap();
+
+        let result = client.spawn_generate("Hello", GenerationConfig::default()).await.unwrap();
+        assert!(matches!(result, Err(Error::Authentication(_))));
+
+        let requests = vec![
+            BatchRequest::new("one").with_id("1"),
+            BatchRequest::new("two").with_id("2"),
+        ];
+        let mut results = client.spawn_generate_batch_stream(requests, &GenerationConfig::default());
+        let mut ids = Vec::new();
+        while let Some(item) = results.recv().await {
+            assert!(item.is_failure());
+            ids.push(item.id.unwrap());
+        }
+        ids.sort();
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_generate_against_mock_server() {
+        use crate::mock_server::{MockResponse, MockServer};
+
+        // Echo each prompt back, failing the one asking for an error
+        let server = MockServer::with_handler(|request| {
+            let input = request.json()["input"].as_str().unwrap_or_default().to_string();
+            if input == "fail" {
+                return MockResponse::json(400, serde_json::json!({"errors": [{"message": "bad prompt"}]}));
+            }
+            MockResponse::json(
+                200,
+                serde_json::json!({"results": [{"generated_text": format!("echo {}", input), "stop_reason": "eos_token"}]}),
+            )
+        });
+        let client = WatsonxClient::for_mock_server(server.url());
+        let config = GenerationConfig::default().with_model("ibm/test-model");
+
+        let result = client.spawn_generate("Hello", config.clone()).await.unwrap().unwrap();
+        assert_eq!(result.text, "echo Hello");
+        assert_eq!(result.model_id, "ibm/test-model");
+
+        let requests = vec![
+            BatchRequest::new("one").with_id("1"),
+            BatchRequest::new("fail").with_id("2"),
+        ];
+        let mut results = client.spawn_generate_batch_stream(requests, &config);
+        let mut items = Vec::new();
+        while let Some(item) = results.recv().await {
+            items.push(item);
+        }
+        items.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(items[0].result.as_ref().unwrap().text, "echo one");
+        assert!(matches!(&items[1].error, Some(Error::HttpStatus { status: 400, .. })));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        for request in &requests {
+            assert!(request.path.starts_with("/ml/v1/text/generation?version="));
+            assert_eq!(request.json()["model_id"], "ibm/test-model");
+            assert_eq!(request.json()["project_id"], TEST_PROJECT_ID);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_raw_responses_against_mock_server() {
+        use crate::mock_server::{MockResponse, MockServer};
+        use crate::types::{ChatCompletionConfig, ChatMessage};
+        use serde_json::json;
+
+        // Fields the typed results drop must come back untouched
+        let generation = json!({
+            "model_id": "ibm/test-model",
+            "results": [{"generated_text": "Hi", "stop_reason": "eos_token", "seed": 7}],
+            "system": {"warnings": [{"id": "w1"}]}
+        });
+        let choices = json!([
+            {"index": 0, "message": {"role": "assistant", "content": "Hi"}, "logprobs": {"content": []}},
+            {"index": 1, "message": {"role": "assistant", "content": "Hello"}}
+        ]);
+        let generation_response = generation.clone();
+        let chat_response = json!({"id": "chat-1", "choices": choices.clone()});
+        let server = MockServer::with_handler(move |request| {
+            if request.path.contains("chat/completions") {
+                MockResponse::json(200, chat_response.clone())
+            } else {
+                MockResponse::json(200, generation_response.clone())
+            }
+        });
+        let client = WatsonxClient::for_mock_server(server.url());
+
+        let config = GenerationConfig::default().with_model("ibm/test-model");
+        assert_eq!(client.generate_raw("Hello", &config).await.unwrap(), generation);
+
+        let config = ChatCompletionConfig::default().with_model("ibm/chat-model");
+        let raw = client
+            .chat_completion_raw(vec![ChatMessage::user("Hello")], &config)
+            .await
+            .unwrap();
+        assert_eq!(serde_json::Value::Array(raw), choices);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].path.starts_with("/ml/v1/text/generation?version="));
+        assert_eq!(requests[0].json()["input"], "Hello");
+        assert_eq!(requests[0].json()["model_id"], "ibm/test-model");
+        assert_eq!(requests[0].json()["project_id"], TEST_PROJECT_ID);
+        assert_eq!(requests[1].path, "/ml/gateway/v1/chat/completions");
+        assert_eq!(requests[1].json()["model"], "ibm/chat-model");
+        assert_eq!(requests[1].json()["messages"][0]["content"], "Hello");
+        assert_eq!(requests[1].header("authorization"), Some("Bearer test-token"));
+    }
+
+    #[tokio::test]
+    async fn test_access_token_shared_between_clones() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        let client = Arc::new(WatsonxClient::new(config).unwrap());
+        let clone = (*client).clone();
+        assert!(clone.ensure_token_valid().await.is_err());
+
+        let shared = Arc::clone(&client);
+        tokio::spawn(async move { shared.set_token("token".to_string()) }).await.unwrap();
+        assert_eq!(client.ensure_token_valid().await.unwrap(), "token");
+        assert_eq!(clone.get_token().as_deref(), Some("token"));
+    }
+
+    #[tokio::test]
+    async fn test_expiring_token_is_refreshed() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_iam_url("127.0.0.1:1".to_string());
+        let client = WatsonxClient::new(config).unwrap();
+
+        client.store_token(IssuedToken {
+            token: "fresh".to_string(),
+            expires_at: Some(Instant::now() + Duration::from_secs(3600)),
+        });
+        assert_eq!(client.ensure_token_valid().await.unwrap(), "fresh");
+
+        // Within the refresh margin the client goes back to the token endpoint
+        client.store_token(IssuedToken {
+            token: "stale".to_string(),
+            expires_at: Some(Instant::now() + Duration::from_secs(30)),
+        });
+        let err = client.ensure_token_valid().await.unwrap_err();
+        assert!(matches!(err, Error::Network(_)));
+        assert_eq!(client.get_token().as_deref(), Some("stale"));
+
+        let response: TokenResponse =
+            serde_json::from_str(r#"{"access_token": "abc", "expires_in": 3600, "token_type": "Bearer"}"#).unwrap();
+        assert_eq!(response.expires_in, Some(3600));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_retry_backs_off_retryable_errors() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        let client = WatsonxClient::new(config).unwrap();
+        let retry = RetryConfig::new(3)
+            .with_retry_delay(Duration::from_millis(1))
+            .with_backoff_multiplier(3.0);
+
+        let calls = std::sync::atomic::AtomicU32::new(0);
+        let (value, stats) = client
+            .execute_with_retry(Some(&retry), || async {
+                match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 | 1 => Err(Error::Timeout("slow".to_string())),
+                    _ => Ok("done"),
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, "done");
+        let stats = stats.unwrap();
+        assert_eq!(stats.attempts, 3);
+        assert_eq!(stats.total_wait, Duration::from_millis(4));
+
+        // Non-retryable errors are returned straight away
+        calls.store(0, std::sync::atomic::Ordering::SeqCst);
+        let err = client
+            .execute_with_retry(Some(&retry), || async {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Err::<(), _>(Error::Authentication("bad key".to_string()))
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Authentication(_)));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_embedding_batches_are_concatenated() {
+        let mut result = EmbeddingResult {
+            input_texts: Some(Vec::new()),
+            ..Default::default()
+        };
+
+        let first: EmbeddingResponse = serde_json::from_str(
+            r#"{"model_id": "ibm/slate-30m-english-rtrvr", "results": [{"embedding": [0.1, 0.2], "input": {"text": "a"}}, {"embedding": [0.3, 0.4]}], "input_token_count": 7, "created_at": "2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        first.append_to(&mut result, 2).unwrap();
+
+        let second: EmbeddingResponse = serde_json::from_str(
+            r#"{"model_id": "ibm/slate-30m-english-rtrvr", "results": [{"embedding": [0.5, 0.6]}], "input_token_count": 3}"#,
+        )
+        .unwrap();
+        second.append_to(&mut result, 1).unwrap();
+
+        assert_eq!(result.embeddings, vec![vec![0.1, 0.2], vec![0.3, 0.4], vec![0.5, 0.6]]);
+        assert_eq!(result.input_texts.as_deref(), Some(&["a".to_string(), String::new(), String::new()][..]));
+        assert_eq!(result.input_token_counts, vec![7, 3]);
+        assert_eq!(result.total_input_tokens(), 10);
+        assert_eq!(result.created_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+
+        let short: EmbeddingResponse =
+            serde_json::from_str(r#"{"model_id": "m", "results": [], "input_token_count": 0}"#).unwrap();
+        assert!(matches!(short.append_to(&mut EmbeddingResult::default(), 1), Err(Error::Api(_))));
+    }
+
+    #[test]
+    fn test_models_response_page_cursor() {
+        let response: ModelsResponse = serde_json::from_str(
+            r#"{"total_count": 3, "limit": 2, "first": {"href": "https://us-south.ml.cloud.ibm.com/ml/v1/foundation_model_specs?version=2023-05-29&limit=2"}, "next": {"href": "https://us-south.ml.cloud.ibm.com/ml/v1/foundation_model_specs?version=2023-05-29&limit=2&start=g1AAAA%3D%3D"}, "resources": [{"model_id": "ibm/a", "provider": "IBM", "lifecycle": [{"id": "available"}]}, {"model_id": "ibm/b"}]}"#,
+        )
+        .unwrap();
+        let page = response.into_page();
+        assert_eq!(page.models.len(), 2);
+        assert_eq!(page.models[0].available, Some(true));
+        assert_eq!(page.total_count, 3);
+        assert_eq!(page.next_cursor.as_deref(), Some("g1AAAA=="));
+
+        let last: ModelsResponse =
+            serde_json::from_str(r#"{"total_count": 3, "resources": [{"model_id": "ibm/c"}]}"#).unwrap();
+        assert!(last.into_page().next_cursor.is_none());
+
+        assert_eq!(page_cursor("/ml/v1/foundation_model_specs?start=abc").as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_model_spec_details() {
+        let spec: ModelSpec = serde_json::from_str(
+            r#"{"model_id": "ibm/granite-13b-instruct-v2", "label": "granite-13b-instruct-v2", "provider": "IBM", "number_params": "13b", "min_shot_size": 0, "model_limits": {"max_sequence_length": 8192}, "versions": [{"version": "1.0.0", "available_date": "2023-12-01"}, {"version": "2.1.0", "available_date": "2024-02-15"}], "training_parameters": {"num_epochs": {"default": 20, "min": 1, "max": 50}}, "functions": [{"id": "text_generation"}]}"#,
+        )
+        .unwrap();
+        let info: crate::types::ModelInfo = spec.into();
+        assert_eq!(info.max_context_length, Some(8192));
+        assert_eq!(info.version.as_deref(), Some("2.1.0"));
+        assert_eq!(info.min_shot_size, Some(0));
+        assert_eq!(info.number_params.as_deref(), Some("13b"));
+        assert_eq!(info.training_parameters.unwrap()["num_epochs"]["max"], 50);
+        assert_eq!(info.supported_tasks, Some(vec!["text_generation".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_text_deltas() {
+        let body = concat!(
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+            "data: [DONE]\n",
+        );
+        let chunks = futures::stream::iter([Ok(body.as_bytes().to_vec())]);
+        let deltas = text_deltas(WatsonxStream::<ChatChunk>::from_chunks(Box::pin(chunks)), |chunk| chunk.content);
+
+        let deltas: Vec<String> = deltas.map(|delta| delta.unwrap()).collect().await;
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+    }
+
+    #[test]
+    fn test_tokenization_response() {
+        let response: TokenizationResponse = serde_json::from_str(
+            r#"{"model_id": "ibm/granite-3-8b-instruct", "result": {"token_count": 3, "tokens": ["Hello", ",", " world"], "token_ids": [1, 2, 3]}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.result.token_count, 3);
+        assert_eq!(response.result.tokens.unwrap()[2], " world");
+        assert_eq!(response.result.token_ids, Some(vec![1, 2, 3]));
+
+        let response: TokenizationResponse =
+            serde_json::from_str(r#"{"model_id": "m", "result": {"token_count": 7}}"#).unwrap();
+        assert_eq!(response.result, TokenizationResult { token_count: 7, ..Default::default() });
+    }
+
+    #[test]
+    fn test_rerank_response_is_ranked() {
+        let documents = ["Paris is in France", "Rust is a language", "Berlin is in Germany"];
+        let response: RerankResponse = serde_json::from_str(
+            r#"{"model_id": "cross-encoder/ms-marco-minilm-l-12-v2", "results": [{"index": 2, "score": 0.2}, {"index": 0, "score": 0.9}], "input_token_count": 42, "created_at": "2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+
+        let result = response.into_rerank_result(&documents).unwrap();
+        assert_eq!(result.results.len(), 2);
+        assert_eq!(result.results[0].index, 0);
+        assert_eq!(result.results[0].text, "Paris is in France");
+        assert_eq!(result.results[0].relevance_score, 0.9);
+        assert_eq!(result.results[1].index, 2);
+        assert_eq!(result.input_token_count, Some(42));
+
+        let out_of_range: RerankResponse =
+            serde_json::from_str(r#"{"model_id": "m", "results": [{"index": 5, "score": 0.1}]}"#).unwrap();
+        assert!(matches!(out_of_range.into_rerank_result(&documents), Err(Error::Api(_))));
+    }
+
+    #[tokio::test]
+    async fn test_lazy_connect_authenticates_on_first_request() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_iam_url("https://127.0.0.1:1".to_string());
+        let client = WatsonxClient::new(config.clone()).unwrap();
+        let err = client.list_models().await.unwrap_err();
+        assert!(err.to_string().contains("Call connect() first"));
+
+        // With lazy_connect the request tries to fetch a token instead
+        let client = WatsonxClient::new(config.with_lazy_connect(true)).unwrap();
+        let err = client.list_models().await.unwrap_err();
+        assert!(!err.to_string().contains("Call connect() first"));
+        assert!(client.get_token().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_stream_cancellable() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        let client = WatsonxClient::new(config).unwrap();
+        client.set_token("token".to_string());
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = client
+            .generate_text_stream_cancellable("Hello", &GenerationConfig::default(), |_| {}, cancel)
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_stream_cancellable_against_mock_server() {
+        use crate::mock_server::{MockResponse, MockServer};
+        use std::sync::Mutex as StdMutex;
+
+        const EVENTS: &str = concat!(
+            "data: {\"results\":[{\"generated_text\":\"Hello\"}]}\n\n",
+            "data: {\"results\":[{\"generated_text\":\" there\",\"stop_reason\":\"eos_token\"}]}\n\n",
+        );
+        // Prompts starting with "slow" only get an answer after a long pause
+        let server = MockServer::with_handler(|request| {
+            if request.json()["input"].as_str().is_some_and(|input| input.starts_with("slow")) {
+                std::thread::sleep(Duration::from_secs(2));
+            }
+            MockResponse::text(200, "text/event-stream", EVENTS)
+        });
+        let client = WatsonxClient::for_mock_server(server.url());
+        let config = GenerationConfig::default();
+
+        // Left alone, it behaves like generate_text_stream
+        let chunks = StdMutex::new(Vec::new());
+        let result = client
+            .generate_text_stream_cancellable(
+                "Hi",
+                &config,
+                |chunk| chunks.lock().unwrap().push(chunk.to_string()),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.text, "Hello there");
+        assert_eq!(chunks.into_inner().unwrap(), vec!["Hello", " there"]);
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+        let started = Instant::now();
+        let result = client
+            .generate_text_stream_cancellable("slow prompt", &config, |_| {}, cancel)
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let requests = server.requests();
+        assert!(requests[0].path.starts_with("/ml/v1/text/generation_stream?version="));
+        assert_eq!(requests[0].json()["input"], "Hi");
+    }
+
+    #[test]
+    fn test_raw_generation_data() {
+        let error = RawGenerationData::default().non_empty("model", 5).err().unwrap();
+        assert!(error.is_empty_generation());
+        assert!(matches!(error, Error::EmptyGeneration { prompt_length: 5, .. }));
+
+        let mut output = RawGenerationData::default();
+        output.push(&GenerationChunk::parse_line(r#"data: {"results":[{"generated_text":"Hi","generated_token_count":1,"input_token_count":2}]}"#).unwrap());
+        output.push(&GenerationChunk::parse_line(r#"data: {"results":[{"generated_text":"!","stop_reason":"eos_token"}]}"#).unwrap());
+        let result = output.non_empty("model", 5).unwrap().into_generation_result("model".to_string());
+        assert_eq!(result.text, "Hi!");
+        assert_eq!(result.stop_reason, Some(StopReason::EosToken));
+    }
+
+    #[tokio::test]
+    async fn test_forward_generation_chunks() {
+        let lines = [
+            r#"data: {"results":[{"generated_text":"Hello","generated_token_count":1,"input_token_count":3,"stop_reason":"not_finished"}]}"#,
+            r#"data: {"results":[{"generated_text":" world","generated_token_count":2,"input_token_count":3,"stop_reason":"eos_token"}]}"#,
+        ];
+        let chunks: ByteChunks = Box::pin(futures::stream::iter(
+            lines.map(|line| Ok(format!("{}\n", line).into_bytes())),
+        ));
+        let (sender, mut receiver) = mpsc::channel(1);
+
+        let consumer = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(chunk) = receiver.recv().await {
+                received.push(chunk);
+            }
+            received
+        });
+        let result = forward_generation_chunks(chunks, "model".to_string(), 5, 1024, "req-1".to_string(), sender)
+            .await
+            .unwrap();
+
+        assert_eq!(consumer.await.unwrap(), vec!["Hello", " world"]);
+        assert_eq!(result.text, "Hello world");
+        assert_eq!(result.stop_reason, Some(StopReason::EosToken));
+        assert_eq!(result.output_tokens, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_get_model_info_encodes_model_id() {
+        use crate::mock_server::{MockResponse, MockServer};
+
+        let server = MockServer::start(vec![MockResponse::json(
+            404,
+            serde_json::json!({"errors": [{"code": "model_not_found"}]}),
+        )]);
+        let client = WatsonxClient::for_mock_server(server.url());
+
+        let error = client.get_model_info("ibm/granite 3").await.err().unwrap();
+        assert!(matches!(error, Error::ModelNotFound(id) if id == "ibm/granite 3"));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].path.starts_with("/ml/v1/foundation_model_specs/ibm%2Fgranite%203?version="));
+    }
+
+    #[test]
+    fn test_sse_line_limit() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_sse_buffer(crate::config::SseBufferConfig::default().with_max_line_bytes(2048));
+        let client = WatsonxClient::new(config).unwrap();
+
+        assert_eq!(client.sse_line_limit(&GenerationConfig::default()), 2048);
+        let config = GenerationConfig::default().with_max_sse_line_bytes(4096);
+        assert_eq!(client.sse_line_limit(&config), 4096);
+    }
+
+    #[tokio::test]
+    async fn test_collect_generation_stream_timeout() {
+        const LINE_LIMIT: usize = 64 * 1024;
+        let line = r#"data: {"results":[{"generated_text":"Partial answer","generated_token_count":2,"stop_reason":"not_finished"}]}"#;
+        let stalled = || -> ByteChunks {
+            Box::pin(
+                futures::stream::iter([Ok(format!("{}\n", line).into_bytes())]).chain(futures::stream::pending()),
+            )
+        };
+        let deadline = || tokio::time::Instant::now() + std::time::Duration::from_millis(50);
+
+        let config = GenerationConfig::default();
+        let output = collect_generation_stream(stalled(), &config, LINE_LIMIT, 5, deadline()).await.unwrap();
+        let result = output.into_generation_result("model".to_string());
+        assert_eq!(result.text, "Partial answer");
+        assert!(result.truncated);
+
+        let config = GenerationConfig::default().with_on_timeout(TimeoutBehavior::ReturnError);
+        let error = collect_generation_stream(stalled(), &config, LINE_LIMIT, 5, deadline()).await.err().unwrap();
+        assert!(matches!(error, Error::Timeout(_)));
+
+        let unterminated: ByteChunks = Box::pin(futures::stream::repeat_with(|| Ok(vec![b'x'; 1024])));
+        let error = collect_generation_stream(unterminated, &GenerationConfig::default(), 4096, 5, deadline()).await.err().unwrap();
+        assert!(matches!(error, Error::Api(message) if message.contains("SSE line too large")));
+
+        // A complete line is held to the same limit as a partial one
+        let oversized = format!("data: {}\n", serde_json::json!({"results": [{"generated_text": "x".repeat(8192)}]}));
+        let terminated: ByteChunks = Box::pin(futures::stream::iter([Ok(oversized.into_bytes())]));
+        let error = collect_generation_stream(terminated, &GenerationConfig::default(), 4096, 5, deadline()).await.err().unwrap();
+        assert!(matches!(error, Error::Api(message) if message.contains("SSE line too large")));
+
+        let nothing: ByteChunks = Box::pin(futures::stream::pending());
+        let error = collect_generation_stream(nothing, &GenerationConfig::default(), LINE_LIMIT, 5, deadline()).await.err().unwrap();
+        assert!(matches!(error, Error::Timeout(_)));
+    }
+
+    #[test]
+    fn test_stream_deduplication() {
+        let line = |text: &str| format!("data: {}", serde_json::json!({"results": [{"generated_text": text}]}));
+        let accumulate = |chunks: &[&str], window: usize| {
+            let mut output = RawGenerationData::default();
+            for chunk in chunks {
+                append_generation_line(&mut output, &line(chunk), window);
+            }
+            output.text
+        };
+
+        // Final chunk repeats the whole generated text
+        let chunks = ["The quick", " brown fox", " jumps.", "The quick brown fox jumps."];
+        assert_eq!(accumulate(&chunks, 64), "The quick brown fox jumps.");
+        assert_eq!(accumulate(&chunks, 0), "The quick brown fox jumps.The quick brown fox jumps.");
+
+        // Off by default
+        assert_eq!(GenerationConfig::default().stream_dedup_window, 0);
+
+        // Legitimately repeated tokens survive
+        assert_eq!(accumulate(&["very", " very", " very", " good"], 64), "very very very good");
+        assert_eq!(accumulate(&["def f():\n", "    ", "    ", "pass"], 64), "def f():\n        pass");
+        assert_eq!(accumulate(&["Hello", " world", " world"], 64), "Hello world world");
+
+        // Short answers are never treated as resent
+        assert_eq!(accumulate(&["ha", "ha", "!", "!"], 64), "haha!!");
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_stream_flushed_skips_duplicates() {
+        use crate::mock_server::{MockResponse, MockServer};
+
+        let event = |text: &str| format!("data: {}\n\n", serde_json::json!({"results": [{"generated_text": text}]}));
+        let body = ["Hello", " world", " world", "Hello world world"].map(event).concat();
+        let server = MockServer::with_handler(move |_| MockResponse::text(200, "text/event-stream", body.clone()));
+        let client = WatsonxClient::for_mock_server(server.url());
+
+        let config = GenerationConfig::default().with_stream_dedup_window(64);
+        let mut written = Vec::new();
+        let result = client
+            .generate_text_stream_flushed("Say hello", &config, &mut written)
+            .await
+            .unwrap();
+        assert_eq!(result.text, "Hello world world");
+        assert_eq!(String::from_utf8(written).unwrap(), "Hello world world");
+
+        let mut written = Vec::new();
+        client
+            .generate_text_stream_flushed("Say hello", &GenerationConfig::default(), &mut written)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), "Hello world worldHello world world");
+    }
+
+    #[test]
+    fn test_config_timeouts() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        assert_eq!(config.connect_timeout(), Duration::from_secs(10));
+        assert_eq!(config.generate_timeout(), Duration::from_secs(120));
+
+        #[allow(deprecated)]
+        let config = config.with_timeout(30);
+        assert_eq!(config.connect_timeout_secs, 30);
+        assert_eq!(config.generate_timeout_secs, 30);
+
+        #[allow(deprecated)]
+        {
+            let mut config = config;
+            config.set_timeout_secs(45);
+            assert_eq!(config.timeout_secs(), 45);
+            assert_eq!(config.generate_timeout_secs, 45);
+            assert_eq!(config.connect_timeout_secs, 45);
+        }
+
+        let legacy: WatsonxConfig = serde_json::from_value(serde_json::json!({
+            "api_key": "test_key",
+            "project_id": TEST_PROJECT_ID,
+            "iam_url": "iam.cloud.ibm.com",
+            "api_url": "https://us-south.ml.cloud.ibm.com",
+            "api_version": "2023-05-29",
+            "timeout_secs": 300
+        }))
+        .unwrap();
+        assert_eq!(legacy.connect_timeout_secs, 300);
+        assert_eq!(legacy.generate_timeout_secs, 300);
+
+        // Explicit timeouts win over the legacy key, and missing ones use the defaults
+        let config: WatsonxConfig = serde_json::from_value(serde_json::json!({
+            "api_key": "test_key",
+            "project_id": TEST_PROJECT_ID,
+            "iam_url": "iam.cloud.ibm.com",
+            "api_url": "https://us-south.ml.cloud.ibm.com",
+            "api_version": "2023-05-29",
+            "timeout_secs": 300,
+            "connect_timeout_secs": 15
+        }))
+        .unwrap();
+        assert_eq!(config.connect_timeout_secs, 15);
+        assert_eq!(config.generate_timeout_secs, 300);
+        let round_trip: WatsonxConfig = serde_json::from_value(serde_json::to_value(&config).unwrap()).unwrap();
+        assert_eq!(round_trip.connect_timeout_secs, 15);
+    }
+
+    #[test]
+    fn test_pinned_certificate_rejects_invalid_der() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_pinned_certificate(b"not a certificate".to_vec());
+        assert!(matches!(WatsonxClient::new(config), Err(Error::Configuration(_))));
+
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string())
+            .with_pinned_certificate_only(true);
         assert!(config.validate().is_err());
+    }
 
-        let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
-        assert!(config.validate().is_ok());
+    #[test]
+    fn test_generation_results_token_usage() {
+        let json = r#"{"results": [{"generated_text": "Hi", "input_token_count": 12, "generated_token_count": 3}]}"#;
+        let data: GenerationData = serde_json::from_str(json).unwrap();
+        let result = RawGenerationData::from(data.results.into_iter().next().unwrap()).into_generation_result("model".to_string());
+
+        assert_eq!(result.text, "Hi");
+        assert_eq!(result.input_tokens, Some(12));
+        assert_eq!(result.output_tokens, Some(3));
+        assert_eq!(result.tokens_used, Some(15));
+        assert_eq!(result.token_usage.unwrap().total_tokens(), 15);
+
+        let json = r#"{"results": [{"generated_text": "Hi"}]}"#;
+        let data: GenerationData = serde_json::from_str(json).unwrap();
+        let result = RawGenerationData::from(data.results.into_iter().next().unwrap()).into_generation_result("model".to_string());
+        assert!(result.tokens_used.is_none());
+        assert!(result.token_usage.is_none());
+
+        // Streamed counts come from the last event that reports them
+        let mut raw = RawGenerationData::default();
+        for line in [
+            r#"data: {"results":[{"generated_text":"Hel","generated_token_count":1,"input_token_count":4}]}"#,
+            r#"data: {"results":[{"generated_text":"lo","generated_token_count":2,"stop_reason":"eos_token"}]}"#,
+        ] {
+            raw.push(&GenerationChunk::parse_line(line).unwrap());
+        }
+        let usage = raw.into_generation_result("model".to_string()).token_usage.unwrap();
+        assert_eq!(usage, TokenUsage { input_tokens: 4, generated_tokens: 2 });
+    }
+
+    #[test]
+    fn test_should_fall_back() {
+        let status = |status| Error::HttpStatus { status, message: "failed".to_string() };
+        assert!(WatsonxClient::should_fall_back(&Error::RateLimit("busy".to_string())));
+        assert!(WatsonxClient::should_fall_back(&Error::ModelNotFound("m".to_string())));
+        assert!(WatsonxClient::should_fall_back(&status(503)));
+        assert!(!WatsonxClient::should_fall_back(&status(400)));
+        // Only the status decides, not what the message says
+        assert!(!WatsonxClient::should_fall_back(&Error::Api("HTTP 503".to_string())));
+        assert!(!WatsonxClient::should_fall_back(&Error::Authentication("no".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_fallback_on_unavailable_model() {
+        use crate::mock_server::{MockResponse, MockServer};
+
+        let server = MockServer::start(vec![
+            MockResponse::json(503, serde_json::json!({"errors": [{"message": "overloaded"}]})),
+            MockResponse::text(
+                200,
+                "text/event-stream",
+                "data: {\"results\":[{\"generated_text\":\"Hi\",\"stop_reason\":\"eos_token\"}]}\n\n",
+            ),
+        ]);
+        let client = WatsonxClient::for_mock_server(server.url());
+        let primary = GenerationConfig::default().with_model("primary/model");
+        let fallback = GenerationConfig::default().with_model("fallback/model");
+
+        let result = client.generate_with_fallback("Hello", &primary, &[fallback]).await.unwrap();
+        assert_eq!(result.model_id, "fallback/model");
+        let models: Vec<_> = server.requests().iter().map(|r| r.json()["model_id"].clone()).collect();
+        assert_eq!(models, vec!["primary/model", "fallback/model"]);
+
+        // A rejected request is not retried on another model
+        let server = MockServer::start(vec![MockResponse::json(400, serde_json::json!({}))]);
+        let client = WatsonxClient::for_mock_server(server.url());
+        let error = client.generate_with_fallback("Hello", &primary, &[GenerationConfig::default().with_model("fallback/model")]).await.err().unwrap();
+        assert!(matches!(error, Error::HttpStatus { status: 400, .. }));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_generate_stream_matches_generate_text_stream() {
+        use crate::mock_server::{MockResponse, MockServer};
+
+        const EVENTS: &str = concat!(
+            "id: 1\nevent: message\ndata: {\"results\":[{\"generated_text\":\"Rust is\",\"stop_reason\":\"not_finished\"}]}\n\n",
+            "id: 2\nevent: message\ndata: {\"results\":[{\"generated_text\":\"\",\"stop_reason\":\"not_finished\"}]}\n\n",
+            "id: 3\nevent: message\ndata: {\"results\":[{\"generated_text\":\" fast and\",\"stop_reason\":\"not_finished\"}]}\n\n",
+            "id: 4\nevent: message\ndata: {\"results\":[{\"generated_text\":\" safe.\",\"stop_reason\":\"eos_token\"}]}\n\n",
+        );
+        let server = MockServer::with_handler(|_| MockResponse::text(200, "text/event-stream", EVENTS));
+        let client = WatsonxClient::for_mock_server(server.url());
+        let config = GenerationConfig::default();
+
+        // Empty chunks are skipped, so every item carries text
+        let chunks: Vec<String> = client
+            .generate_stream("Describe Rust", &config)
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks, vec!["Rust is", " fast and", " safe."]);
+
+        let result = client.generate_text_stream("Describe Rust", &config, |_| {}).await.unwrap();
+        assert_eq!(chunks.concat(), result.text);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].path.starts_with("/ml/v1/text/generation_stream?version="));
+        assert_eq!(requests[0].json()["input"], "Describe Rust");
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_recorded_from_rejected_request() {
+        use crate::mock_server::{MockResponse, MockServer};
+
+        let server = MockServer::start(vec![
+            MockResponse::json(429, serde_json::json!({"errors": [{"message": "Too many requests"}]}))
+                .with_header("X-Ratelimit-Remaining", "0")
+                .with_header("X-Ratelimit-Reset", "30"),
+        ]);
+        let client = WatsonxClient::for_mock_server(server.url());
+        assert!(client.rate_limit_state().is_none());
+
+        assert!(client.list_models_page(None, Some(1)).await.is_err());
+        let state = client.rate_limit_state().unwrap();
+        assert_eq!(state.remaining, 0);
+        assert!(state.is_exhausted());
+    }
+
+    #[test]
+    fn test_extract_code_block() {
+        let (tag, code) = extract_code_block("```rust\nfn one() -> u8 {\n    1\n}\n```\nEnjoy!");
+        assert_eq!(tag, Some("rust"));
+        assert_eq!(code, "fn one() -> u8 {\n    1\n}");
+        assert_eq!(code_language_confidence(tag, code, &ProgrammingLanguage::Rust), 1.0);
+        assert_eq!(code_language_confidence(tag, code, &ProgrammingLanguage::Python), 0.2);
+
+        // The prompt opens the block, so the answer often only closes it
+        let (tag, code) = extract_code_block("def one():\n    return None\n```\n");
+        assert_eq!((tag, code), (None, "def one():\n    return None"));
+        assert!(code_language_confidence(tag, code, &ProgrammingLanguage::Python) > 0.5);
+        assert_eq!(code_language_confidence(tag, code, &ProgrammingLanguage::Sql), 0.0);
+
+        // A second block after the closing fence is not mistaken for the answer
+        let (tag, code) = extract_code_block("fn a() {}\n```\nUsage:\n```rust\na();\n```");
+        assert_eq!((tag, code), (None, "fn a() {}"));
+
+        assert_eq!(extract_code_block("  SELECT 1;  "), (None, "SELECT 1;"));
+    }
+
+    #[test]
+    fn test_code_prompt() {
+        let config = CodeGenConfig::new().with_tests(true).with_max_functions(2);
+        let prompt = code_prompt("Reverse a string", &ProgrammingLanguage::Go, &config);
+        assert!(prompt.starts_with("You are an expert Go programmer."));
+        assert!(prompt.contains("Do not add comments.\nInclude unit tests for the code.\nWrite at most 2 functions."));
+        assert!(prompt.contains("func add(a, b int) int"));
+        assert!(prompt.ends_with("Specification: Reverse a string\n```go\n"));
+
+        let other = code_prompt("Print hello", &ProgrammingLanguage::Other("Haskell".to_string()), &config);
+        assert!(!other.contains("Add two integers"));
+        assert!(other.ends_with("```haskell\n"));
+    }
+
+    #[test]
+    fn test_chat_completion_endpoint_cache() {
+        let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
+        let client = WatsonxClient::new(config).unwrap();
+
+        let defaults = client.chat_completion_endpoints("2024-05-31");
+        assert!(defaults[0].ends_with("/ml/gateway/v1/chat/completions"));
+
+        client.cache_endpoint(&defaults[1]);
+        let cached = client.clone().chat_completion_endpoints("2024-05-31");
+        assert_eq!(cached, vec![defaults[1].clone(), defaults[0].clone()]);
+
+        // A cached URL for a different API version is not a candidate, so the order is unchanged
+        assert!(client.chat_completion_endpoints("2025-01-01")[0].ends_with("/ml/gateway/v1/chat/completions"));
+
+        client.clear_endpoint_cache();
+        assert_eq!(client.chat_completion_endpoints("2024-05-31"), defaults);
     }
 }
 
 #[cfg(test)]
 mod batch_tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_batch_request_creation() {
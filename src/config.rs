@@ -4,9 +4,135 @@ use crate::error::{Error, Result};
 use crate::models::{DEFAULT_API_URL, DEFAULT_IAM_URL};
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::fmt;
+use std::time::Duration;
+
+/// Maximum allowed request timeout in seconds
+const MAX_TIMEOUT_SECS: u64 = 3600;
+
+/// Default timeout for establishing connections and fetching IAM tokens
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Default timeout for generation requests
+const DEFAULT_GENERATE_TIMEOUT_SECS: u64 = 120;
+
+fn default_connect_timeout_secs() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT_SECS
+}
+
+fn default_generate_timeout_secs() -> u64 {
+    DEFAULT_GENERATE_TIMEOUT_SECS
+}
+
+/// Panic with the validation message if a timeout is out of range
+fn assert_timeout_in_range(field: &str, timeout_secs: u64) {
+    assert!(
+        (1..=MAX_TIMEOUT_SECS).contains(&timeout_secs),
+        "{} must be between 1 and {}, got {}",
+        field,
+        MAX_TIMEOUT_SECS,
+        timeout_secs
+    );
+}
+
+/// Parse the contents of a `.env` file into `(key, value)` pairs
+///
+/// Accepts `KEY=VALUE` lines with an optional `export ` prefix. Blank lines and `#`
+/// comments are skipped, and values may be wrapped in single or double quotes.
+#[cfg(feature = "env-file")]
+fn parse_env_file(contents: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=VALUE", number + 1))?;
+
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("line {}: invalid variable name '{}'", number + 1, key));
+        }
+
+        let value = value.trim();
+        let value = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..]
+                .strip_suffix(quote)
+                .ok_or_else(|| format!("line {}: unterminated quoted value for {}", number + 1, key))?,
+            _ => value.split_once(" #").map_or(value, |(value, _)| value.trim_end()),
+        };
+        vars.push((key.to_string(), value.to_string()));
+    }
+    Ok(vars)
+}
+
+/// A validation problem with a single configuration field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Name of the invalid field
+    pub field: String,
+    /// What is wrong with the field
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// Default maximum size of a single SSE line (1 MiB)
+const DEFAULT_SSE_MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Limits on buffering when parsing streaming (SSE) responses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SseBufferConfig {
+    /// Maximum size of a single SSE line; larger lines fail with `Error::Api`
+    ///
+    /// `GenerationConfig::max_sse_line_bytes` overrides this for a single text generation request.
+    pub max_line_bytes: usize,
+    /// Maximum accumulated text; the response is truncated at this size
+    pub max_total_bytes: Option<usize>,
+}
+
+impl Default for SseBufferConfig {
+    fn default() -> Self {
+        Self {
+            max_line_bytes: DEFAULT_SSE_MAX_LINE_BYTES,
+            max_total_bytes: None,
+        }
+    }
+}
+
+impl SseBufferConfig {
+    /// Set the maximum size of a single SSE line
+    pub fn with_max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = max_line_bytes;
+        self
+    }
+
+    /// Set the maximum accumulated text size
+    pub fn with_max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+}
 
 /// Configuration for WatsonX AI client
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "WatsonxConfigRepr")]
 pub struct WatsonxConfig {
     /// IBM Cloud API key for authentication
     pub api_key: String,
@@ -18,8 +144,69 @@ pub struct WatsonxConfig {
     pub api_url: String,
     /// API version
     pub api_version: String,
-    /// Default timeout for requests
-    pub timeout_secs: u64,
+    /// Timeout for establishing connections and fetching IAM tokens
+    pub connect_timeout_secs: u64,
+    /// Timeout for generation and other API requests
+    pub generate_timeout_secs: u64,
+    /// DER-encoded certificate trusted as an additional root for TLS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_certificate: Option<Vec<u8>>,
+    /// Trust only the pinned certificate, not the built-in CA bundle
+    pub pinned_certificate_only: bool,
+    /// Buffer limits for streaming responses
+    pub sse_buffer: SseBufferConfig,
+    /// Authenticate automatically on the first request instead of requiring `connect()`
+    pub lazy_connect: bool,
+}
+
+/// Wire format for `WatsonxConfig`, which also accepts the legacy `timeout_secs` key
+///
+/// `timeout_secs` sets both timeouts unless they are given explicitly.
+#[derive(Deserialize)]
+struct WatsonxConfigRepr {
+    api_key: String,
+    project_id: String,
+    iam_url: String,
+    api_url: String,
+    api_version: String,
+    #[serde(default)]
+    connect_timeout_secs: Option<u64>,
+    #[serde(default)]
+    generate_timeout_secs: Option<u64>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    pinned_certificate: Option<Vec<u8>>,
+    #[serde(default)]
+    pinned_certificate_only: bool,
+    #[serde(default)]
+    sse_buffer: SseBufferConfig,
+    #[serde(default)]
+    lazy_connect: bool,
+}
+
+impl From<WatsonxConfigRepr> for WatsonxConfig {
+    fn from(repr: WatsonxConfigRepr) -> Self {
+        Self {
+            api_key: repr.api_key,
+            project_id: repr.project_id,
+            iam_url: repr.iam_url,
+            api_url: repr.api_url,
+            api_version: repr.api_version,
+            connect_timeout_secs: repr
+                .connect_timeout_secs
+                .or(repr.timeout_secs)
+                .unwrap_or_else(default_connect_timeout_secs),
+            generate_timeout_secs: repr
+                .generate_timeout_secs
+                .or(repr.timeout_secs)
+                .unwrap_or_else(default_generate_timeout_secs),
+            pinned_certificate: repr.pinned_certificate,
+            pinned_certificate_only: repr.pinned_certificate_only,
+            sse_buffer: repr.sse_buffer,
+            lazy_connect: repr.lazy_connect,
+        }
+    }
 }
 
 impl WatsonxConfig {
@@ -28,9 +215,44 @@ impl WatsonxConfig {
         #[cfg(feature = "dotenv")]
         dotenvy::dotenv().ok();
 
-        let api_key = env::var("WATSONX_API_KEY")
-            .or_else(|_| env::var("API_KEY"))
-            .map_err(|_| {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Create configuration from a `.env` file, falling back to environment variables
+    ///
+    /// Reads the same variables as [`WatsonxConfig::from_env`]. Values in the file take
+    /// precedence over the process environment, which is left unchanged.
+    #[cfg(feature = "env-file")]
+    pub fn from_env_file(path: &std::path::Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            Error::Configuration(format!("Failed to read env file {}: {}", path.display(), e))
+        })?;
+        let vars = parse_env_file(&contents).map_err(|e| {
+            Error::Configuration(format!("Failed to parse env file {}: {}", path.display(), e))
+        })?;
+
+        let lookup = |name: &str| {
+            vars.iter()
+                .rev()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .or_else(|| env::var(name).ok())
+        };
+        Self::from_vars(lookup).map_err(|e| match e {
+            Error::Configuration(message) => {
+                Error::Configuration(format!("{} (while loading {})", message, path.display()))
+            }
+            other => other,
+        })
+    }
+
+    /// Create configuration from variables looked up by name
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let timeout_secs = |name: &str| var(name).and_then(|value| value.trim().parse::<u64>().ok());
+
+        let api_key = var("WATSONX_API_KEY")
+            .or_else(|| var("API_KEY"))
+            .ok_or_else(|| {
                 Error::Configuration(
                     "WATSONX_API_KEY or API_KEY environment variable not found".to_string(),
                 )
@@ -41,9 +263,9 @@ impl WatsonxConfig {
             ));
         }
 
-        let project_id = env::var("WATSONX_PROJECT_ID")
-            .or_else(|_| env::var("PROJECT_ID"))
-            .map_err(|_| {
+        let project_id = var("WATSONX_PROJECT_ID")
+            .or_else(|| var("PROJECT_ID"))
+            .ok_or_else(|| {
                 Error::Configuration(
                     "WATSONX_PROJECT_ID or PROJECT_ID environment variable not found".to_string(),
                 )
@@ -54,39 +276,75 @@ impl WatsonxConfig {
             ));
         }
 
-        let iam_url = env::var("IAM_IBM_CLOUD_URL")
-            .unwrap_or_else(|_| DEFAULT_IAM_URL.to_string());
+        let iam_url = var("IAM_IBM_CLOUD_URL")
+            .unwrap_or_else(|| DEFAULT_IAM_URL.to_string());
 
-        let api_url = env::var("WATSONX_API_URL")
-            .unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+        let api_url = var("WATSONX_API_URL")
+            .unwrap_or_else(|| DEFAULT_API_URL.to_string());
 
-        let api_version = env::var("WATSONX_API_VERSION")
-            .unwrap_or_else(|_| "2023-05-29".to_string());
+        let api_version = var("WATSONX_API_VERSION")
+            .unwrap_or_else(|| "2023-05-29".to_string());
 
-        let timeout_secs = env::var("WATSONX_TIMEOUT_SECS")
-            .unwrap_or_else(|_| "120".to_string())
-            .parse()
-            .unwrap_or(120);
+        // WATSONX_TIMEOUT_SECS is the legacy single timeout and applies to both
+        let legacy_timeout_secs = timeout_secs("WATSONX_TIMEOUT_SECS");
+        let connect_timeout_secs = timeout_secs("WATSONX_CONNECT_TIMEOUT")
+            .or(legacy_timeout_secs)
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+        let generate_timeout_secs = timeout_secs("WATSONX_GENERATE_TIMEOUT")
+            .or(legacy_timeout_secs)
+            .unwrap_or(DEFAULT_GENERATE_TIMEOUT_SECS);
 
-        Ok(Self {
+        let config = Self {
             api_key,
             project_id,
             iam_url,
             api_url,
             api_version,
-            timeout_secs,
-        })
+            connect_timeout_secs,
+            generate_timeout_secs,
+            pinned_certificate: None,
+            pinned_certificate_only: false,
+            sse_buffer: SseBufferConfig::default(),
+            lazy_connect: false,
+        };
+        config.validate()?;
+
+        Ok(config)
     }
 
     /// Create configuration with explicit values
+    ///
+    /// # Panics
+    ///
+    /// Panics if `api_key` or `project_id` is empty. Use [`WatsonxConfig::builder`]
+    /// to get an error instead.
     pub fn new(api_key: String, project_id: String) -> Self {
+        assert!(!api_key.trim().is_empty(), "api_key cannot be empty");
+        assert!(!project_id.trim().is_empty(), "project_id cannot be empty");
+        Self::with_credentials(api_key, project_id)
+    }
+
+    /// Start building a configuration that is validated by `build()`
+    pub fn builder(api_key: String, project_id: String) -> WatsonxConfigBuilder {
+        WatsonxConfigBuilder {
+            config: Self::with_credentials(api_key, project_id),
+        }
+    }
+
+    /// Configuration with default settings and unchecked credentials
+    fn with_credentials(api_key: String, project_id: String) -> Self {
         Self {
             api_key,
             project_id,
             iam_url: DEFAULT_IAM_URL.to_string(),
             api_url: DEFAULT_API_URL.to_string(),
             api_version: "2023-05-29".to_string(),
-            timeout_secs: 120,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            generate_timeout_secs: DEFAULT_GENERATE_TIMEOUT_SECS,
+            pinned_certificate: None,
+            pinned_certificate_only: false,
+            sse_buffer: SseBufferConfig::default(),
+            lazy_connect: false,
         }
     }
 
@@ -108,30 +366,245 @@ impl WatsonxConfig {
         self
     }
 
-    /// Set the timeout
-    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
-        self.timeout_secs = timeout_secs;
+    /// Set both the connect and generate timeouts
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout_secs` is not between 1 and 3600.
+    #[deprecated(note = "use `with_connect_timeout` and `with_generate_timeout`")]
+    pub fn with_timeout(self, timeout_secs: u64) -> Self {
+        self.with_connect_timeout(timeout_secs)
+            .with_generate_timeout(timeout_secs)
+    }
+
+    /// Set the timeout for establishing connections and fetching IAM tokens
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout_secs` is not between 1 and 3600.
+    pub fn with_connect_timeout(mut self, timeout_secs: u64) -> Self {
+        assert_timeout_in_range("connect_timeout_secs", timeout_secs);
+        self.connect_timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Set the timeout for generation requests
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout_secs` is not between 1 and 3600.
+    pub fn with_generate_timeout(mut self, timeout_secs: u64) -> Self {
+        assert_timeout_in_range("generate_timeout_secs", timeout_secs);
+        self.generate_timeout_secs = timeout_secs;
         self
     }
 
-    /// Validate the configuration
-    pub fn validate(&self) -> Result<()> {
+    /// Timeout for generation requests in seconds (the former single `timeout_secs` field)
+    #[deprecated(note = "use the `generate_timeout_secs` field")]
+    pub fn timeout_secs(&self) -> u64 {
+        self.generate_timeout_secs
+    }
+
+    /// Set both the connect and generation timeouts in seconds (the former single `timeout_secs` field)
+    #[deprecated(note = "set the `connect_timeout_secs` and `generate_timeout_secs` fields")]
+    pub fn set_timeout_secs(&mut self, timeout_secs: u64) {
+        self.connect_timeout_secs = timeout_secs;
+        self.generate_timeout_secs = timeout_secs;
+    }
+
+    /// Timeout for establishing connections and fetching IAM tokens
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+
+    /// Timeout for generation requests
+    pub fn generate_timeout(&self) -> Duration {
+        Duration::from_secs(self.generate_timeout_secs)
+    }
+
+    /// Pin a DER-encoded certificate as a trusted TLS root
+    ///
+    /// The certificate is trusted alongside the built-in CA bundle. Call
+    /// `with_pinned_certificate_only(true)` to trust it instead of the CA bundle, so
+    /// requests only succeed against endpoints presenting a chain to this certificate.
+    pub fn with_pinned_certificate(mut self, cert_der: Vec<u8>) -> Self {
+        self.pinned_certificate = Some(cert_der);
+        self
+    }
+
+    /// Pin a DER-encoded certificate read from a file
+    pub fn with_pinned_certificate_file(self, path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let cert_der = std::fs::read(path).map_err(|e| {
+            Error::Configuration(format!(
+                "Failed to read pinned certificate {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(self.with_pinned_certificate(cert_der))
+    }
+
+    /// Trust only the pinned certificate instead of the built-in CA bundle
+    pub fn with_pinned_certificate_only(mut self, only: bool) -> Self {
+        self.pinned_certificate_only = only;
+        self
+    }
+
+    /// Set the buffer limits for streaming responses
+    pub fn with_sse_buffer(mut self, sse_buffer: SseBufferConfig) -> Self {
+        self.sse_buffer = sse_buffer;
+        self
+    }
+
+    /// Authenticate automatically on the first request instead of requiring `connect()`
+    pub fn with_lazy_connect(mut self, lazy_connect: bool) -> Self {
+        self.lazy_connect = lazy_connect;
+        self
+    }
+
+    /// Collect every field-level validation problem in the configuration
+    pub fn validation_errors(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
         if self.api_key.trim().is_empty() {
-            return Err(Error::Configuration("API key cannot be empty".to_string()));
+            errors.push(ConfigError::new("api_key", "cannot be empty"));
         }
 
         if self.project_id.trim().is_empty() {
-            return Err(Error::Configuration("Project ID cannot be empty".to_string()));
+            errors.push(ConfigError::new("project_id", "cannot be empty"));
+        } else if uuid::Uuid::parse_str(self.project_id.trim()).is_err() {
+            errors.push(ConfigError::new("project_id", "must be a valid UUID format"));
         }
 
         if self.iam_url.trim().is_empty() {
-            return Err(Error::Configuration("IAM URL cannot be empty".to_string()));
+            errors.push(ConfigError::new("iam_url", "cannot be empty"));
         }
 
         if self.api_url.trim().is_empty() {
-            return Err(Error::Configuration("API URL cannot be empty".to_string()));
+            errors.push(ConfigError::new("api_url", "cannot be empty"));
+        } else if !self.api_url.starts_with("https://") {
+            errors.push(ConfigError::new("api_url", "must begin with https://"));
+        }
+
+        for (field, timeout_secs) in [
+            ("connect_timeout_secs", self.connect_timeout_secs),
+            ("generate_timeout_secs", self.generate_timeout_secs),
+        ] {
+            if timeout_secs == 0 || timeout_secs > MAX_TIMEOUT_SECS {
+                errors.push(ConfigError::new(field, "must be between 1 and 3600"));
+            }
+        }
+
+        if self.sse_buffer.max_line_bytes == 0 {
+            errors.push(ConfigError::new("sse_buffer.max_line_bytes", "must be greater than 0"));
+        }
+
+        if self.pinned_certificate_only && self.pinned_certificate.is_none() {
+            errors.push(ConfigError::new(
+                "pinned_certificate",
+                "must be set when pinned_certificate_only is enabled",
+            ));
         }
 
-        Ok(())
+        errors
+    }
+
+    /// Validate the configuration
+    ///
+    /// Returns `Error::Configuration` listing every invalid field, e.g.
+    /// `"api_key cannot be empty; api_url must begin with https://"`.
+    pub fn validate(&self) -> Result<()> {
+        let errors = self.validation_errors();
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::Configuration(
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ))
+    }
+}
+
+/// Builder for [`WatsonxConfig`] that reports every invalid field from `build()`
+///
+/// ```rust
+/// use watsonx_rs::WatsonxConfig;
+///
+/// let result = WatsonxConfig::builder(String::new(), "project".to_string())
+///     .with_generate_timeout(0)
+///     .build();
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WatsonxConfigBuilder {
+    config: WatsonxConfig,
+}
+
+impl WatsonxConfigBuilder {
+    /// Set the IAM URL
+    pub fn with_iam_url(mut self, iam_url: String) -> Self {
+        self.config.iam_url = iam_url;
+        self
+    }
+
+    /// Set the API URL
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.config.api_url = api_url;
+        self
+    }
+
+    /// Set the API version
+    pub fn with_api_version(mut self, api_version: String) -> Self {
+        self.config.api_version = api_version;
+        self
+    }
+
+    /// Set the timeout for establishing connections and fetching IAM tokens
+    pub fn with_connect_timeout(mut self, timeout_secs: u64) -> Self {
+        self.config.connect_timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Set the timeout for generation requests
+    pub fn with_generate_timeout(mut self, timeout_secs: u64) -> Self {
+        self.config.generate_timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Pin a DER-encoded certificate as a trusted TLS root
+    pub fn with_pinned_certificate(mut self, cert_der: Vec<u8>) -> Self {
+        self.config.pinned_certificate = Some(cert_der);
+        self
+    }
+
+    /// Trust only the pinned certificate instead of the built-in CA bundle
+    pub fn with_pinned_certificate_only(mut self, only: bool) -> Self {
+        self.config.pinned_certificate_only = only;
+        self
+    }
+
+    /// Set the buffer limits for streaming responses
+    pub fn with_sse_buffer(mut self, sse_buffer: SseBufferConfig) -> Self {
+        self.config.sse_buffer = sse_buffer;
+        self
+    }
+
+    /// Authenticate automatically on the first request instead of requiring `connect()`
+    pub fn with_lazy_connect(mut self, lazy_connect: bool) -> Self {
+        self.config.lazy_connect = lazy_connect;
+        self
+    }
+
+    /// Validate and return the configuration
+    ///
+    /// Returns `Error::Configuration` listing every invalid field.
+    pub fn build(self) -> Result<WatsonxConfig> {
+        self.config.validate()?;
+        Ok(self.config)
     }
 }
@@ -33,7 +33,9 @@ impl WatsonxConnection {
     /// - `WATSONX_API_URL`: API endpoint (defaults to IBM Cloud)
     /// - `IAM_IBM_CLOUD_URL`: IAM endpoint (defaults to IBM Cloud)
     /// - `WATSONX_API_VERSION`: API version (defaults to 2023-05-29)
-    /// - `WATSONX_TIMEOUT_SECS`: Request timeout (defaults to 120)
+    /// - `WATSONX_CONNECT_TIMEOUT`: Connect/IAM token timeout in seconds (defaults to 10)
+    /// - `WATSONX_GENERATE_TIMEOUT`: Generation request timeout in seconds (defaults to 120)
+    /// - `WATSONX_TIMEOUT_SECS`: Legacy timeout applied to both when the above are unset
     /// 
     /// # Example
     /// ```ignore
@@ -46,7 +48,7 @@ impl WatsonxConnection {
         let config = WatsonxConfig::from_env()?;
 
         // Create and connect client
-        let mut client = WatsonxClient::new(config)?;
+        let client = WatsonxClient::new(config)?;
         client.connect().await?;
 
         Ok(client)
@@ -75,11 +77,16 @@ impl WatsonxConnection {
             iam_url: "https://iam.cloud.ibm.com".to_string(),
             api_url: "https://us-south.ml.cloud.ibm.com".to_string(),
             api_version: "2023-05-29".to_string(),
-            timeout_secs: 120,
+            connect_timeout_secs: 10,
+            generate_timeout_secs: 120,
+            pinned_certificate: None,
+            pinned_certificate_only: false,
+            sse_buffer: Default::default(),
+            lazy_connect: false,
         };
 
         // Create and connect client
-        let mut client = WatsonxClient::new(config)?;
+        let client = WatsonxClient::new(config)?;
         client.connect().await?;
 
         Ok(client)
@@ -112,11 +119,16 @@ impl WatsonxConnection {
             iam_url: iam_url.to_string(),
             api_url: api_url.to_string(),
             api_version: "2023-05-29".to_string(),
-            timeout_secs: 120,
+            connect_timeout_secs: 10,
+            generate_timeout_secs: 120,
+            pinned_certificate: None,
+            pinned_certificate_only: false,
+            sse_buffer: Default::default(),
+            lazy_connect: false,
         };
 
         // Create and connect client
-        let mut client = WatsonxClient::new(config)?;
+        let client = WatsonxClient::new(config)?;
         client.connect().await?;
 
         Ok(client)
@@ -132,7 +144,7 @@ impl WatsonxConnection {
     /// ```
     pub async fn with_config(self, config: WatsonxConfig) -> Result<WatsonxClient> {
         // Create and connect client
-        let mut client = WatsonxClient::new(config)?;
+        let client = WatsonxClient::new(config)?;
         client.connect().await?;
 
         Ok(client)
@@ -0,0 +1,197 @@
+//! Multi-turn chat conversations
+//!
+//! `Conversation` keeps the message history of a chat and sends it with every turn, so
+//! callers only pass the new user message instead of rebuilding a `Vec<ChatMessage>`.
+
+use crate::client::WatsonxClient;
+use crate::error::Result;
+use crate::types::{ChatCompletionConfig, ChatMessage};
+
+/// A chat conversation that remembers its message history
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use watsonx_rs::{ChatCompletionConfig, Conversation, WatsonxClient, WatsonxConfig};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+/// client.connect().await?;
+///
+/// let mut conversation = Conversation::new(&client, ChatCompletionConfig::default())
+///     .with_max_history_len(20);
+/// conversation.set_system("You are a concise Rust tutor.");
+///
+/// println!("{}", conversation.say("What is a lifetime?").await?);
+/// println!("{}", conversation.say("Show me an example.").await?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Conversation {
+    client: WatsonxClient,
+    config: ChatCompletionConfig,
+    /// Messages sent with each turn; the system prompt, if set, comes first
+    messages: Vec<ChatMessage>,
+    max_history_len: Option<usize>,
+}
+
+impl Conversation {
+    /// Start an empty conversation using `client` and `config` for every turn
+    pub fn new(client: &WatsonxClient, config: ChatCompletionConfig) -> Self {
+        Self {
+            client: client.clone(),
+            config,
+            messages: Vec::new(),
+            max_history_len: None,
+        }
+    }
+
+    /// Keep at most `max_history_len` user and assistant messages, dropping the oldest first
+    ///
+    /// Messages are dropped a whole turn (user message and reply) at a time, so the history
+    /// never starts with an orphaned reply. The system prompt is never dropped and does not
+    /// count towards the limit.
+    pub fn with_max_history_len(mut self, max_history_len: usize) -> Self {
+        self.max_history_len = Some(max_history_len);
+        self.trim_history();
+        self
+    }
+
+    /// Set the system prompt, replacing any previous one
+    pub fn set_system(&mut self, prompt: &str) {
+        match self.messages.first_mut() {
+            Some(message) if message.role == "system" => message.content = prompt.to_string(),
+            _ => self.messages.insert(0, ChatMessage::system(prompt)),
+        }
+    }
+
+    /// Send a user message and return the assistant's reply
+    ///
+    /// Both messages are added to the history. If the request fails, the history is left
+    /// as it was before the call.
+    pub async fn say(&mut self, user_message: &str) -> Result<String> {
+        let messages = self.with_user_message(user_message);
+        let result = self.client.chat_completion(messages, &self.config).await?;
+        Ok(self.record_turn(user_message, result.message.content))
+    }
+
+    /// Send a user message, passing the reply to `callback` as it streams in
+    ///
+    /// The complete reply is added to the history once the stream ends. If the request
+    /// fails, the history is left as it was before the call.
+    pub async fn say_streaming<F>(&mut self, user_message: &str, callback: F) -> Result<()>
+    where
+        F: Fn(&str) + Send + Sync,
+    {
+        let messages = self.with_user_message(user_message);
+        let result = self
+            .client
+            .chat_completion_stream(messages, &self.config, callback)
+            .await?;
+        self.record_turn(user_message, result.message.content);
+        Ok(())
+    }
+
+    /// Messages of the conversation so far, starting with the system prompt if set
+    pub fn history(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Remove every user and assistant message, keeping the system prompt
+    pub fn clear_history(&mut self) {
+        self.messages.retain(|message| message.role == "system");
+    }
+
+    /// Messages to send for a turn starting with `user_message`
+    fn with_user_message(&self, user_message: &str) -> Vec<ChatMessage> {
+        let mut messages = self.messages.clone();
+        messages.push(ChatMessage::user(user_message));
+        messages
+    }
+
+    /// Append a completed turn to the history, returning the reply
+    fn record_turn(&mut self, user_message: &str, reply: String) -> String {
+        self.messages.push(ChatMessage::user(user_message));
+        self.messages.push(ChatMessage::assistant(reply.clone()));
+        self.trim_history();
+        reply
+    }
+
+    /// Drop the oldest turns until at most `max_history_len` non-system messages remain
+    fn trim_history(&mut self) {
+        let Some(max) = self.max_history_len else {
+            return;
+        };
+        let excess = self
+            .messages
+            .iter()
+            .filter(|message| message.role != "system")
+            .count()
+            .saturating_sub(max);
+        // Round up to whole user/assistant pairs
+        let mut excess = excess + excess % 2;
+        self.messages.retain(|message| {
+            if excess > 0 && message.role != "system" {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WatsonxConfig;
+    use crate::error::Error;
+
+    fn conversation() -> Conversation {
+        let config = WatsonxConfig::new(
+            "test_key".to_string(),
+            "12345678-1234-1234-1234-123456789abc".to_string(),
+        );
+        let client = WatsonxClient::new(config).unwrap();
+        Conversation::new(&client, ChatCompletionConfig::default())
+    }
+
+    #[test]
+    fn test_history_trimming_keeps_system_prompt() {
+        let mut conversation = conversation().with_max_history_len(3);
+        conversation.set_system("Be brief.");
+        conversation.record_turn("one", "1".to_string());
+        assert_eq!(conversation.record_turn("two", "2".to_string()), "2");
+
+        // The odd limit drops the whole first turn rather than leaving its reply behind
+        let contents: Vec<&str> = conversation.history().iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["Be brief.", "two", "2"]);
+
+        conversation.set_system("Be thorough.");
+        assert_eq!(conversation.history()[0].content, "Be thorough.");
+        assert_eq!(conversation.history().len(), 3);
+
+        conversation.clear_history();
+        assert_eq!(conversation.history().len(), 1);
+        assert_eq!(conversation.history()[0].role, "system");
+    }
+
+    #[test]
+    fn test_zero_history_len_still_returns_reply() {
+        let mut conversation = conversation().with_max_history_len(0);
+        conversation.set_system("Be brief.");
+        assert_eq!(conversation.record_turn("hello", "hi".to_string()), "hi");
+        assert_eq!(conversation.history().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_turn_leaves_history_unchanged() {
+        let mut conversation = conversation();
+        conversation.record_turn("hello", "hi".to_string());
+
+        // The client never connected, so the request fails before reaching the API
+        assert!(matches!(conversation.say("again").await, Err(Error::Authentication(_))));
+        assert_eq!(conversation.history().len(), 2);
+    }
+}
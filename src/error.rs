@@ -61,6 +61,23 @@ pub enum Error {
     #[error("WatsonX API error: {0}")]
     Api(String),
 
+    /// API errors that carry the HTTP status of the failed response
+    ///
+    /// **Possible causes:**
+    /// - The service is overloaded or unavailable (5xx)
+    /// - The request was rejected (4xx)
+    ///
+    /// **Suggested actions:**
+    /// - Retry server errors (5xx) after a delay, or fall back to another model
+    /// - Check the error message for details of client errors (4xx)
+    #[error("WatsonX API error: {message}")]
+    HttpStatus {
+        /// HTTP status code of the response
+        status: u16,
+        /// Description of the failure, including the response body
+        message: String,
+    },
+
     /// Timeout errors (request took too long)
     ///
     /// **Possible causes:**
@@ -175,6 +192,100 @@ pub enum Error {
     /// - Check disk space
     #[error("I/O error: {0}")]
     Io(String),
+
+    /// Operation cancelled by the caller
+    ///
+    /// **Possible causes:**
+    /// - A `CancellationToken` passed to the operation was cancelled
+    ///   (for example from a Ctrl+C handler or a request deadline)
+    ///
+    /// **Suggested actions:**
+    /// - None; start the operation again if the result is still needed
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    /// Structured error returned by the Watson Orchestrate API
+    ///
+    /// **Possible causes:**
+    /// - The request was rejected with an `{"error": {"code", "message", "details"}}` body
+    ///
+    /// **Suggested actions:**
+    /// - Match on `OrchestrateApiError::code`, or use helpers such as
+    ///   `is_quota_exceeded()` and `is_agent_not_found()`
+    #[error("Orchestrate API error: {0}")]
+    OrchestrateApi(OrchestrateApiError),
+
+    /// The model returned no generated text
+    ///
+    /// **Possible causes:**
+    /// - The model stopped immediately (e.g. a stop sequence matched at the start)
+    /// - The prompt gave the model nothing to continue
+    ///
+    /// **Suggested actions:**
+    /// - Adjust the prompt or stop sequences
+    /// - Retry with sampling (`DecodingMethod::Sample`) or a different model
+    #[error("Model {model_id} generated no output for a {prompt_length}-byte prompt")]
+    EmptyGeneration {
+        /// Model that produced the empty output
+        model_id: String,
+        /// Length of the prompt in bytes
+        prompt_length: usize,
+    },
+}
+
+/// Error body returned by the Watson Orchestrate API
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrchestrateApiError {
+    /// Machine-readable error code (e.g. `agent_not_found`)
+    pub code: String,
+    /// Human-readable error message
+    pub message: String,
+    /// Additional details reported with the error
+    pub details: Vec<String>,
+}
+
+impl OrchestrateApiError {
+    /// Parse an `{"error": {"code": ..., "message": ..., "details": [...]}}` response body
+    ///
+    /// Returns `None` if the body does not have that shape.
+    pub fn from_body(body: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(body).ok()?;
+        let error = value.get("error")?.as_object()?;
+        let text = |value: &serde_json::Value| match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        Some(Self {
+            code: error.get("code").map(text).unwrap_or_default(),
+            message: error.get("message").map(text).unwrap_or_default(),
+            details: error
+                .get("details")
+                .and_then(|details| details.as_array())
+                .map(|details| details.iter().map(text).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Check whether the request was rejected because a usage quota is used up
+    pub fn is_quota_exceeded(&self) -> bool {
+        self.code.to_ascii_lowercase().contains("quota")
+    }
+
+    /// Check whether the requested agent does not exist
+    pub fn is_agent_not_found(&self) -> bool {
+        self.code.eq_ignore_ascii_case("agent_not_found")
+    }
+}
+
+impl std::fmt::Display for OrchestrateApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)?;
+        if !self.details.is_empty() {
+            write!(f, " ({})", self.details.join("; "))?;
+        }
+        Ok(())
+    }
 }
 
 impl Error {
@@ -190,7 +301,13 @@ impl Error {
         matches!(
             self,
             Error::Network(_) | Error::Timeout(_) | Error::RateLimit(_)
-        )
+        ) || matches!(self, Error::HttpStatus { status, .. } if *status >= 500)
+    }
+
+    /// Check if the model produced no output
+    #[must_use]
+    pub fn is_empty_generation(&self) -> bool {
+        matches!(self, Error::EmptyGeneration { .. })
     }
 
     /// Check if this error requires user action
@@ -229,7 +346,7 @@ impl Error {
                     msg
                 )
             }
-            Error::Api(msg) => {
+            Error::Api(msg) | Error::HttpStatus { message: msg, .. } => {
                 format!(
                     "{}\n\nTroubleshooting: Check the error details above, verify your model ID and project ID are correct.",
                     msg
@@ -259,6 +376,12 @@ impl Error {
                     msg
                 )
             }
+            Error::EmptyGeneration { .. } => {
+                format!(
+                    "{}\n\nTroubleshooting: Try adjusting your prompt, stop sequences or decoding parameters.",
+                    self
+                )
+            }
             _ => self.to_string(),
         }
     }
@@ -277,6 +400,10 @@ mod tests {
         assert!(!Error::Configuration("test".to_string()).is_retryable());
         assert!(!Error::InvalidInput("test".to_string()).is_retryable());
         assert!(!Error::Api("test".to_string()).is_retryable());
+        assert!(!Error::Cancelled("test".to_string()).is_retryable());
+        let status = |status| Error::HttpStatus { status, message: "test".to_string() };
+        assert!(status(503).is_retryable());
+        assert!(!status(400).is_retryable());
     }
 
     #[test]
@@ -330,4 +457,22 @@ mod tests {
         let err = Error::Api("api error".to_string());
         assert!(err.to_string().contains("WatsonX API error: api error"));
     }
+
+    #[test]
+    fn test_orchestrate_api_error_from_body() {
+        let body = r#"{"error": {"code": "quota_exceeded", "message": "Monthly message quota reached", "details": ["limit: 1000", {"reset": "2024-02-01"}]}}"#;
+        let error = OrchestrateApiError::from_body(body).unwrap();
+        assert_eq!(error.code, "quota_exceeded");
+        assert_eq!(error.details, vec!["limit: 1000".to_string(), r#"{"reset":"2024-02-01"}"#.to_string()]);
+        assert!(error.is_quota_exceeded());
+        assert!(!error.is_agent_not_found());
+        assert!(Error::OrchestrateApi(error).to_string().contains("Monthly message quota reached"));
+
+        let error = OrchestrateApiError::from_body(r#"{"error": {"code": "AGENT_NOT_FOUND", "message": "No agent abc"}}"#).unwrap();
+        assert!(error.is_agent_not_found());
+        assert!(error.details.is_empty());
+
+        assert!(OrchestrateApiError::from_body("Internal Server Error").is_none());
+        assert!(OrchestrateApiError::from_body(r#"{"detail": "Not found"}"#).is_none());
+    }
 }
@@ -32,7 +32,7 @@
 //!     );
 //!
 //!     // Create client
-//!     let mut client = WatsonxClient::new(config)?;
+//!     let client = WatsonxClient::new(config)?;
 //!
 //!     // Connect to WatsonX
 //!     client.connect().await?;
@@ -49,13 +49,19 @@
 //!
 //! See the `examples/` directory for more detailed usage examples.
 
+pub mod auth;
 pub mod client;
 pub mod config;
 pub mod connection;
+pub mod conversation;
 pub mod error;
 pub mod models;
 pub mod orchestrate;
+pub mod prompts;
+pub mod quality;
 pub mod sse;
+pub mod stream;
+pub mod tasks;
 pub mod types;
 
 #[cfg(test)]
@@ -64,12 +70,26 @@ mod tests;
 #[cfg(test)]
 mod orchestrate_tests;
 
+#[cfg(test)]
+mod mock_server;
+
 // Re-export main types for convenience
+pub use auth::{TokenExchangeProvider, TokenProvider};
 pub use client::WatsonxClient;
-pub use config::WatsonxConfig;
+pub use config::{ConfigError, SseBufferConfig, WatsonxConfig, WatsonxConfigBuilder};
 pub use connection::WatsonxConnection;
-pub use error::{Error, Result};
+pub use conversation::Conversation;
+pub use error::{Error, OrchestrateApiError, Result};
 pub use models::*;
+pub use prompts::{
+    ClassificationConfig, ClassificationResult, MatchConfidence, SummarizationConfig, SummarizationStyle,
+};
+pub use quality::{HeuristicQualityAssessor, LlmQualityAssessor, QualityAssessor};
+pub use stream::{ChatChunk, GenerationChunk, OrchestrateChunk, WatsonxStream};
+pub use tasks::{
+    EntityType, ExtractedEntity, Formality, TranslationConfig, TranslationResult,
+};
+pub use tokio_util::sync::CancellationToken;
 pub use orchestrate::OrchestrateClient;
 pub use orchestrate::{OrchestrateConfig, Agent, Message, MessagePayload};
 pub use orchestrate::*;
@@ -77,4 +97,4 @@ pub use types::*;
 // Re-export batch types explicitly for better discoverability
 pub use types::{BatchRequest, BatchItemResult, BatchGenerationResult};
 // Re-export chat completion types
-pub use types::{ChatMessage, ChatCompletionConfig, ChatCompletionResult};
+pub use types::{ChatMessage, ChatCompletionChoice, ChatCompletionConfig, ChatCompletionResult};
@@ -116,6 +116,12 @@ pub mod models {
 /// Default model to use
 pub const DEFAULT_MODEL: &str = models::GRANITE_4_H_SMALL;
 
+/// Default model for text embeddings
+pub const DEFAULT_EMBEDDING_MODEL: &str = models::SLATE_30M_ENGLISH_RTRVR;
+
+/// Default model for reranking
+pub const DEFAULT_RERANK_MODEL: &str = models::CROSS_ENCODER_MS_MARCO_MINILM_L_12_V2;
+
 /// Maximum tokens supported by WatsonX models
 pub const MAX_TOKENS_LIMIT: u32 = 131_072; // 128k tokens
 
@@ -1,15 +1,59 @@
 //! Agent management operations
 
+use crate::client::encode_path_segment;
 use crate::error::{Error, Result};
-use super::types::Agent;
+use super::types::{Agent, AgentCapabilities, CreateAgentRequest, Skill, UpdateAgentRequest};
 use super::OrchestrateClient;
+use super::client::{parse_list_response, retry_request, send_error};
+use super::http_log::SendLogged;
+use futures::future::try_join_all;
+use futures::TryFutureExt;
 
 impl OrchestrateClient {
+    /// Create an agent
+    ///
+    /// With `CreateAgentRequest::with_knowledge_base`, the agent runs retrieval against the
+    /// given document collections for every user query.
+    pub async fn create_agent(&self, request: &CreateAgentRequest) -> Result<Agent> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents", base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .header("X-Instance-ID", &self.config.instance_id)
+            .json(request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to create agent {}: {} - {}",
+                request.name, status, error_text
+            )));
+        }
+
+        let agent: Agent = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(agent)
+    }
+
     /// List all agents (Watson Orchestrate API)
     pub async fn list_agents(&self) -> Result<Vec<Agent>> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         
@@ -22,23 +66,26 @@ impl OrchestrateClient {
         ];
 
         for url in endpoints {
-            let response = self
-                .client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", token))
-                .header("Content-Type", "application/json")
-                .header("X-Instance-ID", &self.config.instance_id)
-                .send()
-                .await
-                .map_err(|e| Error::Network(e.to_string()))?;
+            let response = retry_request(
+                || {
+                    self.client
+                        .get(&url)
+                        .header("Authorization", format!("Bearer {}", token))
+                        .header("Content-Type", "application/json")
+                        .header("X-Instance-ID", &self.config.instance_id)
+                        .send_logged()
+                        .map_err(send_error)
+                },
+                &self.retry_config,
+            )
+            .await?;
 
             if response.status().is_success() {
-                // Parse the JSON array response directly
-                let agents: Vec<Agent> = response
-                    .json()
+                let text = response
+                    .text()
                     .await
                     .map_err(|e| Error::Serialization(e.to_string()))?;
-                return Ok(agents);
+                return parse_list_response::<Agent>(&text, "agents");
             }
         }
 
@@ -49,11 +96,26 @@ impl OrchestrateClient {
         )))
     }
 
+    /// Describe what an agent can do: its model, instructions, tools and skills
+    ///
+    /// Tools listed in `Agent::tool_ids` are fetched individually; skills come from
+    /// `list_agent_skills`. Useful for picking an agent by capability at runtime.
+    pub async fn get_agent_capabilities(&self, agent_id: &str) -> Result<AgentCapabilities> {
+        let agent = self.get_agent(agent_id).await?;
+
+        let tool_ids = agent.tool_ids.clone().unwrap_or_default();
+        let (tools, skills) = futures::future::try_join(
+            try_join_all(tool_ids.iter().map(|tool_id| self.get_tool(tool_id))),
+            self.list_agent_skills(agent_id),
+        )
+        .await?;
+
+        Ok(AgentCapabilities::new(agent, tools, skills))
+    }
+
     /// Get a specific agent by ID
     pub async fn get_agent(&self, agent_id: &str) -> Result<Agent> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/agents/{}", base_url, agent_id);
@@ -63,7 +125,7 @@ impl OrchestrateClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -86,4 +148,395 @@ impl OrchestrateClient {
 
         Ok(agent)
     }
+
+    /// Update an agent, changing only the fields set in `request`
+    pub async fn update_agent(&self, agent_id: &str, request: UpdateAgentRequest) -> Result<Agent> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}", base_url, agent_id);
+
+        let response = self
+            .client
+            .patch(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .header("X-Instance-ID", &self.config.instance_id)
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to update agent {}: {} - {}",
+                agent_id, status, error_text
+            )));
+        }
+
+        let agent: Agent = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(agent)
+    }
+
+    /// Delete an agent
+    pub async fn delete_agent(&self, agent_id: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}", base_url, agent_id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("X-Instance-ID", &self.config.instance_id)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to delete agent {}: {} - {}",
+                agent_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// List the skills assigned to an agent
+    pub async fn list_agent_skills(&self, agent_id: &str) -> Result<Vec<Skill>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/skills", base_url, agent_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to list skills for agent {}: {} - {}",
+                agent_id, status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        parse_list_response::<Skill>(&text, "skills")
+    }
+
+    /// Assign a skill to an agent, returning the updated agent
+    pub async fn assign_skill_to_agent(&self, agent_id: &str, skill_id: &str) -> Result<Agent> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/skills/{}", base_url, agent_id, skill_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to assign skill {} to agent {}: {} - {}",
+                skill_id, agent_id, status, error_text
+            )));
+        }
+
+        let agent: Agent = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(agent)
+    }
+
+    /// Remove a skill from an agent, returning the updated agent
+    pub async fn remove_skill_from_agent(&self, agent_id: &str, skill_id: &str) -> Result<Agent> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/skills/{}", base_url, agent_id, skill_id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to remove skill {} from agent {}: {} - {}",
+                skill_id, agent_id, status, error_text
+            )));
+        }
+
+        let agent: Agent = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(agent)
+    }
+
+    /// List agents carrying a tag
+    ///
+    /// Filtering happens server-side through the `tags` query parameter.
+    pub async fn list_agents_by_tag(&self, tag: &str) -> Result<Vec<Agent>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents", base_url);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .query(&[("tags", tag)])
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .header("X-Instance-ID", &self.config.instance_id)
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to list agents with tag {}: {} - {}",
+                tag, status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        parse_list_response::<Agent>(&text, "agents")
+    }
+
+    /// List the tags on an agent
+    pub async fn list_agent_tags(&self, agent_id: &str) -> Result<Vec<String>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/tags", base_url, agent_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to list tags for agent {}: {} - {}",
+                agent_id, status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        parse_list_response::<String>(&text, "tags")
+    }
+
+    /// Add a tag to an agent
+    pub async fn add_agent_tag(&self, agent_id: &str, tag: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/tags/{}", base_url, agent_id, encode_path_segment(tag));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .header("X-Instance-ID", &self.config.instance_id)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to add tag {} to agent {}: {} - {}",
+                tag, agent_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a tag from an agent
+    pub async fn remove_agent_tag(&self, agent_id: &str, tag: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/tags/{}", base_url, agent_id, encode_path_segment(tag));
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .header("X-Instance-ID", &self.config.instance_id)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to remove tag {} from agent {}: {} - {}",
+                tag, agent_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Add a document collection to an agent's knowledge base
+    pub async fn link_collection_to_agent(&self, agent_id: &str, collection_id: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/knowledge_bases/{}", base_url, agent_id, collection_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to link collection {} to agent {}: {} - {}",
+                collection_id, agent_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Remove a document collection from an agent's knowledge base
+    pub async fn unlink_collection_from_agent(&self, agent_id: &str, collection_id: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/agents/{}/knowledge_bases/{}", base_url, agent_id, collection_id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to unlink collection {} from agent {}: {} - {}",
+                collection_id, agent_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
 }
@@ -1,50 +1,143 @@
 //! Chat and messaging operations
 
-use crate::error::{Error, Result};
-use super::types::{Message, MessagePayload, ChatWithDocsRequest, ChatWithDocsResponse, ChatWithDocsStatus};
+use crate::error::{Error, OrchestrateApiError, Result};
+use super::types::{
+    Message, MessageAttachment, MessagePayload, ChatWithDocsRequest, ChatWithDocsResponse, ChatWithDocsStatus,
+    OrchestrateEventType, OrchestrateRetryConfig, OrchestrateStreamEvent, SendMessageResult,
+};
 use super::OrchestrateClient;
-use std::collections::HashMap;
+use super::client::{retry_request, send_error};
+use super::http_log::SendLogged;
+use futures::TryFutureExt;
 use serde_json::Value;
+use std::time::Instant;
+use crate::stream::WatsonxStream;
 use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Decodes answer text from `stream_chat_with_docs` response lines
+///
+/// The chat-with-docs endpoints send SSE, where a `data:` line holds either the event's data
+/// object (named by a preceding `event:` line) or a whole `{"event": ..., "data": ...}` wrapper.
+/// The `runs/stream` fallbacks send raw Orchestrate JSON lines instead.
+#[derive(Debug, Default)]
+pub(crate) struct DocsStreamDecoder {
+    event: Option<String>,
+}
+
+impl DocsStreamDecoder {
+    /// Text delta carried by a line, if any
+    pub(crate) fn decode_line(&mut self, line: &str) -> Option<String> {
+        let line = line.trim();
+        if line.is_empty() {
+            self.event = None;
+            return None;
+        }
+        if let Some(event) = line.strip_prefix("event:") {
+            self.event = Some(event.trim().to_string());
+            return None;
+        }
+
+        let Some(data) = line.strip_prefix("data:") else {
+            return OrchestrateStreamEvent::parse_line(line).and_then(|event| event.delta);
+        };
+        let data = data.trim();
+        let event = self.event.take();
+        let value: Value = serde_json::from_str(data).ok()?;
+        if value.get("event").is_some() {
+            return OrchestrateStreamEvent::parse_line(data).and_then(|event| event.delta);
+        }
+        if event.is_some_and(|event| event != "message.delta") {
+            return None;
+        }
 
-#[derive(serde::Deserialize)]
-struct EventData {
-    event: String,
-    data: Value,
+        let delta = value.get("delta").filter(|d| d.is_object()).unwrap_or(&value);
+        delta
+            .get("content")?
+            .as_array()?
+            .first()?
+            .get("text")?
+            .as_str()
+            .map(str::to_string)
+    }
 }
 
 impl OrchestrateClient {
     /// Send a message to an agent and get response (matches wxo-client pattern)
     /// Uses /runs/stream endpoint and maintains thread_id for conversation continuity
-    pub async fn send_message(&self, agent_id: &str, message: &str, thread_id: Option<String>) -> Result<(String, Option<String>)> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
+    ///
+    /// Returns the answer together with the run ID, tool calls and run duration.
+    /// The duration is measured client-side when the run does not report one.
+    /// Files in `attachments` are sent with the message for the agent to process.
+    pub async fn send_message(
+        &self,
+        agent_id: &str,
+        message: &str,
+        thread_id: Option<String>,
+        attachments: Option<Vec<MessageAttachment>>,
+    ) -> Result<SendMessageResult> {
+        let payload = MessagePayload::builder(agent_id, message)
+            .with_thread_id(thread_id)
+            .with_attachments(attachments.unwrap_or_default())
+            .build();
+
+        self.send_message_payload(&payload).await
+    }
+
+    /// Send a message together with client-maintained conversation history
+    ///
+    /// The last entry of `messages` is sent as the new message; the earlier entries are
+    /// included as `previous_messages`. Use this with deployments that do not persist
+    /// thread history server-side, or to replay a conversation offline.
+    pub async fn send_message_with_history(
+        &self,
+        agent_id: &str,
+        messages: Vec<Message>,
+        thread_id: Option<String>,
+    ) -> Result<SendMessageResult> {
+        let mut messages = messages;
+        let message = messages.pop().ok_or_else(|| {
+            Error::InvalidInput("messages must contain at least one message".to_string())
         })?;
 
+        let mut payload = MessagePayload::builder(agent_id, message.content)
+            .with_thread_id(thread_id)
+            .with_previous_messages(messages)
+            .build();
+        payload.message.role = message.role;
+
+        self.send_message_payload(&payload).await
+    }
+
+    /// Post a message payload to the run stream and collect the result
+    async fn send_message_payload(&self, payload: &MessagePayload) -> Result<SendMessageResult> {
+        self.acquire_thread_rate_limit(payload.thread_id.as_deref()).await;
+        let token = self.ensure_token_valid().await?;
+        let started = Instant::now();
+
         let base_url = self.config.get_base_url();
         let url = format!("{}/runs/stream", base_url);
-
-        let payload = MessagePayload {
-            message: Message {
-                role: "user".to_string(),
-                content: message.to_string(),
+        let idempotency_key = self.message_idempotency_key(payload);
+
+        let response = retry_request(
+            || {
+                let mut request = self
+                    .with_stream_timeout(self.client.post(&url))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .header("X-Instance-ID", &self.config.instance_id);
+                if let Some(key) = &idempotency_key {
+                    request = request.header("X-Idempotency-Key", key);
+                }
+                request
+                    .json(payload)
+                    .send_logged()
+                    .map_err(send_error)
             },
-            additional_properties: HashMap::new(),
-            context: HashMap::new(),
-            agent_id: agent_id.to_string(),
-            thread_id: thread_id.clone(),
-        };
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .header("X-Instance-ID", &self.config.instance_id)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+            &self.message_retry_config(idempotency_key.as_deref()),
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -52,86 +145,91 @@ impl OrchestrateClient {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(Error::Api(format!(
-                "Failed to send message: {} - {}",
-                status, error_text
-            )));
+            return Err(match OrchestrateApiError::from_body(&error_text) {
+                Some(api_error) => Error::OrchestrateApi(api_error),
+                None => Error::Api(format!("Failed to send message: {} - {}", status, error_text)),
+            });
         }
 
         let text = response.text().await.map_err(|e| Error::Network(e.to_string()))?;
-        let mut answer = String::new();
-        let mut new_thread_id = thread_id;
-
-        for line in text.lines() {
-            if !line.is_empty() {
-                if let Ok(event_data) = serde_json::from_str::<EventData>(&line) {
-                    if event_data.event == "message.created" {
-                        if let Some(data_obj) = event_data.data.as_object() {
-                            if let Some(message_obj) = data_obj.get("message") {
-                                if let Some(content_array) = message_obj.get("content").and_then(|c| c.as_array()) {
-                                    if let Some(first_content) = content_array.first() {
-                                        if let Some(text) = first_content.get("text").and_then(|t| t.as_str()) {
-                                            answer = text.to_string();
-                                        }
-                                    }
-                                }
-                            }
-                            if let Some(tid) = data_obj.get("thread_id").and_then(|t| t.as_str()) {
-                                new_thread_id = Some(tid.to_string());
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
+        let mut result = SendMessageResult::from_stream_text(&text, payload.thread_id.clone());
+        if result.duration_ms.is_none() {
+            result.duration_ms = Some(started.elapsed().as_millis() as u64);
         }
+        result.idempotency_key = idempotency_key;
 
-        Ok((answer, new_thread_id))
+        Ok(result)
     }
 
-    /// Stream response from an agent (matches wxo-client pattern)
-    pub async fn stream_message<F>(
+    /// Retry policy for posting a message
+    ///
+    /// A retried POST could deliver the message twice, so it is only retried when it carries
+    /// an idempotency key the server can deduplicate on.
+    pub(crate) fn message_retry_config(&self, idempotency_key: Option<&str>) -> OrchestrateRetryConfig {
+        let mut config = self.retry_config.clone();
+        if idempotency_key.is_none() {
+            config.max_attempts = 1;
+        }
+        config
+    }
+
+    /// Idempotency key for a message: the payload's own, or a generated one if enabled
+    pub(crate) fn message_idempotency_key(&self, payload: &MessagePayload) -> Option<String> {
+        payload.idempotency_key.clone().or_else(|| {
+            self.idempotency_keys
+                .then(|| format!("{}-{}", payload.agent_id, uuid::Uuid::new_v4()))
+        })
+    }
+
+    /// Stream typed events from an agent run
+    ///
+    /// Every event (text deltas, message creation, tool calls, run lifecycle) is yielded
+    /// as an `OrchestrateStreamEvent`; lines that are not valid events are skipped.
+    pub async fn stream_message_typed(
         &self,
         agent_id: &str,
         message: &str,
         thread_id: Option<String>,
-        mut callback: F,
-    ) -> Result<Option<String>>
-    where
-        F: FnMut(String) -> Result<()>,
-    {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+    ) -> Result<WatsonxStream<OrchestrateStreamEvent>> {
+        let payload = MessagePayload::builder(agent_id, message)
+            .with_thread_id(thread_id)
+            .build();
+
+        self.stream_message_payload(&payload).await
+    }
+
+    /// Post a message payload to the run stream and return its events
+    async fn stream_message_payload(
+        &self,
+        payload: &MessagePayload,
+    ) -> Result<WatsonxStream<OrchestrateStreamEvent>> {
+        self.acquire_thread_rate_limit(payload.thread_id.as_deref()).await;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/runs/stream", base_url);
 
-        let payload = MessagePayload {
-            message: Message {
-                role: "user".to_string(),
-                content: message.to_string(),
+        let idempotency_key = self.message_idempotency_key(payload);
+
+        let response = retry_request(
+            || {
+                let mut request = self
+                    .with_stream_timeout(self.client.post(&url))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .header("Accept", "text/event-stream")
+                    .header("Cache-Control", "no-cache")
+                    .header("Connection", "keep-alive")
+                    .header("X-Accel-Buffering", "no")
+                    .header("X-Instance-ID", &self.config.instance_id);
+                if let Some(key) = &idempotency_key {
+                    request = request.header("X-Idempotency-Key", key);
+                }
+                request.json(payload).send_logged().map_err(send_error)
             },
-            additional_properties: HashMap::new(),
-            context: HashMap::new(),
-            agent_id: agent_id.to_string(),
-            thread_id: thread_id.clone(),
-        };
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .header("Accept", "text/event-stream")
-            .header("Cache-Control", "no-cache")
-            .header("Connection", "keep-alive")
-            .header("X-Accel-Buffering", "no")
-            .header("X-Instance-ID", &self.config.instance_id)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+            &self.message_retry_config(idempotency_key.as_deref()),
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -145,110 +243,56 @@ impl OrchestrateClient {
             )));
         }
 
-        let mut stream = response.bytes_stream();
-        let mut buffer = Vec::<u8>::new();
-        let mut new_thread_id = thread_id;
-        let mut chunk_count = 0;
-
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.map_err(|e| Error::Network(e.to_string()))?;
-            chunk_count += 1;
+        Ok(WatsonxStream::from_response(response))
+    }
 
-            if chunk_count > 1 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
-            }
+    /// Stream response from an agent (matches wxo-client pattern)
+    ///
+    /// Convenience wrapper around `stream_message_typed` that passes text deltas to the callback.
+    pub async fn stream_message<F>(
+        &self,
+        agent_id: &str,
+        message: &str,
+        thread_id: Option<String>,
+        callback: F,
+    ) -> Result<Option<String>>
+    where
+        F: FnMut(String) -> Result<()>,
+    {
+        let events = self
+            .stream_message_typed(agent_id, message, thread_id.clone())
+            .await?;
 
-            buffer.extend_from_slice(&chunk);
-
-            loop {
-                let newline_pos = buffer.iter().position(|&b| b == b'\n');
-
-                if let Some(newline_pos) = newline_pos {
-                    let line_bytes = buffer[..newline_pos].to_vec();
-                    buffer = buffer[newline_pos + 1..].to_vec();
-
-                    if let Ok(line) = String::from_utf8(line_bytes) {
-                        let trimmed = line.trim();
-
-                        if !trimmed.is_empty() {
-                            if let Ok(event_data) = serde_json::from_str::<EventData>(trimmed) {
-                                if event_data.event == "message.delta" {
-                                    if let Some(data_obj) = event_data.data.as_object() {
-                                        if let Some(delta_obj) = data_obj.get("delta").and_then(|d| d.as_object()) {
-                                            if let Some(content_array) = delta_obj.get("content").and_then(|c| c.as_array()) {
-                                                if let Some(first_content) = content_array.first() {
-                                                    if let Some(text) = first_content.get("text").and_then(|t| t.as_str()) {
-                                                        callback(text.to_string())?;
-                                                    }
-                                                }
-                                            }
-                                        } else if let Some(content_array) = data_obj.get("content").and_then(|c| c.as_array()) {
-                                            if let Some(first_content) = content_array.first() {
-                                                if let Some(text) = first_content.get("text").and_then(|t| t.as_str()) {
-                                                    callback(text.to_string())?;
-                                                }
-                                            }
-                                        }
-                                        if let Some(tid) = data_obj.get("thread_id").and_then(|t| t.as_str()) {
-                                            new_thread_id = Some(tid.to_string());
-                                        }
-                                    }
-                                } else if event_data.event == "message.created" {
-                                    if let Some(data_obj) = event_data.data.as_object() {
-                                        if let Some(tid) = data_obj.get("thread_id").and_then(|t| t.as_str()) {
-                                            new_thread_id = Some(tid.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    break;
-                }
-            }
-        }
+        forward_message_deltas(events, thread_id, callback).await
+    }
 
-        if !buffer.is_empty() {
-            if let Ok(line) = String::from_utf8(buffer) {
-                let trimmed = line.trim();
-                if !trimmed.is_empty() {
-                    if let Ok(event_data) = serde_json::from_str::<EventData>(trimmed) {
-                        if event_data.event == "message.delta" {
-                            if let Some(data_obj) = event_data.data.as_object() {
-                                if let Some(delta_obj) = data_obj.get("delta").and_then(|d| d.as_object()) {
-                                    if let Some(content_array) = delta_obj.get("content").and_then(|c| c.as_array()) {
-                                        if let Some(first_content) = content_array.first() {
-                                            if let Some(text) = first_content.get("text").and_then(|t| t.as_str()) {
-                                                callback(text.to_string())?;
-                                            }
-                                        }
-                                    }
-                                } else if let Some(content_array) = data_obj.get("content").and_then(|c| c.as_array()) {
-                                    if let Some(first_content) = content_array.first() {
-                                        if let Some(text) = first_content.get("text").and_then(|t| t.as_str()) {
-                                            callback(text.to_string())?;
-                                        }
-                                    }
-                                }
-                                if let Some(tid) = data_obj.get("thread_id").and_then(|t| t.as_str()) {
-                                    new_thread_id = Some(tid.to_string());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    /// Start a message request whose execution mode is chosen on the returned builder
+    ///
+    /// The same request can be sent with `.non_streaming()`, `.streaming(callback)` or
+    /// `.channel()`, so switching modes does not change how the request is built.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let request = MessagePayload::builder("agent-id", "Hello!").build();
+    /// let result = client.message(request.clone()).non_streaming().await?;
+    /// let thread_id = client
+    ///     .message(request)
+    ///     .streaming(|delta| {
+    ///         print!("{}", delta);
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// ```
+    pub fn message(&self, request: OrchestrateMessageRequest) -> OrchestrateMessageBuilder<'_> {
+        OrchestrateMessageBuilder {
+            client: self,
+            request,
         }
-
-        Ok(new_thread_id)
     }
 
     /// Get the status of chat with documents knowledge base for a thread
     pub async fn get_chat_with_docs_status(&self, agent_id: &str, thread_id: &str) -> Result<ChatWithDocsStatus> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         
@@ -265,7 +309,7 @@ impl OrchestrateClient {
                 .header("Authorization", format!("Bearer {}", token))
                 .header("Content-Type", "application/json")
                 .header("X-Instance-ID", &self.config.instance_id)
-                .send()
+                .send_logged()
                 .await
                 .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -285,9 +329,7 @@ impl OrchestrateClient {
 
     /// Send a message with document context (chat with documents)
     pub async fn chat_with_docs(&self, agent_id: &str, thread_id: &str, request: ChatWithDocsRequest) -> Result<ChatWithDocsResponse> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         
@@ -327,7 +369,7 @@ impl OrchestrateClient {
                 .header("Content-Type", "application/json")
                 .header("X-Instance-ID", &self.config.instance_id)
                 .json(&payload)
-                .send()
+                .send_logged()
                 .await
                 .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -382,9 +424,7 @@ impl OrchestrateClient {
     where
         F: FnMut(String) -> Result<()>,
     {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         
@@ -418,8 +458,7 @@ impl OrchestrateClient {
             };
 
             let response = self
-                .client
-                .post(&url)
+                .with_stream_timeout(self.client.post(&url))
                 .header("Authorization", format!("Bearer {}", token))
                 .header("Content-Type", "application/json")
                 .header("Accept", "text/event-stream")
@@ -428,7 +467,7 @@ impl OrchestrateClient {
                 .header("X-Accel-Buffering", "no")
                 .header("X-Instance-ID", &self.config.instance_id)
                 .json(&payload)
-                .send()
+                .send_logged()
                 .await
                 .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -438,6 +477,7 @@ impl OrchestrateClient {
 
             let mut stream = response.bytes_stream();
             let mut buffer = String::new();
+            let mut decoder = DocsStreamDecoder::default();
 
             while let Some(chunk_result) = stream.next().await {
                 let chunk = chunk_result.map_err(|e| Error::Network(e.to_string()))?;
@@ -448,26 +488,14 @@ impl OrchestrateClient {
                     let line = buffer[..line_end].to_string();
                     buffer = buffer[line_end + 1..].to_string();
 
-                    if !line.is_empty() && line.starts_with("data:") {
-                        let data_str = &line[5..].trim();
-                        if let Ok(event_data) = serde_json::from_str::<EventData>(data_str) {
-                            if event_data.event == "message.delta" {
-                                if let Some(data_obj) = event_data.data.as_object() {
-                                    if let Some(delta_obj) = data_obj.get("delta").and_then(|d| d.as_object()) {
-                                        if let Some(content_array) = delta_obj.get("content").and_then(|c| c.as_array()) {
-                                            if let Some(first_content) = content_array.first() {
-                                                if let Some(text) = first_content.get("text").and_then(|t| t.as_str()) {
-                                                    callback(text.to_string())?;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                    if let Some(text) = decoder.decode_line(&line) {
+                        callback(text)?;
                     }
                 }
             }
+            if let Some(text) = decoder.decode_line(&buffer) {
+                callback(text)?;
+            }
 
             return Ok(());
         }
@@ -477,3 +505,86 @@ impl OrchestrateClient {
         )))
     }
 }
+
+/// Request sent by `OrchestrateClient::message`
+pub type OrchestrateMessageRequest = MessagePayload;
+
+/// Pending message request, sent once an execution mode is selected
+pub struct OrchestrateMessageBuilder<'a> {
+    client: &'a OrchestrateClient,
+    request: OrchestrateMessageRequest,
+}
+
+impl OrchestrateMessageBuilder<'_> {
+    /// The request that will be sent
+    pub fn request(&self) -> &OrchestrateMessageRequest {
+        &self.request
+    }
+
+    /// Wait for the run to finish and return the complete result
+    pub async fn non_streaming(self) -> Result<SendMessageResult> {
+        self.client.send_message_payload(&self.request).await
+    }
+
+    /// Pass text deltas to `callback` as they arrive, returning the thread ID
+    pub async fn streaming<F>(self, callback: F) -> Result<Option<String>>
+    where
+        F: FnMut(String) -> Result<()>,
+    {
+        let events = self.client.stream_message_payload(&self.request).await?;
+        forward_message_deltas(events, self.request.thread_id, callback).await
+    }
+
+    /// Deliver text deltas over a channel from a background task
+    ///
+    /// Receive deltas until the channel closes, then await the handle for the thread ID.
+    /// Must be called from within a Tokio runtime.
+    pub async fn channel(
+        self,
+    ) -> Result<(mpsc::UnboundedReceiver<String>, JoinHandle<Result<Option<String>>>)> {
+        let events = self.client.stream_message_payload(&self.request).await?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let handle = tokio::spawn(forward_message_deltas(
+            events,
+            self.request.thread_id,
+            move |delta| {
+                // A closed receiver only means nobody is listening; keep reading the thread ID
+                let _ = sender.send(delta);
+                Ok(())
+            },
+        ));
+
+        Ok((receiver, handle))
+    }
+}
+
+/// Pass the text deltas of a run stream to `callback`, returning the final thread ID
+async fn forward_message_deltas<F>(
+    mut events: WatsonxStream<OrchestrateStreamEvent>,
+    thread_id: Option<String>,
+    mut callback: F,
+) -> Result<Option<String>>
+where
+    F: FnMut(String) -> Result<()>,
+{
+    let mut new_thread_id = thread_id;
+
+    while let Some(event) = events.next().await {
+        let event = event?;
+        match event.event_type {
+            OrchestrateEventType::MessageDelta => {
+                if let Some(delta) = event.delta {
+                    callback(delta)?;
+                }
+            }
+            OrchestrateEventType::MessageCreated => {}
+            _ => continue,
+        }
+        if let Some(tid) = event.thread_id {
+            new_thread_id = Some(tid);
+        }
+    }
+
+    Ok(new_thread_id)
+}
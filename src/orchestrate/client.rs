@@ -3,47 +3,258 @@
 //! This module provides the main client for interacting with WatsonX Orchestrate services,
 //! including custom assistants, document collections, and chat functionality.
 
-use crate::error::{Error, Result};
+use crate::error::{Error, OrchestrateApiError, Result};
 use super::types::*;
-use super::config::OrchestrateConfig;
-use reqwest::{Client, ClientBuilder};
+use super::config::{AuthMode, OrchestrateConfig};
+use super::rate_limit::TokenBucket;
+use super::http_log::SendLogged;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand::Rng;
+use futures::TryFutureExt;
+use reqwest::{Client, ClientBuilder, RequestBuilder, Response, StatusCode};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::future::Future;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Refresh the access token when it expires within this window
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// Assumed lifetime of IAM tokens whose expiry cannot be read from the JWT payload
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// Access token together with the instant at which it expires
+#[derive(Clone, Debug)]
+pub(crate) struct JwtTokenHolder {
+    pub(crate) token: String,
+    pub(crate) expires_at: Instant,
+}
+
+impl JwtTokenHolder {
+    /// Wrap a token, reading its expiry from the JWT `exp` claim when available
+    pub(crate) fn new(token: String) -> Self {
+        let expires_at = jwt_expiry(&token).unwrap_or_else(|| Instant::now() + DEFAULT_TOKEN_LIFETIME);
+        Self { token, expires_at }
+    }
+
+    /// Check whether the token expires within the given margin
+    pub(crate) fn expires_within(&self, margin: Duration) -> bool {
+        Instant::now() + margin >= self.expires_at
+    }
+}
+
+/// Read the `exp` claim from a JWT payload (no signature verification)
+fn jwt_expiry(token: &str) -> Option<Instant> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: Value = serde_json::from_slice(&bytes).ok()?;
+    let exp = claims.get("exp")?.as_u64()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Instant::now() + Duration::from_secs(exp.saturating_sub(now)))
+}
+
+/// Error category names matched against `OrchestrateRetryConfig::retry_on_errors`
+pub(crate) fn retry_error_kind(error: &Error) -> &'static str {
+    match error {
+        Error::Network(_) => "network_error",
+        Error::Timeout(_) => "timeout",
+        Error::RateLimit(_) => "rate_limit",
+        Error::Api(_) | Error::HttpStatus { .. } => "api_error",
+        Error::Authentication(_) => "authentication",
+        Error::Serialization(_) => "serialization",
+        Error::Configuration(_) => "configuration",
+        Error::InvalidInput(_) => "invalid_input",
+        Error::ModelNotFound(_) => "model_not_found",
+        Error::ProjectNotFound(_) => "project_not_found",
+        Error::Io(_) => "io",
+        Error::Cancelled(_) => "cancelled",
+        Error::OrchestrateApi(_) => "api_error",
+        Error::EmptyGeneration { .. } => "empty_generation",
+    }
+}
+
+/// Backoff delay before the retry that follows `attempt` (1-based), with full jitter
+pub(crate) fn retry_delay(config: &OrchestrateRetryConfig, attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1) as i32;
+    let delay = config
+        .base_delay
+        .mul_f64(f64::from(config.backoff_multiplier).powi(exponent))
+        .min(config.max_delay);
+    delay.mul_f64(rand::thread_rng().gen_range(0.0..1.0))
+}
+
+/// Parse a list response, accepting a bare JSON array or an object wrapping it under `key`
+///
+/// Returns `Error::Serialization` with the raw body if neither shape matches, so an unexpected
+/// response is not mistaken for an empty list.
+pub(crate) fn parse_list_response<T: serde::de::DeserializeOwned>(text: &str, key: &str) -> Result<Vec<T>> {
+    if let Ok(items) = serde_json::from_str::<Vec<T>>(text) {
+        return Ok(items);
+    }
+
+    let value: Value = serde_json::from_str(text).map_err(|e| {
+        Error::Serialization(format!("Failed to parse list response: {} - body: {}", e, text))
+    })?;
+    match value.get(key) {
+        Some(items) => serde_json::from_value(items.clone()).map_err(|e| {
+            Error::Serialization(format!("Failed to parse \"{}\" in list response: {} - body: {}", key, e, text))
+        }),
+        None => Err(Error::Serialization(format!(
+            "Unexpected list response, expected an array or an object with \"{}\": {}",
+            key, text
+        ))),
+    }
+}
+
+/// Map a failed send to `Error::Timeout` when the request timed out, `Error::Network` otherwise
+pub(crate) fn send_error(error: reqwest::Error) -> Error {
+    if error.is_timeout() {
+        Error::Timeout(error.to_string())
+    } else {
+        Error::Network(error.to_string())
+    }
+}
+
+/// Retry category of a 429 (`rate_limit`) or 5xx (`api_error`) response status
+fn retryable_status_kind(status: StatusCode) -> Option<&'static str> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        Some("rate_limit")
+    } else if status.is_server_error() {
+        Some("api_error")
+    } else {
+        None
+    }
+}
+
+/// Map a 429 or 5xx response that is no longer retried to an error
+///
+/// A structured `{"error": {...}}` body becomes `Error::OrchestrateApi`, so quota errors can
+/// be recognised; otherwise a 429 is `Error::RateLimit` and a 5xx is `Error::HttpStatus`.
+async fn retryable_status_error(response: Response) -> Error {
+    let status = response.status();
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    if let Some(api_error) = OrchestrateApiError::from_body(&error_text) {
+        return Error::OrchestrateApi(api_error);
+    }
+    let message = format!("Orchestrate request failed: {} - {}", status, error_text);
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        Error::RateLimit(message)
+    } else {
+        Error::HttpStatus {
+            status: status.as_u16(),
+            message,
+        }
+    }
+}
+
+/// Send a request, retrying errors listed in `retry_on_errors` with exponential backoff and jitter
+///
+/// 429 and 5xx responses count as `rate_limit` and `api_error` failures; if they are still
+/// returned after the last attempt they are reported through `retryable_status_error`.
+pub(crate) async fn retry_request<F, Fut>(mut f: F, config: &OrchestrateRetryConfig) -> Result<Response>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Response>>,
+{
+    let should_retry = |attempt: u32, kind: &str| {
+        attempt < config.max_attempts && config.retry_on_errors.iter().any(|retried| retried == kind)
+    };
+
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Ok(response) => match retryable_status_kind(response.status()) {
+                None => return Ok(response),
+                Some(kind) if !should_retry(attempt, kind) => {
+                    return Err(retryable_status_error(response).await);
+                }
+                Some(_) => {}
+            },
+            Err(error) if should_retry(attempt, retry_error_kind(&error)) => {}
+            Err(error) => return Err(error),
+        }
+        tokio::time::sleep(retry_delay(config, attempt)).await;
+        attempt += 1;
+    }
+}
 
 /// WatsonX Orchestrate client for managing custom assistants and document collections
 pub struct OrchestrateClient {
     pub(crate) config: OrchestrateConfig,
-    pub(crate) access_token: Option<String>,
+    pub(crate) access_token: RwLock<Option<JwtTokenHolder>>,
     pub(crate) client: Client,
+    pub(crate) retry_config: OrchestrateRetryConfig,
+    pub(crate) tool_rate_limiters: Mutex<HashMap<String, TokenBucket>>,
+    pub(crate) thread_rate_limit: Option<f64>,
+    pub(crate) thread_rate_limiters: Mutex<HashMap<String, TokenBucket>>,
+    pub(crate) idempotency_keys: bool,
 }
 
 impl OrchestrateClient {
     /// Create a new Orchestrate client (matches wxo-client-main pattern)
     pub fn new(config: OrchestrateConfig) -> Self {
-        let client = ClientBuilder::new()
-            .timeout(Duration::from_secs(300)) // 5 minute timeout for streaming
+        let mut builder = ClientBuilder::new()
             .tcp_keepalive(Duration::from_secs(60))
-            .http1_title_case_headers()
-            .build()
-            .unwrap_or_else(|_| Client::new());
+            .http1_title_case_headers();
+        if let Some(connect_timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        let client = builder.build().unwrap_or_else(|_| Client::new());
 
         Self {
             config,
-            access_token: None,
+            access_token: RwLock::new(None),
             client,
+            retry_config: OrchestrateRetryConfig::default(),
+            tool_rate_limiters: Mutex::new(HashMap::new()),
+            thread_rate_limit: None,
+            thread_rate_limiters: Mutex::new(HashMap::new()),
+            idempotency_keys: false,
         }
     }
 
     /// Set the access token for authentication
-    pub fn with_token(mut self, token: String) -> Self {
-        self.access_token = Some(token);
+    pub fn with_token(self, token: String) -> Self {
+        self.set_token(token);
+        self
+    }
+
+    /// Authenticate with an IBM Cloud API key
+    ///
+    /// Switches to `AuthMode::Iam`. The first request exchanges the key for an IAM token,
+    /// and the token is refreshed from the key whenever it is about to expire, so neither
+    /// `connect` nor `set_token` needs to be called.
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.config.ibm_api_key = Some(api_key);
+        self.config.auth_mode = AuthMode::Iam;
+        self
+    }
+
+    /// Set the retry policy used for message and list requests
+    pub fn with_retry_config(mut self, retry_config: OrchestrateRetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Generate an idempotency key for messages that do not carry one
+    ///
+    /// Keys have the form `{agent_id}-{uuid}` and are reused for every retry of the same
+    /// message, so the server can return its cached response instead of posting a duplicate.
+    /// Keys set with `MessagePayloadBuilder::with_idempotency_key` are always sent.
+    pub fn with_idempotency_keys(mut self, enabled: bool) -> Self {
+        self.idempotency_keys = enabled;
         self
     }
 
     /// Set the access token for authentication (mutable)
-    pub fn set_token(&mut self, token: String) {
-        self.access_token = Some(token);
+    pub fn set_token(&self, token: String) {
+        let mut guard = self.access_token.write().unwrap_or_else(|e| e.into_inner());
+        *guard = Some(JwtTokenHolder::new(token));
     }
 
     /// Get the current configuration
@@ -51,9 +262,145 @@ impl OrchestrateClient {
         &self.config
     }
 
+    /// Apply `request_timeout` to a streaming request
+    pub(crate) fn with_stream_timeout(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.config.request_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
     /// Check if authenticated
     pub fn is_authenticated(&self) -> bool {
-        self.access_token.is_some()
+        self.access_token
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some()
+    }
+
+    /// Instant at which the current access token expires, if one is set
+    ///
+    /// Read from the JWT `exp` claim, or assumed to be one hour after the token was set.
+    pub fn token_expiry(&self) -> Option<Instant> {
+        self.access_token
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|holder| holder.expires_at)
+    }
+
+    /// Authenticate according to `OrchestrateConfig::auth_mode`
+    ///
+    /// With `AuthMode::Iam` this exchanges `ibm_api_key` for an IAM token, with
+    /// `AuthMode::Cp4d` it calls `connect_cp4d`, and with `AuthMode::StaticToken` it only
+    /// checks that a token has been set. Requests authenticate on demand anyway; call this
+    /// at startup to surface credential problems early.
+    pub async fn connect(&self) -> Result<()> {
+        match &self.config.auth_mode {
+            AuthMode::Iam => {
+                let api_key = self.config.ibm_api_key.as_deref().ok_or_else(|| {
+                    Error::Configuration(
+                        "IAM authentication requires an API key. Use OrchestrateConfig::with_api_key".to_string(),
+                    )
+                })?;
+                let token = Self::generate_jwt_token(api_key).await?;
+                self.set_token(token);
+                Ok(())
+            }
+            AuthMode::Cp4d { .. } => self.connect_cp4d().await,
+            AuthMode::StaticToken if self.is_authenticated() => Ok(()),
+            AuthMode::StaticToken => Err(Error::Authentication(
+                "Not authenticated. Set an access token with with_token or set_token.".to_string(),
+            )),
+        }
+    }
+
+    /// Authenticate against Cloud Pak for Data and store the resulting token
+    ///
+    /// Requires `AuthMode::Cp4d`, as set up by `OrchestrateConfig::cp4d_url`.
+    pub async fn connect_cp4d(&self) -> Result<()> {
+        let token = self.fetch_cp4d_token().await?;
+        self.set_token(token);
+        Ok(())
+    }
+
+    /// Request a token from the CP4D `/v1/preauth/validateAuth` endpoint
+    async fn fetch_cp4d_token(&self) -> Result<String> {
+        let AuthMode::Cp4d { host, username, api_key } = &self.config.auth_mode else {
+            return Err(Error::Configuration(
+                "CP4D authentication requires AuthMode::Cp4d. Use OrchestrateConfig::cp4d_url".to_string(),
+            ));
+        };
+
+        let url = format!("https://{}/v1/preauth/validateAuth", host);
+        let response = self
+            .client
+            .get(&url)
+            .header("username", username)
+            .header("api_key", api_key)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(format!("Failed to generate CP4D token: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Authentication(format!(
+                "Failed to generate CP4D token: {} - {}",
+                status, error_text
+            )));
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Cp4dTokenResponse {
+            #[serde(rename = "accessToken")]
+            access_token: String,
+        }
+
+        let token_response: Cp4dTokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(format!("Failed to parse CP4D token response: {}", e)))?;
+
+        Ok(token_response.access_token)
+    }
+
+    /// Return a valid access token, refreshing it first if it expires within five minutes
+    ///
+    /// Refreshing uses `OrchestrateConfig::ibm_api_key` for `AuthMode::Iam` and the CP4D
+    /// credentials for `AuthMode::Cp4d`. Otherwise the current token is returned as-is.
+    pub(crate) async fn ensure_token_valid(&self) -> Result<String> {
+        let current = self
+            .access_token
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        if let Some(holder) = &current {
+            if !holder.expires_within(TOKEN_REFRESH_MARGIN) {
+                return Ok(holder.token.clone());
+            }
+        }
+
+        let refreshed = match (&self.config.auth_mode, &self.config.ibm_api_key) {
+            (AuthMode::Iam, Some(api_key)) => Some(Self::generate_jwt_token(api_key).await?),
+            (AuthMode::Cp4d { .. }, _) => Some(self.fetch_cp4d_token().await?),
+            _ => None,
+        };
+
+        match (refreshed, current) {
+            (Some(token), _) => {
+                self.set_token(token.clone());
+                Ok(token)
+            }
+            (None, Some(holder)) => Ok(holder.token),
+            (None, None) => Err(Error::Authentication(
+                "Not authenticated. Set access token or API key first.".to_string(),
+            )),
+        }
     }
 
     /// Generate IAM Access Token from Watson Orchestrate API key
@@ -69,7 +416,7 @@ impl OrchestrateClient {
             .post("https://iam.cloud.ibm.com/identity/token")
             .header("Content-Type", "application/x-www-form-urlencoded")
             .body(body)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(format!("Failed to generate IAM token: {}", e)))?;
 
@@ -104,23 +451,25 @@ impl OrchestrateClient {
 
     /// List all custom assistants
     pub async fn list_assistants(&self) -> Result<Vec<CustomAssistant>> {
-        let access_token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token first.".to_string())
-        })?;
+        let access_token = self.ensure_token_valid().await?;
 
         let url = format!(
             "{}/v1/assistants",
             self.config.get_base_url()
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", access_token))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -140,32 +489,12 @@ impl OrchestrateClient {
             .map_err(|e| Error::Serialization(e.to_string()))?;
 
         // Try to parse as direct array first
-        if let Ok(assistants) = serde_json::from_str::<Vec<CustomAssistant>>(&text) {
-            return Ok(assistants);
-        }
-
-        // Try to extract from wrapper object
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(assistants_array) = obj.get("assistants").and_then(|a| a.as_array()) {
-                let assistants: Result<Vec<CustomAssistant>> = assistants_array
-                    .iter()
-                    .map(|item| {
-                        serde_json::from_value::<CustomAssistant>(item.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return assistants;
-            }
-        }
-
-        Ok(Vec::new())
+        parse_list_response::<CustomAssistant>(&text, "assistants")
     }
 
     /// Send multiple messages in a batch
     pub async fn send_batch_messages(&self, request: BatchMessageRequest) -> Result<BatchMessageResponse> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/batch/messages", base_url);
@@ -176,7 +505,7 @@ impl OrchestrateClient {
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -206,19 +535,57 @@ impl OrchestrateClient {
 
     /// List all skills
     pub async fn list_skills(&self) -> Result<Vec<Skill>> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/skills", base_url);
 
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to list skills: {} - {}",
+                status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        parse_list_response::<Skill>(&text, "skills")
+    }
+
+    /// Get a specific skill by ID
+    pub async fn get_skill(&self, skill_id: &str) -> Result<Skill> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/skills/{}", base_url, skill_id);
+
         let response = self
             .client
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -229,8 +596,48 @@ impl OrchestrateClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(Error::Api(format!(
-                "Failed to list skills: {} - {}",
-                status, error_text
+                "Failed to get skill {}: {} - {}",
+                skill_id, status, error_text
+            )));
+        }
+
+        let skill: Skill = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(skill)
+    }
+
+    /// Get all versions of a skill
+    pub async fn get_skill_versions(&self, skill_id: &str) -> Result<Vec<SkillVersion>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/skills/{}/versions", base_url, skill_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to get versions of skill {}: {} - {}",
+                skill_id, status, error_text
             )));
         }
 
@@ -239,41 +646,119 @@ impl OrchestrateClient {
             .await
             .map_err(|e| Error::Serialization(e.to_string()))?;
 
-        if let Ok(skills) = serde_json::from_str::<Vec<Skill>>(&text) {
-            return Ok(skills);
+        parse_list_response::<SkillVersion>(&text, "versions")
+    }
+
+    /// Make a specific version of a skill the active one
+    pub async fn activate_skill_version(&self, skill_id: &str, version_id: &str) -> Result<Skill> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/skills/{}/versions/{}/activate", base_url, skill_id, version_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to activate version {} of skill {}: {} - {}",
+                version_id, skill_id, status, error_text
+            )));
         }
 
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(skills_array) = obj.get("skills").and_then(|s| s.as_array()) {
-                let skills: Result<Vec<Skill>> = skills_array
-                    .iter()
-                    .map(|skill| {
-                        serde_json::from_value::<Skill>(skill.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return skills;
-            }
+        let skill: Skill = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(skill)
+    }
+
+    /// Roll a skill back to an earlier version
+    ///
+    /// Checks that the version exists before activating it. If it is already the active
+    /// version, the skill is returned unchanged.
+    pub async fn rollback_skill(&self, skill_id: &str, version_id: &str) -> Result<Skill> {
+        let versions = self.get_skill_versions(skill_id).await?;
+        let version = versions
+            .iter()
+            .find(|v| v.version_id == version_id)
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Skill {} has no version {}",
+                    skill_id, version_id
+                ))
+            })?;
+
+        if version.is_active {
+            return self.get_skill(skill_id).await;
         }
 
-        Ok(Vec::new())
+        self.activate_skill_version(skill_id, version_id).await
     }
 
-    /// Get a specific skill by ID
-    pub async fn get_skill(&self, skill_id: &str) -> Result<Skill> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+    /// Create a new skill
+    pub async fn create_skill(&self, request: CreateSkillRequest) -> Result<Skill> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/skills", base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to create skill: {} - {}",
+                status, error_text
+            )));
+        }
+
+        let skill: Skill = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(skill)
+    }
+
+    /// Update a skill, changing only the fields set in `request`
+    pub async fn update_skill(&self, skill_id: &str, request: UpdateSkillRequest) -> Result<Skill> {
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/skills/{}", base_url, skill_id);
 
         let response = self
             .client
-            .get(&url)
+            .patch(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .json(&request)
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -284,7 +769,7 @@ impl OrchestrateClient {
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(Error::Api(format!(
-                "Failed to get skill {}: {} - {}",
+                "Failed to update skill {}: {} - {}",
                 skill_id, status, error_text
             )));
         }
@@ -296,6 +781,202 @@ impl OrchestrateClient {
 
         Ok(skill)
     }
+
+    /// Delete a skill
+    pub async fn delete_skill(&self, skill_id: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/skills/{}", base_url, skill_id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to delete skill {}: {} - {}",
+                skill_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Execute a skill directly
+    pub async fn execute_skill(&self, request: SkillExecutionRequest) -> Result<SkillExecutionResult> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/skills/{}/execute", base_url, request.skill_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to execute skill {}: {} - {}",
+                request.skill_id, status, error_text
+            )));
+        }
+
+        let result: SkillExecutionResult = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Execute skills in parallel, running at most `max_concurrency` at a time
+    ///
+    /// Items start in submission order and results are returned in the same order.
+    /// Failed items are reported in their `BatchSkillResult`; see
+    /// `run_skill_batch_with_config` to stop at the first failure or limit each item's time.
+    pub async fn run_skill_batch(
+        &self,
+        requests: Vec<SkillExecutionRequest>,
+        max_concurrency: usize,
+    ) -> Result<Vec<BatchSkillResult>> {
+        self.run_skill_batch_with_config(requests, max_concurrency, &BatchSkillConfig::default())
+            .await
+    }
+
+    /// Execute skills in parallel with explicit batch options
+    ///
+    /// With `continue_on_error` disabled, the first failure cancels the items still running
+    /// or waiting and is returned as the error.
+    pub async fn run_skill_batch_with_config(
+        &self,
+        requests: Vec<SkillExecutionRequest>,
+        max_concurrency: usize,
+        config: &BatchSkillConfig,
+    ) -> Result<Vec<BatchSkillResult>> {
+        if max_concurrency == 0 {
+            return Err(Error::InvalidInput(
+                "max_concurrency must be at least 1".to_string(),
+            ));
+        }
+
+        // Tokio's semaphore is fair, so permits are granted in submission order
+        let semaphore = tokio::sync::Semaphore::new(max_concurrency);
+        let items = requests.into_iter().map(|request| {
+            let semaphore = &semaphore;
+            async move {
+                let _permit = semaphore.acquire().await.expect("semaphore is never closed");
+                let skill_id = request.skill_id.clone();
+                let result = match config.timeout_per_item {
+                    Some(limit) => tokio::time::timeout(limit, self.execute_skill(request))
+                        .await
+                        .unwrap_or_else(|_| {
+                            Err(Error::Timeout(format!(
+                                "Skill {} did not finish within {:?}",
+                                skill_id, limit
+                            )))
+                        }),
+                    None => self.execute_skill(request).await,
+                };
+
+                match result {
+                    Err(error) if !config.continue_on_error => Err(error),
+                    result => Ok(BatchSkillResult { skill_id, result }),
+                }
+            }
+        });
+
+        futures::future::try_join_all(items).await
+    }
+
+    /// Import a skill from a YAML definition file and create it
+    #[cfg(feature = "yaml")]
+    pub async fn import_skill_from_yaml(&self, path: impl AsRef<std::path::Path>) -> Result<Skill> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path).map_err(|e| {
+            Error::Io(format!("Failed to read skill definition {}: {}", path.display(), e))
+        })?;
+        self.import_skill_from_yaml_str(&yaml).await
+    }
+
+    /// Import a skill from a YAML definition string and create it
+    #[cfg(feature = "yaml")]
+    pub async fn import_skill_from_yaml_str(&self, yaml: &str) -> Result<Skill> {
+        let errors = Self::validate_skill_yaml(yaml)?;
+        if !errors.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "Invalid skill definition: {}",
+                errors.join("; ")
+            )));
+        }
+
+        let request: CreateSkillRequest = serde_yaml::from_str(yaml)
+            .map_err(|e| Error::Serialization(format!("Failed to parse skill YAML: {}", e)))?;
+
+        self.create_skill(request).await
+    }
+
+    /// Validate a YAML skill definition without making an API call
+    ///
+    /// Returns the list of schema errors found (empty if the definition is valid).
+    /// Fails only when the input is not well-formed YAML.
+    #[cfg(feature = "yaml")]
+    pub fn validate_skill_yaml(yaml: &str) -> Result<Vec<String>> {
+        let value: serde_yaml::Value = serde_yaml::from_str(yaml)
+            .map_err(|e| Error::Serialization(format!("Failed to parse skill YAML: {}", e)))?;
+
+        let Some(mapping) = value.as_mapping() else {
+            return Ok(vec!["skill definition must be a mapping".to_string()]);
+        };
+
+        let mut errors = Vec::new();
+
+        match mapping.get("name") {
+            Some(serde_yaml::Value::String(name)) if !name.trim().is_empty() => {}
+            Some(serde_yaml::Value::String(_)) => errors.push("name cannot be empty".to_string()),
+            Some(_) => errors.push("name must be a string".to_string()),
+            None => errors.push("name is required".to_string()),
+        }
+
+        if mapping.get("skill_type").is_none() {
+            errors.push("skill_type is required".to_string());
+        }
+
+        for field in ["input_params", "output_params"] {
+            if let Some(params) = mapping.get(field) {
+                if !params.is_mapping() {
+                    errors.push(format!("{} must be a mapping of parameter definitions", field));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            if let Err(e) = serde_yaml::from_value::<CreateSkillRequest>(value) {
+                errors.push(e.to_string());
+            }
+        }
+
+        Ok(errors)
+    }
 }
 
 // Helper structs for SSE parsing
@@ -1,27 +1,48 @@
 //! Document collection management operations
 
 use crate::error::{Error, Result};
-use super::types::{DocumentCollection, Document, SearchRequest, SearchResponse};
+use super::types::{
+    AddDocumentsRequest, BatchSearchResult, CollectionExport, CollectionFilter, CreateCollectionRequest, Document, DocumentCollection,
+    ListOptions, Page, ReindexJob, ReindexStatus, SearchRequest, SearchResponse,
+};
 use super::OrchestrateClient;
+use futures::future::join_all;
+use futures::stream::{self, Stream};
+use super::client::{parse_list_response, retry_request, send_error};
+use super::http_log::SendLogged;
+use futures::{TryFutureExt, TryStreamExt};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Page size used when iterating, exporting and importing collections
+const COLLECTION_TRANSFER_BATCH_SIZE: u32 = 100;
+
+/// Interval between status checks in `wait_for_reindex`
+const REINDEX_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default number of searches run concurrently by `batch_search_documents`
+const DEFAULT_BATCH_SEARCH_CONCURRENCY: usize = 5;
 
 impl OrchestrateClient {
     /// List all document collections
     pub async fn list_collections(&self) -> Result<Vec<DocumentCollection>> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/collections", base_url);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -40,31 +61,26 @@ impl OrchestrateClient {
             .await
             .map_err(|e| Error::Serialization(e.to_string()))?;
 
-        if let Ok(collections) = serde_json::from_str::<Vec<DocumentCollection>>(&text) {
-            return Ok(collections);
-        }
+        parse_list_response::<DocumentCollection>(&text, "collections")
+    }
 
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(collections_array) = obj.get("collections").and_then(|c| c.as_array()) {
-                let collections: Result<Vec<DocumentCollection>> = collections_array
-                    .iter()
-                    .map(|coll| {
-                        serde_json::from_value::<DocumentCollection>(coll.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return collections;
-            }
-        }
+    /// List document collections matching a filter
+    ///
+    /// Filtering is applied client-side to the result of `list_collections`.
+    pub async fn list_collections_with_filter(&self, filter: &CollectionFilter) -> Result<Vec<DocumentCollection>> {
+        let collections = self.list_collections().await?;
+        Ok(collections.into_iter().filter(|c| filter.matches(c)).collect())
+    }
 
-        Ok(Vec::new())
+    /// Find a collection by its exact name
+    pub async fn find_collection_by_name(&self, name: &str) -> Result<Option<DocumentCollection>> {
+        let collections = self.list_collections().await?;
+        Ok(collections.into_iter().find(|c| c.name == name))
     }
 
     /// Get a specific document collection
     pub async fn get_collection(&self, collection_id: &str) -> Result<DocumentCollection> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/collections/{}", base_url, collection_id);
@@ -74,7 +90,7 @@ impl OrchestrateClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -98,11 +114,197 @@ impl OrchestrateClient {
         Ok(collection)
     }
 
+    /// Create a new document collection
+    pub async fn create_collection(&self, request: CreateCollectionRequest) -> Result<DocumentCollection> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/collections", base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to create collection: {} - {}",
+                status, error_text
+            )));
+        }
+
+        let collection: DocumentCollection = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(collection)
+    }
+
+    /// Add documents to a collection
+    pub async fn add_documents(&self, collection_id: &str, request: AddDocumentsRequest) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/collections/{}/documents", base_url, collection_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to add documents to collection {}: {} - {}",
+                collection_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// List documents in a collection, one page at a time
+    pub async fn list_collection_documents(&self, collection_id: &str, options: ListOptions) -> Result<Page<Document>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!(
+            "{}/collections/{}/documents{}",
+            base_url,
+            collection_id,
+            options.to_query_string()
+        );
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to list documents in collection {}: {} - {}",
+                collection_id, status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        let offset = options.offset.unwrap_or(0);
+
+        if let Ok(items) = serde_json::from_str::<Vec<Document>>(&text) {
+            return Ok(Page { items, total: None, limit: options.limit, offset });
+        }
+
+        let obj = serde_json::from_str::<serde_json::Value>(&text)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        let items_array = obj
+            .get("documents")
+            .and_then(|d| d.as_array())
+            .ok_or_else(|| Error::Serialization(format!("Unexpected documents response: {}", text)))?;
+        let items = items_array
+            .iter()
+            .map(|item| {
+                serde_json::from_value::<Document>(item.clone())
+                    .map_err(|e| Error::Serialization(e.to_string()))
+            })
+            .collect::<Result<Vec<Document>>>()?;
+        let total = obj
+            .get("total")
+            .or_else(|| obj.get("total_count"))
+            .and_then(|t| t.as_u64())
+            .map(|t| t as u32);
+
+        Ok(Page { items, total, limit: options.limit, offset })
+    }
+
+    /// Stream every document in a collection, fetching pages as they are consumed
+    ///
+    /// The stream ends after the first error.
+    pub fn iter_collection_documents<'a>(
+        &'a self,
+        collection_id: &'a str,
+    ) -> impl Stream<Item = Result<Document>> + 'a {
+        let first = Some(ListOptions::new().with_limit(COLLECTION_TRANSFER_BATCH_SIZE));
+        stream::try_unfold(first, move |options| async move {
+            let Some(options) = options else {
+                return Ok(None);
+            };
+            let page = self.list_collection_documents(collection_id, options).await?;
+            let next = page.next_options();
+            Ok(Some((stream::iter(page.items.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+    }
+
+    /// Export a collection and all of its documents
+    pub async fn export_collection(&self, collection_id: &str) -> Result<CollectionExport> {
+        let collection = self.get_collection(collection_id).await?;
+        let documents = self.iter_collection_documents(collection_id).try_collect().await?;
+
+        Ok(CollectionExport { collection, documents })
+    }
+
+    /// Import an exported collection as a new collection
+    ///
+    /// The new collection keeps the original name unless `new_name` is given.
+    pub async fn import_collection(&self, export: &CollectionExport, new_name: Option<String>) -> Result<DocumentCollection> {
+        let request = CreateCollectionRequest {
+            name: new_name.unwrap_or_else(|| export.collection.name.clone()),
+            description: export.collection.description.clone(),
+            vector_index: export.collection.vector_index.clone(),
+        };
+        let collection = self.create_collection(request).await?;
+
+        for batch in export.documents.chunks(COLLECTION_TRANSFER_BATCH_SIZE as usize) {
+            let request = AddDocumentsRequest {
+                documents: batch.to_vec(),
+                async_processing: false,
+            };
+            self.add_documents(&collection.id, request).await?;
+        }
+
+        Ok(collection)
+    }
+
     /// Get a specific document from a collection
     pub async fn get_document(&self, collection_id: &str, document_id: &str) -> Result<Document> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/collections/{}/documents/{}", base_url, collection_id, document_id);
@@ -112,7 +314,7 @@ impl OrchestrateClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -138,9 +340,7 @@ impl OrchestrateClient {
 
     /// Delete a document from a collection
     pub async fn delete_document(&self, collection_id: &str, document_id: &str) -> Result<()> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/collections/{}/documents/{}", base_url, collection_id, document_id);
@@ -149,7 +349,7 @@ impl OrchestrateClient {
             .client
             .delete(&url)
             .header("Authorization", format!("Bearer {}", api_key))
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -170,9 +370,7 @@ impl OrchestrateClient {
 
     /// Search documents in a collection
     pub async fn search_documents(&self, collection_id: &str, request: SearchRequest) -> Result<SearchResponse> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/collections/{}/search", base_url, collection_id);
@@ -183,7 +381,7 @@ impl OrchestrateClient {
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -206,4 +404,152 @@ impl OrchestrateClient {
 
         Ok(search_response)
     }
+
+    /// Run several searches against a collection concurrently
+    ///
+    /// Each query succeeds or fails independently; failures are reported in
+    /// `BatchSearchResult::error` instead of aborting the batch.
+    pub async fn batch_search_documents(&self, collection_id: &str, queries: &[SearchRequest]) -> Result<Vec<BatchSearchResult>> {
+        self.batch_search_documents_with_concurrency(collection_id, queries, DEFAULT_BATCH_SEARCH_CONCURRENCY)
+            .await
+    }
+
+    /// Run several searches against a collection with at most `max_concurrency` in flight
+    pub async fn batch_search_documents_with_concurrency(
+        &self,
+        collection_id: &str,
+        queries: &[SearchRequest],
+        max_concurrency: usize,
+    ) -> Result<Vec<BatchSearchResult>> {
+        let mut results = Vec::with_capacity(queries.len());
+
+        for batch in queries.chunks(max_concurrency.max(1)) {
+            let searches = batch
+                .iter()
+                .map(|request| self.search_documents(collection_id, request.clone()));
+
+            for (request, outcome) in batch.iter().zip(join_all(searches).await) {
+                results.push(match outcome {
+                    Ok(response) => BatchSearchResult {
+                        query: request.query.clone(),
+                        results: response.results,
+                        error: None,
+                    },
+                    Err(error) => BatchSearchResult {
+                        query: request.query.clone(),
+                        results: Vec::new(),
+                        error: Some(error),
+                    },
+                });
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Start rebuilding a collection's vector index
+    ///
+    /// Use after the embedding model changes or documents are modified in bulk, then
+    /// follow the job with `get_reindex_status` or `wait_for_reindex`.
+    pub async fn reindex_collection(&self, collection_id: &str) -> Result<ReindexJob> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/collections/{}/reindex", base_url, collection_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to reindex collection {}: {} - {}",
+                collection_id, status, error_text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))
+    }
+
+    /// Get the current state of a reindex job
+    pub async fn get_reindex_status(&self, collection_id: &str, job_id: &str) -> Result<ReindexJob> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/collections/{}/reindex/{}", base_url, collection_id, job_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to get reindex job {} for collection {}: {} - {}",
+                job_id, collection_id, status, error_text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))
+    }
+
+    /// Poll a reindex job until it completes
+    ///
+    /// Returns `Error::Api` if the job fails and `Error::Timeout` if it is still running
+    /// after `timeout`.
+    pub async fn wait_for_reindex(&self, collection_id: &str, job_id: &str, timeout: Duration) -> Result<ReindexJob> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let job = self.get_reindex_status(collection_id, job_id).await?;
+            match job.status {
+                ReindexStatus::Completed => return Ok(job),
+                ReindexStatus::Failed => {
+                    return Err(Error::Api(format!(
+                        "Reindex job {} for collection {} failed",
+                        job_id, collection_id
+                    )));
+                }
+                _ => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Timeout(format!(
+                    "Reindex job {} for collection {} did not finish within {:?}",
+                    job_id, collection_id, timeout
+                )));
+            }
+            tokio::time::sleep(REINDEX_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
 }
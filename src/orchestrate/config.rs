@@ -3,14 +3,117 @@
 //! Configuration management for Watson Orchestrate operations,
 //! including environment variable handling and URL construction.
 
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Default time allowed to establish a connection
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time allowed for a streaming request, long enough for slow runs
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Region codes accepted by `Region::from_str` and `WXO_REGION`
+const VALID_REGIONS: &str = "us-south, eu-de, ap-north (jp-tok), uk-south (eu-gb)";
+
+/// IBM Cloud region hosting a Watson Orchestrate instance
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Region {
+    /// Dallas (us-south)
+    #[default]
+    UsSouth,
+    /// Frankfurt (eu-de)
+    EuDe,
+    /// Tokyo (jp-tok)
+    ApNorth,
+    /// London (eu-gb)
+    UkSouth,
+    /// Custom deployment, holding its API host name
+    Custom(String),
+}
+
+impl Region {
+    /// Host name of the Orchestrate API in this region
+    pub fn api_host(&self) -> &str {
+        match self {
+            Region::UsSouth => "us-south.watson-orchestrate.cloud.ibm.com",
+            Region::EuDe => "eu-de.watson-orchestrate.cloud.ibm.com",
+            Region::ApNorth => "jp-tok.watson-orchestrate.cloud.ibm.com",
+            Region::UkSouth => "eu-gb.watson-orchestrate.cloud.ibm.com",
+            Region::Custom(host) => host,
+        }
+    }
+
+    /// Default API base URL for this region
+    pub fn base_url(&self) -> String {
+        format!("https://{}/api/v1/", self.api_host())
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Region::UsSouth => write!(f, "us-south"),
+            Region::EuDe => write!(f, "eu-de"),
+            Region::ApNorth => write!(f, "ap-north"),
+            Region::UkSouth => write!(f, "uk-south"),
+            Region::Custom(host) => write!(f, "{}", host),
+        }
+    }
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "us-south" => Ok(Region::UsSouth),
+            "eu-de" => Ok(Region::EuDe),
+            "ap-north" | "jp-tok" => Ok(Region::ApNorth),
+            "uk-south" | "eu-gb" => Ok(Region::UkSouth),
+            _ => Err(format!(
+                "Unknown Watson Orchestrate region '{}'. Valid values: {}",
+                s, VALID_REGIONS
+            )),
+        }
+    }
+}
+
+/// How `OrchestrateClient` obtains and refreshes its access token
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AuthMode {
+    /// IBM Cloud IAM, refreshed from `OrchestrateConfig::ibm_api_key` when set
+    #[default]
+    Iam,
+    /// Cloud Pak for Data, authenticated against `/v1/preauth/validateAuth` on `host`
+    Cp4d {
+        /// CP4D host name (without scheme)
+        host: String,
+        /// CP4D user name
+        username: String,
+        /// CP4D API key for the user
+        api_key: String,
+    },
+    /// A token supplied with `with_token`/`set_token`, never refreshed
+    StaticToken,
+}
+
 /// Configuration for WatsonX Orchestrate operations
 #[derive(Clone, Debug)]
 pub struct OrchestrateConfig {
     pub instance_id: String,
     /// Region (defaults to us-south, can be set via WXO_REGION env var)
-    pub region: String,
+    pub region: Region,
     /// Base URL (from WXO_URL env var, with {} placeholder for instance_id)
     pub base_url: String,
+    /// IBM Cloud API key used to refresh the access token automatically
+    pub ibm_api_key: Option<String>,
+    /// Time allowed to establish a connection (defaults to 10 seconds, `None` for no limit)
+    pub connect_timeout: Option<Duration>,
+    /// Time allowed for a streaming request, including the streamed response (defaults to 5 minutes, `None` for no limit)
+    pub request_timeout: Option<Duration>,
+    /// How access tokens are obtained (defaults to IBM Cloud IAM)
+    pub auth_mode: AuthMode,
 }
 
 impl OrchestrateConfig {
@@ -22,34 +125,90 @@ impl OrchestrateConfig {
         let instance_id = env::var("WXO_INSTANCE_ID")
             .map_err(|_| "WXO_INSTANCE_ID must be set in environment variables".to_string())?;
         
-        let region = env::var("WXO_REGION")
-            .unwrap_or_else(|_| "us-south".to_string());
+        let region = match env::var("WXO_REGION") {
+            Ok(value) => value.parse::<Region>()?,
+            Err(_) => Region::UsSouth,
+        };
         
         // Read base URL from WXO_URL env var, with fallback to default pattern
         let base_url = env::var("WXO_URL")
-            .unwrap_or_else(|_| {
-                format!(
-                    "https://{}.watson-orchestrate.cloud.ibm.com/api/v1/",
-                    region
-                )
-            });
+            .unwrap_or_else(|_| region.base_url());
         
         Ok(Self {
             instance_id,
             region,
             base_url,
+            ibm_api_key: None,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            auth_mode: AuthMode::Iam,
         })
     }
 
     /// Create a new Orchestrate configuration with instance ID
     pub fn new(instance_id: String) -> Self {
+        Self::for_region(instance_id, Region::UsSouth)
+    }
+
+    /// Create a configuration for an instance in the given region
+    pub fn for_region(instance_id: String, region: Region) -> Self {
         Self {
             instance_id,
-            region: "us-south".to_string(),
-            base_url: "https://us-south.watson-orchestrate.cloud.ibm.com/api/v1/".to_string(),
+            base_url: region.base_url(),
+            region,
+            ibm_api_key: None,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            request_timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            auth_mode: AuthMode::Iam,
         }
     }
 
+    /// Create a configuration for an instance in Frankfurt (eu-de)
+    pub fn for_eu_de(instance_id: String) -> Self {
+        Self::for_region(instance_id, Region::EuDe)
+    }
+
+    /// Create a configuration for an instance in Tokyo (ap-north)
+    pub fn for_ap_north(instance_id: String) -> Self {
+        Self::for_region(instance_id, Region::ApNorth)
+    }
+
+    /// Create a configuration for an on-prem Cloud Pak for Data deployment
+    ///
+    /// The API is expected at `https://{host}/api/v1/`; change `base_url` if the deployment
+    /// serves it elsewhere. Call `OrchestrateClient::connect` (or `connect_cp4d`) to authenticate.
+    pub fn cp4d_url(host: String, username: String, api_key: String) -> Self {
+        let mut config = Self::for_region(String::new(), Region::Custom(host.clone()));
+        config.auth_mode = AuthMode::Cp4d { host, username, api_key };
+        config
+    }
+
+    /// Set how access tokens are obtained
+    pub fn with_auth_mode(mut self, auth_mode: AuthMode) -> Self {
+        self.auth_mode = auth_mode;
+        self
+    }
+
+    /// Set the IBM Cloud API key used to refresh access tokens before they expire
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.ibm_api_key = Some(api_key.into());
+        self
+    }
+
+    /// Set the time allowed to establish a connection
+    ///
+    /// Keeps failures on unreachable hosts fast even when `request_timeout` is long.
+    pub fn with_connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set the time allowed for a streaming request, including the streamed response
+    pub fn with_request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
     /// Get the base URL with instance ID substituted
     pub fn get_base_url(&self) -> String {
         // Replace {} placeholder with instance_id if present
@@ -5,7 +5,7 @@
 
 use crate::error::{Error, Result};
 use super::client::OrchestrateClient;
-use super::config::OrchestrateConfig;
+use super::config::{OrchestrateConfig, Region};
 
 /// Simplified connection builder for Watson Orchestrate
 /// 
@@ -56,7 +56,7 @@ impl OrchestrateConnection {
         let token = OrchestrateClient::generate_jwt_token(&api_key).await?;
 
         // Create and return authenticated client
-        Ok(OrchestrateClient::new(config).with_token(token))
+        Ok(OrchestrateClient::new(config.with_api_key(api_key)).with_token(token))
     }
 
     /// Initialize with explicit parameters (for programmatic use)
@@ -78,14 +78,9 @@ impl OrchestrateConnection {
         region: &str,
     ) -> Result<OrchestrateClient> {
         // Create config
-        let config = OrchestrateConfig {
-            instance_id: instance_id.to_string(),
-            region: region.to_string(),
-            base_url: format!(
-                "https://{}.watson-orchestrate.cloud.ibm.com/api/v1/",
-                region
-            ),
-        };
+        let region = region.parse::<Region>().map_err(Error::Configuration)?;
+        let config = OrchestrateConfig::for_region(instance_id.to_string(), region)
+            .with_api_key(api_key);
 
         // Generate token
         let token = OrchestrateClient::generate_jwt_token(api_key).await?;
@@ -113,11 +108,18 @@ impl OrchestrateConnection {
         base_url: &str,
     ) -> Result<OrchestrateClient> {
         // Create config with custom URL
-        let config = OrchestrateConfig {
-            instance_id: instance_id.to_string(),
-            region: "custom".to_string(),
-            base_url: base_url.to_string(),
-        };
+        let region = Region::Custom(
+            base_url
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .split('/')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        );
+        let mut config = OrchestrateConfig::for_region(instance_id.to_string(), region)
+            .with_api_key(api_key);
+        config.base_url = base_url.to_string();
 
         // Generate token
         let token = OrchestrateClient::generate_jwt_token(api_key).await?;
@@ -0,0 +1,128 @@
+//! Request/response logging for Orchestrate HTTP calls
+//!
+//! With the `tracing` feature enabled, every request sent through [`SendLogged::send_logged`]
+//! is logged under the `watsonx_rs::orchestrate` target, so `RUST_LOG=watsonx_rs::orchestrate=debug`
+//! shows each call. Successful exchanges log at `debug`; non-2xx responses log at `error` along
+//! with the start of the response body. The `Authorization` header is always redacted.
+
+use reqwest::{RequestBuilder, Response};
+use std::future::Future;
+
+/// Number of characters of an error response body included in the log
+#[cfg(feature = "tracing")]
+const ERROR_BODY_LOG_CHARS: usize = 500;
+
+/// Send a request, logging the exchange when the `tracing` feature is enabled
+pub(crate) trait SendLogged {
+    /// Drop-in replacement for `RequestBuilder::send`
+    fn send_logged(self) -> impl Future<Output = reqwest::Result<Response>> + Send;
+}
+
+impl SendLogged for RequestBuilder {
+    #[cfg(not(feature = "tracing"))]
+    fn send_logged(self) -> impl Future<Output = reqwest::Result<Response>> + Send {
+        self.send()
+    }
+
+    #[cfg(feature = "tracing")]
+    async fn send_logged(self) -> reqwest::Result<Response> {
+        let (client, request) = self.build_split();
+        let request = request?;
+        let method = request.method().clone();
+        let url = request.url().clone();
+        let authorization = request
+            .headers()
+            .get(reqwest::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .map(redact_authorization);
+
+        tracing::debug!(
+            target: "watsonx_rs::orchestrate",
+            %method,
+            %url,
+            authorization = authorization.as_deref().unwrap_or("<none>"),
+            "Sending Orchestrate request"
+        );
+
+        let response = match client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::error!(target: "watsonx_rs::orchestrate", %method, %url, error = %e, "Orchestrate request failed");
+                return Err(e);
+            }
+        };
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("<none>")
+            .to_string();
+
+        if status.is_success() {
+            tracing::debug!(
+                target: "watsonx_rs::orchestrate",
+                %method,
+                %url,
+                status = status.as_u16(),
+                content_type,
+                "Orchestrate response"
+            );
+            return Ok(response);
+        }
+
+        // Read the body so it can be logged, then hand the caller an equivalent response
+        let version = response.version();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        tracing::error!(
+            target: "watsonx_rs::orchestrate",
+            %method,
+            %url,
+            status = status.as_u16(),
+            content_type,
+            body = %truncate_body(&body),
+            "Orchestrate request returned an error status"
+        );
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.version_mut() = version;
+        *rebuilt.headers_mut() = headers;
+        Ok(Response::from(rebuilt))
+    }
+}
+
+/// Replace the credential in an `Authorization` header value, keeping the scheme
+#[cfg(feature = "tracing")]
+fn redact_authorization(value: &str) -> String {
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{} [REDACTED]", scheme),
+        None => "[REDACTED]".to_string(),
+    }
+}
+
+/// First `ERROR_BODY_LOG_CHARS` characters of a response body
+#[cfg(feature = "tracing")]
+fn truncate_body(body: &[u8]) -> String {
+    String::from_utf8_lossy(body).chars().take(ERROR_BODY_LOG_CHARS).collect()
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_authorization() {
+        assert_eq!(redact_authorization("Bearer secret-token"), "Bearer [REDACTED]");
+        assert_eq!(redact_authorization("secret-token"), "[REDACTED]");
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        let body = "x".repeat(ERROR_BODY_LOG_CHARS + 10);
+        assert_eq!(truncate_body(body.as_bytes()).len(), ERROR_BODY_LOG_CHARS);
+        assert_eq!(truncate_body(b"not found"), "not found");
+    }
+}
@@ -23,8 +23,12 @@ pub mod run;
 pub mod collection;
 pub mod chat;
 pub mod connection;
+pub mod webhook;
+pub(crate) mod rate_limit;
+pub(crate) mod http_log;
 
-pub use config::OrchestrateConfig;
+pub use config::{AuthMode, OrchestrateConfig, Region};
 pub use client::OrchestrateClient;
 pub use connection::OrchestrateConnection;
+pub use chat::{OrchestrateMessageBuilder, OrchestrateMessageRequest};
 pub use types::*;
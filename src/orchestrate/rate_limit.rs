@@ -0,0 +1,112 @@
+//! Token-bucket rate limiting for tools and threads
+
+use super::types::RateLimiterStats;
+use super::OrchestrateClient;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token-bucket rate limiter
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    max_rps: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+    total_acquired: u64,
+    total_wait: Duration,
+}
+
+impl TokenBucket {
+    /// Create a limiter allowing `max_rps` requests per second, with a burst of one second's worth
+    pub(crate) fn new(max_rps: f64) -> Self {
+        let capacity = max_rps.ceil().max(1.0);
+        Self {
+            max_rps,
+            capacity,
+            tokens: capacity,
+            last_refill: Instant::now(),
+            total_acquired: 0,
+            total_wait: Duration::ZERO,
+        }
+    }
+
+    /// Reserve one token, returning how long the caller must wait before using it
+    pub(crate) fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.max_rps).min(self.capacity);
+        self.last_refill = now;
+
+        self.tokens -= 1.0;
+        self.total_acquired += 1;
+
+        if self.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let wait = Duration::from_secs_f64(-self.tokens / self.max_rps);
+        self.total_wait += wait;
+        wait
+    }
+
+    /// Snapshot of the limiter state
+    pub(crate) fn stats(&self) -> RateLimiterStats {
+        RateLimiterStats {
+            max_rps: self.max_rps,
+            available_tokens: self.tokens.max(0.0),
+            total_acquired: self.total_acquired,
+            total_wait: self.total_wait,
+        }
+    }
+}
+
+impl OrchestrateClient {
+    /// Limit messages sent on each thread to `max_rps_per_thread` requests per second
+    ///
+    /// Every thread gets its own limiter, so a runaway thread cannot starve the others.
+    /// Messages without a thread ID are not limited. Non-positive rates are ignored.
+    pub fn with_thread_rate_limiter(mut self, max_rps_per_thread: f64) -> Self {
+        if max_rps_per_thread > 0.0 {
+            self.thread_rate_limit = Some(max_rps_per_thread);
+        }
+        self
+    }
+
+    /// Get rate limiter statistics for a thread, if it has sent messages under a limit
+    pub fn thread_rate_limiter_stats(&self, thread_id: &str) -> Option<RateLimiterStats> {
+        self.thread_rate_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(thread_id)
+            .map(TokenBucket::stats)
+    }
+
+    /// Drop the rate limiter state of a thread
+    ///
+    /// Called automatically by `delete_thread`.
+    pub fn thread_rate_limiter_remove(&self, thread_id: &str) {
+        self.thread_rate_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(thread_id);
+    }
+
+    /// Wait for the thread's rate limiter (if enabled) to allow another message
+    pub(crate) async fn acquire_thread_rate_limit(&self, thread_id: Option<&str>) {
+        let (Some(max_rps), Some(thread_id)) = (self.thread_rate_limit, thread_id) else {
+            return;
+        };
+
+        let wait = self
+            .thread_rate_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(thread_id.to_string())
+            .or_insert_with(|| TokenBucket::new(max_rps))
+            .reserve();
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
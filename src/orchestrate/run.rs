@@ -1,15 +1,16 @@
 //! Run management operations
 
 use crate::error::{Error, Result};
-use super::types::RunInfo;
+use super::types::{Message, RunInfo, ToolCall};
 use super::OrchestrateClient;
+use super::client::{parse_list_response, retry_request, send_error};
+use super::http_log::SendLogged;
+use futures::TryFutureExt;
 
 impl OrchestrateClient {
     /// Get information about a specific run
     pub async fn get_run(&self, run_id: &str) -> Result<RunInfo> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/runs/{}", base_url, run_id);
@@ -19,7 +20,7 @@ impl OrchestrateClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -45,9 +46,7 @@ impl OrchestrateClient {
 
     /// List all runs for an agent
     pub async fn list_runs(&self, agent_id: Option<&str>) -> Result<Vec<RunInfo>> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = if let Some(agent_id) = agent_id {
@@ -56,14 +55,18 @@ impl OrchestrateClient {
             format!("{}/runs", base_url)
         };
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -82,31 +85,12 @@ impl OrchestrateClient {
             .await
             .map_err(|e| Error::Serialization(e.to_string()))?;
 
-        if let Ok(runs) = serde_json::from_str::<Vec<RunInfo>>(&text) {
-            return Ok(runs);
-        }
-
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(runs_array) = obj.get("runs").and_then(|r| r.as_array()) {
-                let runs: Result<Vec<RunInfo>> = runs_array
-                    .iter()
-                    .map(|run| {
-                        serde_json::from_value::<RunInfo>(run.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return runs;
-            }
-        }
-
-        Ok(Vec::new())
+        parse_list_response::<RunInfo>(&text, "runs")
     }
 
     /// Cancel a running execution
     pub async fn cancel_run(&self, run_id: &str) -> Result<()> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/runs/{}/cancel", base_url, run_id);
@@ -116,7 +100,7 @@ impl OrchestrateClient {
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -134,4 +118,84 @@ impl OrchestrateClient {
 
         Ok(())
     }
+
+    /// Get the tool calls made during a run
+    pub async fn get_run_tool_calls(&self, run_id: &str) -> Result<Vec<ToolCall>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/runs/{}/tool_calls", base_url, run_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to get tool calls for run {}: {} - {}",
+                run_id, status, error_text
+            )));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(ToolCall::list_from_response(&body))
+    }
+
+    /// Get the messages exchanged during a run
+    pub async fn get_run_messages(&self, run_id: &str) -> Result<Vec<Message>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/runs/{}/messages", base_url, run_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to get messages for run {}: {} - {}",
+                run_id, status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        parse_list_response::<Message>(&text, "messages")
+    }
 }
@@ -1,9 +1,13 @@
 //! Thread management operations
 
 use crate::error::{Error, Result};
-use super::types::{ThreadInfo, Message};
+use super::types::{CreateThreadRequest, Message, SendMessageResult, ThreadInfo};
 use super::OrchestrateClient;
+use super::client::{parse_list_response, retry_request, send_error};
+use super::http_log::SendLogged;
+use futures::TryFutureExt;
 use serde_json::Value;
+use std::collections::HashMap;
 
 #[derive(serde::Deserialize)]
 struct EventData {
@@ -14,9 +18,7 @@ struct EventData {
 impl OrchestrateClient {
     /// List all threads for an agent
     pub async fn list_threads(&self, agent_id: Option<&str>) -> Result<Vec<ThreadInfo>> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = if let Some(agent_id) = agent_id {
@@ -25,14 +27,18 @@ impl OrchestrateClient {
             format!("{}/threads", base_url)
         };
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -54,27 +60,75 @@ impl OrchestrateClient {
         Ok(threads)
     }
 
+    /// Get a specific thread
+    pub async fn get_thread(&self, thread_id: &str) -> Result<ThreadInfo> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/threads/{}", base_url, thread_id);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to get thread {}: {} - {}",
+                thread_id, status, error_text
+            )));
+        }
+
+        let thread: ThreadInfo = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(thread)
+    }
+
+    /// Get the metadata attached to a thread (empty if the thread has none)
+    pub async fn get_thread_metadata(&self, thread_id: &str) -> Result<HashMap<String, Value>> {
+        let thread = self.get_thread(thread_id).await?;
+        Ok(thread.metadata.unwrap_or_default())
+    }
+
     /// Create a new thread for conversation
     pub async fn create_thread(&self, agent_id: Option<&str>) -> Result<ThreadInfo> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let request = CreateThreadRequest {
+            agent_id: agent_id.map(str::to_string),
+            ..Default::default()
+        };
+        self.create_thread_with_request(&request).await
+    }
+
+    /// Create a new thread with metadata and initial context
+    pub async fn create_thread_with_request(&self, request: &CreateThreadRequest) -> Result<ThreadInfo> {
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/threads", base_url);
 
-        let mut body = serde_json::json!({});
-        if let Some(agent_id) = agent_id {
-            body["agent_id"] = serde_json::json!(agent_id);
-        }
-
         let response = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+            .json(request)
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -98,11 +152,22 @@ impl OrchestrateClient {
         Ok(thread)
     }
 
+    /// Create a thread for an agent and send the first message to it
+    pub async fn create_thread_with_message(
+        &self,
+        agent_id: &str,
+        initial_message: &str,
+    ) -> Result<(ThreadInfo, SendMessageResult)> {
+        let thread = self.create_thread(Some(agent_id)).await?;
+        let result = self
+            .send_message(agent_id, initial_message, Some(thread.thread_id.clone()), None)
+            .await?;
+        Ok((thread, result))
+    }
+
     /// Delete a thread
     pub async fn delete_thread(&self, thread_id: &str) -> Result<()> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/threads/{}", base_url, thread_id);
@@ -111,7 +176,7 @@ impl OrchestrateClient {
             .client
             .delete(&url)
             .header("Authorization", format!("Bearer {}", api_key))
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -127,14 +192,13 @@ impl OrchestrateClient {
             )));
         }
 
+        self.thread_rate_limiter_remove(thread_id);
         Ok(())
     }
 
     /// Get conversation history from a thread
     pub async fn get_thread_messages(&self, thread_id: &str) -> Result<Vec<Message>> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/threads/{}/messages", base_url, thread_id);
@@ -144,7 +208,7 @@ impl OrchestrateClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -160,31 +224,11 @@ impl OrchestrateClient {
             )));
         }
 
-        // Try to parse as direct array first
         let text = response
             .text()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
-        if let Ok(messages) = serde_json::from_str::<Vec<Message>>(&text) {
-            return Ok(messages);
-        }
-
-        // Try to extract from wrapper object
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(messages_array) = obj.get("messages").and_then(|m| m.as_array()) {
-                let messages: Result<Vec<Message>> = messages_array
-                    .iter()
-                    .map(|item| {
-                        serde_json::from_value::<Message>(item.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return messages;
-            }
-        }
-
-        // Fallback to empty vec
-        Ok(Vec::new())
+        parse_list_response::<Message>(&text, "messages")
     }
 }
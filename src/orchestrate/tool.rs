@@ -1,27 +1,105 @@
 //! Tool management operations
 
 use crate::error::{Error, Result};
-use super::types::{Tool, ToolExecutionRequest, ToolExecutionResult, ToolUpdateRequest, ToolTestRequest, ToolTestResult, ToolExecutionHistory, ToolVersion};
+use super::types::{Tool, CreateToolRequest, ToolFilter, ListOptions, Page, ToolExecutionRequest, ToolExecutionResult, ToolUpdateRequest, ToolTestRequest, ToolTestResult, ToolExecutionHistory, ToolVersion, RateLimiterStats};
 use super::OrchestrateClient;
+use super::client::{parse_list_response, retry_request, send_error};
+use super::rate_limit::TokenBucket;
+use super::http_log::SendLogged;
+use futures::TryFutureExt;
+use std::path::Path;
+
+/// Format of an OpenAPI document
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OpenApiFormat {
+    Json,
+    Yaml,
+}
+
+impl OpenApiFormat {
+    /// Detect the format from the file extension, falling back to the content
+    pub(crate) fn detect(path: &Path, content: &str) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("json") => Self::Json,
+            Some("yaml") | Some("yml") => Self::Yaml,
+            _ if content.trim_start().starts_with('{') => Self::Json,
+            _ => Self::Yaml,
+        }
+    }
+}
+
+/// Parse an OpenAPI document in the given format
+pub(crate) fn parse_openapi_spec(content: &str, format: OpenApiFormat) -> Result<serde_json::Value> {
+    match format {
+        OpenApiFormat::Json => serde_json::from_str(content)
+            .map_err(|e| Error::Serialization(format!("Failed to parse OpenAPI JSON: {}", e))),
+        #[cfg(feature = "yaml")]
+        OpenApiFormat::Yaml => serde_yaml::from_str(content)
+            .map_err(|e| Error::Serialization(format!("Failed to parse OpenAPI YAML: {}", e))),
+        #[cfg(not(feature = "yaml"))]
+        OpenApiFormat::Yaml => Err(Error::Configuration(
+            "Parsing YAML OpenAPI specifications requires the `yaml` feature".to_string(),
+        )),
+    }
+}
 
 impl OrchestrateClient {
+    /// Limit executions of a tool to `max_rps` requests per second
+    ///
+    /// The limiter is shared by every `execute_tool` call on this client. Non-positive
+    /// rates are ignored.
+    pub fn with_tool_rate_limit(self, tool_id: &str, max_rps: f64) -> Self {
+        if max_rps > 0.0 {
+            self.tool_rate_limiters
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(tool_id.to_string(), TokenBucket::new(max_rps));
+        }
+        self
+    }
+
+    /// Get rate limiter statistics for a tool, if it is rate limited
+    pub fn tool_rate_limiter_stats(&self, tool_id: &str) -> Option<RateLimiterStats> {
+        self.tool_rate_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tool_id)
+            .map(TokenBucket::stats)
+    }
+
+    /// Wait for the tool's rate limiter (if any) to allow another execution
+    async fn acquire_tool_rate_limit(&self, tool_id: &str) {
+        let wait = self
+            .tool_rate_limiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(tool_id)
+            .map(TokenBucket::reserve);
+
+        if let Some(wait) = wait.filter(|w| !w.is_zero()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
     /// List all tools
     pub async fn list_tools(&self) -> Result<Vec<Tool>> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/tools", base_url);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -40,31 +118,43 @@ impl OrchestrateClient {
             .await
             .map_err(|e| Error::Serialization(e.to_string()))?;
 
-        if let Ok(tools) = serde_json::from_str::<Vec<Tool>>(&text) {
-            return Ok(tools);
-        }
+        parse_list_response::<Tool>(&text, "tools")
+    }
 
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(tools_array) = obj.get("tools").and_then(|t| t.as_array()) {
-                let tools: Result<Vec<Tool>> = tools_array
-                    .iter()
-                    .map(|tool| {
-                        serde_json::from_value::<Tool>(tool.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return tools;
-            }
-        }
+    /// List tools matching a filter, one page at a time
+    ///
+    /// The tools API has no server-side filtering or paging, so both are applied client-side.
+    pub async fn list_tools_with_filter(&self, filter: &ToolFilter, options: ListOptions) -> Result<Page<Tool>> {
+        let tools = self.list_tools().await?;
+        let matching = tools.into_iter().filter(|t| filter.matches(t)).collect();
+        Ok(Page::from_items(matching, &options))
+    }
 
-        Ok(Vec::new())
+    /// Search tools by a case-insensitive substring of their name or description
+    pub async fn search_tools(&self, query: &str) -> Result<Vec<Tool>> {
+        let query = query.to_lowercase();
+        let tools = self.list_tools().await?;
+        Ok(tools
+            .into_iter()
+            .filter(|tool| {
+                tool.name.to_lowercase().contains(&query)
+                    || tool
+                        .description
+                        .as_ref()
+                        .is_some_and(|d| d.to_lowercase().contains(&query))
+            })
+            .collect())
+    }
+
+    /// Find a tool by its exact name
+    pub async fn find_tool_by_name(&self, name: &str) -> Result<Option<Tool>> {
+        let tools = self.list_tools().await?;
+        Ok(tools.into_iter().find(|t| t.name == name))
     }
 
     /// Get a specific tool by ID
     pub async fn get_tool(&self, tool_id: &str) -> Result<Tool> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/tools/{}", base_url, tool_id);
@@ -74,7 +164,7 @@ impl OrchestrateClient {
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -99,10 +189,13 @@ impl OrchestrateClient {
     }
 
     /// Execute a tool directly
+    ///
+    /// If a rate limit was registered for the tool with `with_tool_rate_limit`, this waits
+    /// for the next available slot before sending the request.
     pub async fn execute_tool(&self, request: ToolExecutionRequest) -> Result<ToolExecutionResult> {
-        let api_key = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (API key) first.".to_string())
-        })?;
+        self.acquire_tool_rate_limit(&request.tool_id).await;
+
+        let api_key = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/tools/{}/execute", base_url, request.tool_id);
@@ -113,7 +206,7 @@ impl OrchestrateClient {
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -139,9 +232,7 @@ impl OrchestrateClient {
 
     /// Update a tool
     pub async fn update_tool(&self, tool_id: &str, request: ToolUpdateRequest) -> Result<Tool> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/orchestrate/tools/{}", base_url, tool_id);
@@ -153,7 +244,7 @@ impl OrchestrateClient {
             .header("Content-Type", "application/json")
             .header("X-Instance-ID", &self.config.instance_id)
             .json(&request)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -177,11 +268,95 @@ impl OrchestrateClient {
         Ok(tool)
     }
 
+    /// Create a tool
+    ///
+    /// Returns `Error::InvalidInput` without sending anything if the request has neither an
+    /// OpenAPI specification nor an endpoint.
+    pub async fn create_tool(&self, request: CreateToolRequest) -> Result<Tool> {
+        request.validate()?;
+        let token = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/orchestrate/tools", base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .header("X-Instance-ID", &self.config.instance_id)
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to create tool: {} - {}",
+                status, error_text
+            )));
+        }
+
+        let tool: Tool = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(tool)
+    }
+
+    /// Create a tool from an OpenAPI specification file
+    ///
+    /// The format is taken from the `.json`, `.yaml` or `.yml` extension, or detected from
+    /// the content for other extensions. YAML requires the `yaml` feature.
+    pub async fn import_tool_from_openapi_file(&self, path: impl AsRef<Path>) -> Result<Tool> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Io(format!("Failed to read OpenAPI specification {}: {}", path.display(), e))
+        })?;
+
+        let spec = parse_openapi_spec(&content, OpenApiFormat::detect(path, &content))?;
+        self.create_tool(CreateToolRequest::from_openapi(spec)?).await
+    }
+
+    /// Create a tool from every `.json`, `.yaml` and `.yml` file in a directory
+    ///
+    /// Files are processed in name order, and each file's outcome is returned
+    /// separately so one invalid specification does not stop the rest.
+    pub async fn import_tools_from_openapi_dir(&self, dir: impl AsRef<Path>) -> Result<Vec<Result<Tool>>> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir).map_err(|e| {
+            Error::Io(format!("Failed to read directory {}: {}", dir.display(), e))
+        })?;
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref(),
+                        Some("json") | Some("yaml") | Some("yml")
+                    )
+            })
+            .collect();
+        paths.sort();
+
+        let mut results = Vec::with_capacity(paths.len());
+        for path in paths {
+            results.push(self.import_tool_from_openapi_file(&path).await);
+        }
+
+        Ok(results)
+    }
+
     /// Delete a tool
     pub async fn delete_tool(&self, tool_id: &str) -> Result<()> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/orchestrate/tools/{}", base_url, tool_id);
@@ -191,7 +366,7 @@ impl OrchestrateClient {
             .delete(&url)
             .header("Authorization", format!("Bearer {}", token))
             .header("X-Instance-ID", &self.config.instance_id)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -212,9 +387,7 @@ impl OrchestrateClient {
 
     /// Test a tool with sample input
     pub async fn test_tool(&self, request: ToolTestRequest) -> Result<ToolTestResult> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/orchestrate/tools/{}/test", base_url, request.tool_id);
@@ -226,7 +399,7 @@ impl OrchestrateClient {
             .header("Content-Type", "application/json")
             .header("X-Instance-ID", &self.config.instance_id)
             .json(&request)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -252,9 +425,7 @@ impl OrchestrateClient {
 
     /// Get tool execution history
     pub async fn get_tool_execution_history(&self, tool_id: &str, limit: Option<u32>) -> Result<Vec<ToolExecutionHistory>> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let mut url = format!("{}/orchestrate/tools/{}/execution-history", base_url, tool_id);
@@ -269,7 +440,7 @@ impl OrchestrateClient {
             .header("Authorization", format!("Bearer {}", token))
             .header("Content-Type", "application/json")
             .header("X-Instance-ID", &self.config.instance_id)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -290,31 +461,12 @@ impl OrchestrateClient {
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
-        if let Ok(history) = serde_json::from_str::<Vec<ToolExecutionHistory>>(&text) {
-            return Ok(history);
-        }
-
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(history_array) = obj.get("history").and_then(|h| h.as_array()) {
-                let history: Result<Vec<ToolExecutionHistory>> = history_array
-                    .iter()
-                    .map(|item| {
-                        serde_json::from_value::<ToolExecutionHistory>(item.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return history;
-            }
-        }
-
-        Ok(Vec::new())
+        parse_list_response::<ToolExecutionHistory>(&text, "history")
     }
 
     /// Get tool versions
     pub async fn get_tool_versions(&self, tool_id: &str) -> Result<Vec<ToolVersion>> {
-        let token = self.access_token.as_ref().ok_or_else(|| {
-            Error::Authentication("Not authenticated. Set access token (Bearer token) first.".to_string())
-        })?;
+        let token = self.ensure_token_valid().await?;
 
         let base_url = self.config.get_base_url();
         let url = format!("{}/orchestrate/tools/{}/versions", base_url, tool_id);
@@ -325,7 +477,7 @@ impl OrchestrateClient {
             .header("Authorization", format!("Bearer {}", token))
             .header("Content-Type", "application/json")
             .header("X-Instance-ID", &self.config.instance_id)
-            .send()
+            .send_logged()
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
@@ -346,23 +498,6 @@ impl OrchestrateClient {
             .await
             .map_err(|e| Error::Network(e.to_string()))?;
 
-        if let Ok(versions) = serde_json::from_str::<Vec<ToolVersion>>(&text) {
-            return Ok(versions);
-        }
-
-        if let Ok(obj) = serde_json::from_str::<serde_json::Value>(&text) {
-            if let Some(versions_array) = obj.get("versions").and_then(|v| v.as_array()) {
-                let versions: Result<Vec<ToolVersion>> = versions_array
-                    .iter()
-                    .map(|item| {
-                        serde_json::from_value::<ToolVersion>(item.clone())
-                            .map_err(|e| Error::Serialization(e.to_string()))
-                    })
-                    .collect();
-                return versions;
-            }
-        }
-
-        Ok(Vec::new())
+        parse_list_response::<ToolVersion>(&text, "versions")
     }
 }
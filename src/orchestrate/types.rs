@@ -19,6 +19,229 @@ pub struct Agent {
     /// Agent display name from API (field name: "display_name")
     #[serde(rename = "display_name")]
     pub name: String,
+    /// Agent description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Agent status (e.g. "active")
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Model used by the agent
+    #[serde(default, alias = "llm")]
+    pub model_id: Option<String>,
+    /// Created timestamp
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Updated timestamp
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// IDs of tools attached to the agent
+    #[serde(default)]
+    pub tool_ids: Option<Vec<String>>,
+    /// IDs of skills attached to the agent
+    #[serde(default)]
+    pub skill_ids: Option<Vec<String>>,
+    /// Instructions given to the agent's model
+    #[serde(default, alias = "system_prompt")]
+    pub instructions: Option<String>,
+    /// Languages the agent is configured to answer in
+    #[serde(default)]
+    pub supported_languages: Option<Vec<String>>,
+    /// Context window of the agent's model, in tokens
+    #[serde(default)]
+    pub max_context_tokens: Option<u32>,
+    /// Tags grouping the agent by team, environment or domain
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Document collections the agent searches for every user query
+    #[serde(default)]
+    pub knowledge_base_collection_ids: Option<Vec<String>>,
+}
+
+/// Request to create an agent
+#[derive(Clone, Debug, Serialize)]
+pub struct CreateAgentRequest {
+    /// Agent display name
+    #[serde(rename = "display_name")]
+    pub name: String,
+    /// Agent description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Model used by the agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    /// Instructions given to the agent's model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// IDs of tools to attach to the agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_ids: Option<Vec<String>>,
+    /// Document collections searched (RAG) for every user query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub knowledge_base_collection_ids: Option<Vec<String>>,
+    /// Search settings for the knowledge base; `query` is replaced by each user message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub knowledge_base_search_config: Option<SearchRequest>,
+}
+
+impl CreateAgentRequest {
+    /// Create a request for an agent with the given display name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            model_id: None,
+            instructions: None,
+            tool_ids: None,
+            knowledge_base_collection_ids: None,
+            knowledge_base_search_config: None,
+        }
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the model
+    pub fn with_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = Some(model_id.into());
+        self
+    }
+
+    /// Set the instructions
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Attach tools to the agent
+    pub fn with_tool_ids(mut self, tool_ids: Vec<String>) -> Self {
+        self.tool_ids = Some(tool_ids);
+        self
+    }
+
+    /// Search these document collections for every user query
+    pub fn with_knowledge_base(mut self, collection_ids: Vec<String>) -> Self {
+        self.knowledge_base_collection_ids = Some(collection_ids);
+        self
+    }
+
+    /// Set how the knowledge base is searched (result limit, threshold, filters)
+    pub fn with_knowledge_base_search_config(mut self, search_config: SearchRequest) -> Self {
+        self.knowledge_base_search_config = Some(search_config);
+        self
+    }
+}
+
+/// Request to update an agent
+///
+/// Only the fields that are set are sent, so everything else keeps its current value.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct UpdateAgentRequest {
+    /// New display name
+    #[serde(rename = "display_name", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// New model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    /// New instructions for the agent's model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// IDs of tools to attach, replacing the current ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_ids: Option<Vec<String>>,
+}
+
+impl UpdateAgentRequest {
+    /// Create a request that changes nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the display name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the model
+    pub fn with_model_id(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = Some(model_id.into());
+        self
+    }
+
+    /// Set the instructions
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Replace the attached tools
+    pub fn with_tool_ids(mut self, tool_ids: Vec<String>) -> Self {
+        self.tool_ids = Some(tool_ids);
+        self
+    }
+}
+
+/// Everything an agent can do, resolved from its configuration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentCapabilities {
+    /// The agent itself
+    pub agent: Agent,
+    /// Tools attached to the agent
+    pub tools: Vec<Tool>,
+    /// Skills assigned to the agent
+    pub skills: Vec<Skill>,
+    /// Model used by the agent (empty if not reported)
+    pub model_id: String,
+    /// Instructions given to the agent's model
+    pub system_prompt: Option<String>,
+    /// Languages the agent is configured to answer in
+    pub supported_languages: Option<Vec<String>>,
+    /// Context window of the agent's model, in tokens
+    pub max_context_tokens: Option<u32>,
+}
+
+impl AgentCapabilities {
+    /// Combine an agent with its resolved tools and skills
+    pub fn new(agent: Agent, tools: Vec<Tool>, skills: Vec<Skill>) -> Self {
+        Self {
+            model_id: agent.model_id.clone().unwrap_or_default(),
+            system_prompt: agent.instructions.clone(),
+            supported_languages: agent.supported_languages.clone(),
+            max_context_tokens: agent.max_context_tokens,
+            agent,
+            tools,
+            skills,
+        }
+    }
+
+    /// Whether the agent has a tool with this ID or name
+    pub fn has_tool(&self, tool: &str) -> bool {
+        self.tools.iter().any(|t| t.id == tool || t.name == tool)
+    }
+
+    /// Whether the agent has a skill with this ID or name
+    pub fn has_skill(&self, skill: &str) -> bool {
+        self.skills.iter().any(|s| s.id == skill || s.name == skill)
+    }
+
+    /// Whether the agent answers in this language (assumed when no languages are reported)
+    pub fn supports_language(&self, language: &str) -> bool {
+        self.supported_languages
+            .as_ref()
+            .is_none_or(|languages| languages.iter().any(|l| l.eq_ignore_ascii_case(language)))
+    }
 }
 
 /// Custom Assistant information
@@ -111,6 +334,69 @@ pub struct Skill {
     pub version: Option<String>,
 }
 
+/// Skill version information
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkillVersion {
+    /// Version ID
+    #[serde(alias = "id")]
+    pub version_id: String,
+    /// Created timestamp
+    #[serde(default)]
+    pub created_at: String,
+    /// Whether this version is the active one
+    #[serde(default, alias = "active")]
+    pub is_active: bool,
+    /// Description of the changes in this version
+    #[serde(default)]
+    pub changelog: Option<String>,
+}
+
+/// Request to create a skill
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateSkillRequest {
+    /// Skill name
+    pub name: String,
+    /// Skill description
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Skill type
+    pub skill_type: SkillType,
+    /// Input parameter definitions
+    #[serde(default)]
+    pub input_params: HashMap<String, ParameterDefinition>,
+    /// Output parameter definitions
+    #[serde(default)]
+    pub output_params: HashMap<String, ParameterDefinition>,
+    /// OpenAPI specification backing the skill
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub openapi_spec: Option<serde_json::Value>,
+}
+
+/// Request to update a skill
+///
+/// Only the fields that are set are sent, so everything else keeps its current value.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UpdateSkillRequest {
+    /// New skill name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New skill description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// New skill type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skill_type: Option<SkillType>,
+    /// Input parameter definitions, replacing the current ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_params: Option<HashMap<String, ParameterDefinition>>,
+    /// Output parameter definitions, replacing the current ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_params: Option<HashMap<String, ParameterDefinition>>,
+    /// OpenAPI specification backing the skill
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub openapi_spec: Option<serde_json::Value>,
+}
+
 /// Skill type enumeration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SkillType {
@@ -217,7 +503,7 @@ pub struct Tool {
 }
 
 /// Tool type enumeration
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ToolType {
     /// API tool
     Api,
@@ -231,6 +517,63 @@ pub enum ToolType {
     Custom(String),
 }
 
+/// Client-side filter for listing tools
+#[derive(Clone, Debug, Default)]
+pub struct ToolFilter {
+    /// Only tools whose name contains this text (case-insensitive)
+    pub name_contains: Option<String>,
+    /// Only tools of this type
+    pub tool_type: Option<ToolType>,
+    /// Only enabled tools
+    pub enabled_only: bool,
+}
+
+impl ToolFilter {
+    /// Create an empty filter that matches every tool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by a substring of the tool name
+    pub fn with_name_contains(mut self, text: impl Into<String>) -> Self {
+        self.name_contains = Some(text.into());
+        self
+    }
+
+    /// Filter by tool type
+    pub fn with_tool_type(mut self, tool_type: ToolType) -> Self {
+        self.tool_type = Some(tool_type);
+        self
+    }
+
+    /// Only include enabled tools
+    pub fn with_enabled_only(mut self, enabled_only: bool) -> Self {
+        self.enabled_only = enabled_only;
+        self
+    }
+
+    /// Check whether a tool matches this filter
+    pub fn matches(&self, tool: &Tool) -> bool {
+        if let Some(text) = &self.name_contains {
+            if !tool.name.to_lowercase().contains(&text.to_lowercase()) {
+                return false;
+            }
+        }
+
+        if let Some(tool_type) = &self.tool_type {
+            if tool.tool_type.as_ref() != Some(tool_type) {
+                return false;
+            }
+        }
+
+        if self.enabled_only && !tool.enabled {
+            return false;
+        }
+
+        true
+    }
+}
+
 /// Tool configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolConfig {
@@ -291,10 +634,92 @@ pub struct DocumentCollection {
     pub document_count: u32,
     /// Vector index configuration
     pub vector_index: Option<VectorIndexConfig>,
+    /// Collection tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Collection metadata
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Client-side filter for `list_collections_with_filter`
+///
+/// Every criterion that is set must match; unset criteria match everything.
+#[derive(Clone, Debug, Default)]
+pub struct CollectionFilter {
+    /// Only collections with this status
+    pub status: Option<CollectionStatus>,
+    /// Only collections whose name contains this text (case-insensitive)
+    pub name_contains: Option<String>,
+    /// Only collections carrying all of these tags
+    pub tags: Option<Vec<String>>,
+    /// Only collections with (`true`) or without (`false`) documents
+    pub has_documents: Option<bool>,
+}
+
+impl CollectionFilter {
+    /// Create an empty filter that matches every collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by status
+    pub fn with_status(mut self, status: CollectionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Filter by a substring of the collection name
+    pub fn with_name_contains(mut self, text: impl Into<String>) -> Self {
+        self.name_contains = Some(text.into());
+        self
+    }
+
+    /// Filter by required tags
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Filter by whether the collection has documents
+    pub fn with_has_documents(mut self, has_documents: bool) -> Self {
+        self.has_documents = Some(has_documents);
+        self
+    }
+
+    /// Check whether a collection matches this filter
+    pub fn matches(&self, collection: &DocumentCollection) -> bool {
+        if let Some(status) = &self.status {
+            if &collection.status != status {
+                return false;
+            }
+        }
+
+        if let Some(text) = &self.name_contains {
+            if !collection.name.to_lowercase().contains(&text.to_lowercase()) {
+                return false;
+            }
+        }
+
+        if let Some(tags) = &self.tags {
+            let collection_tags = collection.tags.as_deref().unwrap_or(&[]);
+            if !tags.iter().all(|tag| collection_tags.contains(tag)) {
+                return false;
+            }
+        }
+
+        if let Some(has_documents) = self.has_documents {
+            if (collection.document_count > 0) != has_documents {
+                return false;
+            }
+        }
+
+        true
+    }
 }
 
 /// Collection status enumeration
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum CollectionStatus {
     /// Collection is active
     Active,
@@ -306,6 +731,43 @@ pub enum CollectionStatus {
     Error,
 }
 
+/// State of a collection reindex job
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReindexStatus {
+    /// Job is waiting to start
+    #[serde(rename = "queued")]
+    Queued,
+    /// Documents are being re-embedded
+    #[serde(rename = "in_progress", alias = "running")]
+    InProgress,
+    /// Index rebuilt successfully
+    #[serde(rename = "completed")]
+    Completed,
+    /// Reindexing failed
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+impl ReindexStatus {
+    /// Check whether the job has finished, successfully or not
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ReindexStatus::Completed | ReindexStatus::Failed)
+    }
+}
+
+/// A job rebuilding a collection's vector index
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReindexJob {
+    /// Job ID
+    #[serde(alias = "id")]
+    pub job_id: String,
+    /// Current job state
+    pub status: ReindexStatus,
+    /// Server estimate of the total job duration
+    #[serde(default)]
+    pub estimated_duration_secs: Option<u64>,
+}
+
 /// Vector index configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VectorIndexConfig {
@@ -388,6 +850,49 @@ pub struct Message {
     pub content: String,
 }
 
+/// Content of a file attached to a message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentData {
+    /// Base64-encoded file content
+    Base64(String),
+    /// URL the file can be fetched from
+    Url(String),
+}
+
+/// A file attached to a message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MessageAttachment {
+    /// File name
+    pub filename: String,
+    /// MIME type (e.g. "application/pdf")
+    pub content_type: String,
+    /// File content or location
+    pub data: AttachmentData,
+}
+
+impl MessageAttachment {
+    /// Create an attachment from raw bytes, base64-encoding them
+    pub fn from_bytes(filename: impl Into<String>, content_type: impl Into<String>, bytes: &[u8]) -> Self {
+        use base64::Engine;
+
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            data: AttachmentData::Base64(base64::engine::general_purpose::STANDARD.encode(bytes)),
+        }
+    }
+
+    /// Create an attachment referring to a file by URL
+    pub fn from_url(filename: impl Into<String>, content_type: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            data: AttachmentData::Url(url.into()),
+        }
+    }
+}
+
 /// Message payload for Watson Orchestrate API
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MessagePayload {
@@ -399,6 +904,271 @@ pub struct MessagePayload {
     pub agent_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thread_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<MessageAttachment>,
+    /// Earlier conversation turns, for deployments that do not keep thread history
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_messages: Vec<Message>,
+    /// Sent as the `X-Idempotency-Key` header so a retried request is not processed twice
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
+}
+
+impl MessagePayload {
+    /// Start building a user message for an agent
+    pub fn builder(agent_id: impl Into<String>, message: impl Into<String>) -> MessagePayloadBuilder {
+        MessagePayloadBuilder::new(agent_id, message)
+    }
+}
+
+/// Builder for `MessagePayload`
+#[derive(Clone, Debug)]
+pub struct MessagePayloadBuilder {
+    payload: MessagePayload,
+}
+
+impl MessagePayloadBuilder {
+    /// Create a builder for a user message to an agent
+    pub fn new(agent_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            payload: MessagePayload {
+                message: Message {
+                    role: "user".to_string(),
+                    content: message.into(),
+                },
+                additional_properties: HashMap::new(),
+                context: HashMap::new(),
+                agent_id: agent_id.into(),
+                thread_id: None,
+                attachments: Vec::new(),
+                previous_messages: Vec::new(),
+                idempotency_key: None,
+            },
+        }
+    }
+
+    /// Continue an existing thread
+    pub fn with_thread_id(mut self, thread_id: Option<String>) -> Self {
+        self.payload.thread_id = thread_id;
+        self
+    }
+
+    /// Add a context value
+    pub fn with_context(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.payload.context.insert(key.into(), value);
+        self
+    }
+
+    /// Attach a file to the message
+    pub fn with_attachment(mut self, attachment: MessageAttachment) -> Self {
+        self.payload.attachments.push(attachment);
+        self
+    }
+
+    /// Attach several files to the message
+    pub fn with_attachments(mut self, attachments: impl IntoIterator<Item = MessageAttachment>) -> Self {
+        self.payload.attachments.extend(attachments);
+        self
+    }
+
+    /// Include earlier conversation turns with the message
+    pub fn with_previous_messages(mut self, messages: Vec<Message>) -> Self {
+        self.payload.previous_messages = messages;
+        self
+    }
+
+    /// Set the idempotency key instead of letting the client generate one
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.payload.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Build the payload
+    pub fn build(self) -> MessagePayload {
+        self.payload
+    }
+}
+
+/// Type of an event emitted by the Orchestrate run stream
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrchestrateEventType {
+    /// A complete message was created (`message.created`)
+    MessageCreated,
+    /// Incremental message text (`message.delta`)
+    MessageDelta,
+    /// The agent requested a tool call
+    ToolCall,
+    /// The run was created (`run.created`)
+    RunCreated,
+    /// The run started (`run.started`)
+    RunStarted,
+    /// The run completed (`run.completed`)
+    RunCompleted,
+    /// The run failed (`run.failed`)
+    RunFailed,
+    /// Any other event, with its raw name
+    Other(String),
+}
+
+impl From<&str> for OrchestrateEventType {
+    fn from(event: &str) -> Self {
+        match event {
+            "message.created" => Self::MessageCreated,
+            "message.delta" => Self::MessageDelta,
+            "run.created" => Self::RunCreated,
+            "run.started" => Self::RunStarted,
+            "run.completed" => Self::RunCompleted,
+            "run.failed" => Self::RunFailed,
+            other if other.contains("tool_call") => Self::ToolCall,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+/// Parsed event from the Orchestrate run stream
+#[derive(Clone, Debug)]
+pub struct OrchestrateStreamEvent {
+    /// Text delta carried by the event (for `message.delta` events)
+    pub delta: Option<String>,
+    /// Thread ID reported by the event
+    pub thread_id: Option<String>,
+    /// Run ID reported by the event
+    pub run_id: Option<String>,
+    /// Event type
+    pub event_type: OrchestrateEventType,
+    /// Raw event JSON
+    pub raw: serde_json::Value,
+}
+
+impl OrchestrateStreamEvent {
+    /// Parse a single stream line into an event, ignoring blank or malformed lines
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let raw: serde_json::Value = serde_json::from_str(trimmed).ok()?;
+        let event_type = OrchestrateEventType::from(raw.get("event")?.as_str()?);
+        let data = raw.get("data").and_then(|d| d.as_object());
+
+        let first_text = |content: Option<&serde_json::Value>| {
+            content
+                .and_then(|c| c.as_array())
+                .and_then(|c| c.first())
+                .and_then(|c| c.get("text"))
+                .and_then(|t| t.as_str())
+                .map(str::to_string)
+        };
+
+        let delta = match (&event_type, data) {
+            (OrchestrateEventType::MessageDelta, Some(data)) => match data.get("delta") {
+                Some(delta) if delta.is_object() => first_text(delta.get("content")),
+                _ => first_text(data.get("content")),
+            },
+            _ => None,
+        };
+        let field = |name: &str| {
+            data.and_then(|d| d.get(name))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+
+        let run_id = match event_type {
+            OrchestrateEventType::RunCreated => field("run_id").or_else(|| field("id")),
+            _ => field("run_id"),
+        };
+
+        Some(Self {
+            delta,
+            thread_id: field("thread_id"),
+            run_id,
+            event_type,
+            raw,
+        })
+    }
+}
+
+/// Result of a non-streaming agent run
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SendMessageResult {
+    /// Final answer text from the agent
+    pub answer: String,
+    /// Thread ID for continuing the conversation
+    pub thread_id: Option<String>,
+    /// Run ID (from the `run.created` event)
+    pub run_id: Option<String>,
+    /// Tool calls made during the run
+    pub tool_calls: Vec<ToolCall>,
+    /// Run duration in milliseconds
+    pub duration_ms: Option<u64>,
+    /// Idempotency key the message was sent with, for resending it safely
+    pub idempotency_key: Option<String>,
+}
+
+impl SendMessageResult {
+    /// Build a result from the newline-delimited events of a run stream
+    pub(crate) fn from_stream_text(text: &str, thread_id: Option<String>) -> Self {
+        let mut result = Self {
+            thread_id,
+            ..Self::default()
+        };
+
+        for event in text.lines().filter_map(OrchestrateStreamEvent::parse_line) {
+            if let Some(tid) = &event.thread_id {
+                result.thread_id = Some(tid.clone());
+            }
+            if result.run_id.is_none() {
+                result.run_id = event.run_id.clone();
+            }
+
+            let data = event.raw.get("data");
+            match event.event_type {
+                OrchestrateEventType::MessageCreated => {
+                    if let Some(text) = data
+                        .and_then(|d| d.get("message"))
+                        .and_then(|m| m.get("content"))
+                        .and_then(|c| c.as_array())
+                        .and_then(|c| c.first())
+                        .and_then(|c| c.get("text"))
+                        .and_then(|t| t.as_str())
+                    {
+                        result.answer = text.to_string();
+                    }
+                }
+                OrchestrateEventType::ToolCall => {
+                    if let Some(call) = data.and_then(ToolCall::from_event_data) {
+                        result.merge_tool_call(call);
+                    }
+                }
+                OrchestrateEventType::RunCompleted => {
+                    result.duration_ms = data
+                        .and_then(|d| d.get("duration_ms"))
+                        .and_then(|v| v.as_u64())
+                        .or(result.duration_ms);
+                }
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    /// Add a tool call, merging it with an earlier event for the same call ID
+    fn merge_tool_call(&mut self, call: ToolCall) {
+        match self.tool_calls.iter_mut().find(|existing| existing.id == call.id) {
+            Some(existing) => {
+                if existing.tool_name.is_empty() {
+                    existing.tool_name = call.tool_name;
+                }
+                existing.parameters.extend(call.parameters);
+                if call.result.is_some() {
+                    existing.result = call.result;
+                }
+            }
+            None => self.tool_calls.push(call),
+        }
+    }
 }
 
 /// Thread information for conversation management
@@ -416,6 +1186,48 @@ pub struct ThreadInfo {
     pub updated_at: Option<String>,
     /// Message count
     pub message_count: Option<u32>,
+    /// Thread-level metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Request to create a conversation thread
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CreateThreadRequest {
+    /// Agent the thread belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    /// Thread-level metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// Context made available to the agent for the whole thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_context: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl CreateThreadRequest {
+    /// Create an empty request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the agent the thread belongs to
+    pub fn with_agent_id(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = Some(agent_id.into());
+        self
+    }
+
+    /// Add a metadata entry
+    pub fn with_metadata(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.metadata.get_or_insert_with(HashMap::new).insert(key.into(), value);
+        self
+    }
+
+    /// Add an initial context entry
+    pub fn with_initial_context(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.initial_context.get_or_insert_with(HashMap::new).insert(key.into(), value);
+        self
+    }
 }
 
 /// Chat message for assistant conversations
@@ -538,6 +1350,51 @@ pub struct ToolCall {
     pub result: Option<serde_json::Value>,
 }
 
+impl ToolCall {
+    /// Build a tool call from the data of a `tool_call.*` stream event
+    pub(crate) fn from_event_data(data: &serde_json::Value) -> Option<Self> {
+        let call = data.get("tool_call").unwrap_or(data);
+        let text = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| call.get(*name).and_then(|v| v.as_str()))
+                .map(str::to_string)
+        };
+
+        let id = text(&["id", "tool_call_id"])?;
+        let parameters = ["parameters", "arguments", "input"]
+            .iter()
+            .find_map(|name| match call.get(*name) {
+                Some(serde_json::Value::Object(map)) => Some(map.clone()),
+                Some(serde_json::Value::String(raw)) => serde_json::from_str(raw).ok(),
+                _ => None,
+            })
+            .map(|map| map.into_iter().collect())
+            .unwrap_or_default();
+
+        Some(Self {
+            id,
+            tool_name: text(&["tool_name", "name"]).unwrap_or_default(),
+            parameters,
+            result: call.get("result").or_else(|| call.get("output")).cloned(),
+        })
+    }
+
+    /// Parse the tool calls from a `/runs/{id}/tool_calls` response body
+    ///
+    /// Accepts a bare array or an object wrapping it in `tool_calls`/`data`.
+    pub(crate) fn list_from_response(body: &serde_json::Value) -> Vec<Self> {
+        body.as_array()
+            .or_else(|| {
+                ["tool_calls", "data"]
+                    .iter()
+                    .find_map(|key| body.get(*key).and_then(|v| v.as_array()))
+            })
+            .map(|calls| calls.iter().filter_map(Self::from_event_data).collect())
+            .unwrap_or_default()
+    }
+}
+
 /// Request to create a document collection
 #[derive(Clone, Debug, Serialize)]
 pub struct CreateCollectionRequest {
@@ -549,6 +1406,138 @@ pub struct CreateCollectionRequest {
     pub vector_index: Option<VectorIndexConfig>,
 }
 
+/// Portable snapshot of a document collection and all of its documents
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CollectionExport {
+    /// Exported collection
+    pub collection: DocumentCollection,
+    /// All documents in the collection
+    pub documents: Vec<Document>,
+}
+
+impl CollectionExport {
+    /// Write the export to a JSON file
+    pub fn to_json_file(&self, path: impl AsRef<std::path::Path>) -> crate::error::Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::error::Error::Serialization(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| {
+            crate::error::Error::Io(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+
+    /// Read an export from a JSON file
+    pub fn from_json_file(path: impl AsRef<std::path::Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            crate::error::Error::Io(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&json).map_err(|e| crate::error::Error::Serialization(e.to_string()))
+    }
+}
+
+/// Pagination options for list operations
+#[derive(Clone, Debug, Default)]
+pub struct ListOptions {
+    /// Maximum number of items to return
+    pub limit: Option<u32>,
+    /// Number of items to skip
+    pub offset: Option<u32>,
+}
+
+impl ListOptions {
+    /// Create empty list options (server defaults)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the page size
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the offset
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Render as a URL query string (including the leading `?`), or empty if unset
+    pub fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(limit) = self.limit {
+            params.push(format!("limit={}", limit));
+        }
+        if let Some(offset) = self.offset {
+            params.push(format!("offset={}", offset));
+        }
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+}
+
+/// A single page of results from a list operation
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items in this page
+    pub items: Vec<T>,
+    /// Total number of items (if reported by the API)
+    pub total: Option<u32>,
+    /// Page size that was requested
+    pub limit: Option<u32>,
+    /// Offset of the first item in this page
+    pub offset: u32,
+}
+
+impl<T> Page<T> {
+    /// Check whether more items are available after this page
+    ///
+    /// An empty page never has more, even if a stale `total` says otherwise, so paging
+    /// always ends.
+    pub fn has_more(&self) -> bool {
+        if self.items.is_empty() {
+            return false;
+        }
+        match (self.total, self.limit) {
+            (Some(total), _) => self.offset + (self.items.len() as u32) < total,
+            (None, Some(limit)) => self.items.len() as u32 >= limit,
+            (None, None) => false,
+        }
+    }
+
+    /// Build a page by slicing a complete, client-side list of items
+    pub fn from_items(items: Vec<T>, options: &ListOptions) -> Self {
+        let total = items.len() as u32;
+        let offset = options.offset.unwrap_or(0);
+        let items = items
+            .into_iter()
+            .skip(offset as usize)
+            .take(options.limit.map_or(usize::MAX, |limit| limit as usize))
+            .collect();
+
+        Self {
+            items,
+            total: Some(total),
+            limit: options.limit,
+            offset,
+        }
+    }
+
+    /// List options for fetching the next page, if there is one
+    pub fn next_options(&self) -> Option<ListOptions> {
+        if !self.has_more() {
+            return None;
+        }
+        let mut options = ListOptions::new().with_offset(self.offset + self.items.len() as u32);
+        options.limit = self.limit;
+        Some(options)
+    }
+}
+
 /// Request to add documents to a collection
 #[derive(Clone, Debug, Serialize)]
 pub struct AddDocumentsRequest {
@@ -597,6 +1586,24 @@ pub struct SearchResponse {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// Result of a single query within a batch search
+#[derive(Clone, Debug)]
+pub struct BatchSearchResult {
+    /// The query that was searched
+    pub query: String,
+    /// Search results (empty if the query failed)
+    pub results: Vec<SearchResult>,
+    /// Error for this query, if it failed
+    pub error: Option<crate::error::Error>,
+}
+
+impl BatchSearchResult {
+    /// Check if this query succeeded
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 /// Retry configuration for Orchestrate operations
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrchestrateRetryConfig {
@@ -663,6 +1670,140 @@ pub enum RunStatus {
     Cancelled,
 }
 
+/// Orchestrate event types a webhook can subscribe to
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum WebhookEventType {
+    /// A run finished successfully
+    #[serde(rename = "run_completed")]
+    RunCompleted,
+    /// A run failed
+    #[serde(rename = "run_failed")]
+    RunFailed,
+    /// A message was added to a thread
+    #[serde(rename = "message_created")]
+    MessageCreated,
+    /// An event type this version of the client does not recognize
+    #[serde(other)]
+    Unknown,
+}
+
+/// Request to register a webhook
+#[derive(Clone, Debug, Serialize)]
+pub struct WebhookRequest {
+    /// URL that receives event payloads
+    pub url: String,
+    /// Events to deliver
+    pub events: Vec<WebhookEventType>,
+    /// Shared secret used to sign payloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl WebhookRequest {
+    /// Create a request for the given URL and events
+    pub fn new(url: impl Into<String>, events: Vec<WebhookEventType>) -> Self {
+        Self {
+            url: url.into(),
+            events,
+            secret: None,
+        }
+    }
+
+    /// Set the secret used to sign payloads
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+}
+
+/// A registered webhook
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Webhook {
+    /// Webhook ID
+    #[serde(alias = "webhook_id")]
+    pub id: String,
+    /// URL that receives event payloads
+    pub url: String,
+    /// Subscribed events
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+    /// Creation time
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+impl Webhook {
+    /// Verify the HMAC-SHA256 signature of a webhook payload
+    ///
+    /// `signature` is the hex digest sent with the delivery, optionally prefixed with `sha256=`.
+    /// The comparison is constant-time.
+    pub fn verify_signature(payload: &[u8], signature: &str, secret: &str) -> bool {
+        use hmac::{Hmac, Mac};
+
+        let signature = signature.trim();
+        let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+        let Some(expected) = decode_hex(signature) else {
+            return false;
+        };
+
+        let Ok(mut mac) = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(payload);
+        mac.verify_slice(&expected).is_ok()
+    }
+}
+
+/// Decode a hex string, returning `None` if it is malformed
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Event payload delivered to a webhook URL
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Event type
+    #[serde(alias = "event_type")]
+    pub event: WebhookEventType,
+    /// ID of the webhook that delivered the event
+    #[serde(default)]
+    pub webhook_id: Option<String>,
+    /// Associated agent ID
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// Associated thread ID
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Associated run ID
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Event time
+    #[serde(default, alias = "timestamp")]
+    pub created_at: Option<String>,
+    /// Event-specific data
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+/// Statistics for a per-tool rate limiter
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimiterStats {
+    /// Configured maximum requests per second
+    pub max_rps: f64,
+    /// Tokens currently available for immediate execution
+    pub available_tokens: f64,
+    /// Total number of executions admitted
+    pub total_acquired: u64,
+    /// Total time callers have been delayed
+    pub total_wait: Duration,
+}
+
 /// Tool execution request
 #[derive(Clone, Debug, Serialize)]
 pub struct ToolExecutionRequest {
@@ -674,14 +1815,43 @@ pub struct ToolExecutionRequest {
     pub context: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// Tool execution status
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolExecutionStatus {
+    /// Tool completed successfully
+    #[serde(alias = "succeeded", alias = "completed", alias = "SUCCESS")]
+    Success,
+    /// Tool completed but only part of the work succeeded
+    #[serde(alias = "partial", alias = "PARTIAL_SUCCESS")]
+    PartialSuccess,
+    /// Tool failed
+    #[serde(alias = "failed", alias = "error", alias = "FAILURE")]
+    Failure,
+    /// Tool did not finish in time
+    #[serde(alias = "timed_out", alias = "TIMEOUT")]
+    Timeout,
+    /// A status this version of the client does not recognize
+    #[serde(other)]
+    Unknown,
+}
+
+impl ToolExecutionStatus {
+    /// Whether the tool produced a (possibly partial) result
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success | Self::PartialSuccess)
+    }
+}
+
 /// Tool execution result
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolExecutionResult {
     /// Tool ID
     pub tool_id: String,
     /// Execution status
-    pub status: String,
+    pub status: ToolExecutionStatus,
     /// Result data
+    #[serde(alias = "output")]
     pub result: serde_json::Value,
     /// Execution time in milliseconds
     pub execution_time_ms: Option<u64>,
@@ -689,6 +1859,130 @@ pub struct ToolExecutionResult {
     pub error: Option<String>,
 }
 
+impl ToolExecutionResult {
+    /// Deserialize the tool output into a typed value
+    pub fn parse_output<T: serde::de::DeserializeOwned>(&self) -> crate::error::Result<T> {
+        serde_json::from_value(self.result.clone()).map_err(|e| {
+            crate::error::Error::Serialization(format!(
+                "Failed to parse output of tool {}: {}",
+                self.tool_id, e
+            ))
+        })
+    }
+
+    /// Output as a string slice, if the tool returned a JSON string
+    pub fn output_as_str(&self) -> Option<&str> {
+        self.result.as_str()
+    }
+
+    /// Output as a number, if the tool returned a JSON number
+    pub fn output_as_number(&self) -> Option<f64> {
+        self.result.as_f64()
+    }
+
+    /// Execution time reported by the service
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.execution_time_ms.map(Duration::from_millis)
+    }
+}
+
+/// Request to create a tool
+///
+/// A tool is described either by an OpenAPI specification or by the endpoint it calls;
+/// `OrchestrateClient::create_tool` rejects requests that have neither.
+#[derive(Clone, Debug, Serialize)]
+pub struct CreateToolRequest {
+    /// Tool name
+    pub name: String,
+    /// Tool description
+    pub description: Option<String>,
+    /// Tool type
+    pub tool_type: Option<ToolType>,
+    /// OpenAPI specification describing the tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub openapi_spec: Option<serde_json::Value>,
+    /// Endpoint URL the tool calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Authentication used when calling the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthConfig>,
+}
+
+impl CreateToolRequest {
+    /// Create a request for a tool that calls `endpoint`
+    pub fn new(name: impl Into<String>, tool_type: ToolType, endpoint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            tool_type: Some(tool_type),
+            openapi_spec: None,
+            endpoint: Some(endpoint.into()),
+            auth: None,
+        }
+    }
+
+    /// Build a request from an OpenAPI document, taking the name and description from `info`
+    pub fn from_openapi(spec: serde_json::Value) -> crate::error::Result<Self> {
+        let info = spec.get("info");
+        let name = info
+            .and_then(|i| i.get("title"))
+            .and_then(|t| t.as_str())
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| {
+                crate::error::Error::InvalidInput(
+                    "OpenAPI specification is missing info.title".to_string(),
+                )
+            })?
+            .to_string();
+        let description = info
+            .and_then(|i| i.get("description"))
+            .and_then(|d| d.as_str())
+            .map(str::to_string);
+
+        Ok(Self {
+            name,
+            description,
+            tool_type: Some(ToolType::Api),
+            openapi_spec: Some(spec),
+            endpoint: None,
+            auth: None,
+        })
+    }
+
+    /// Build a request from an OpenAPI document in YAML
+    ///
+    /// Requires the `yaml` feature; without it `Error::Configuration` is returned.
+    pub fn from_spec_yaml(spec_yaml: &str) -> crate::error::Result<Self> {
+        let spec = super::tool::parse_openapi_spec(spec_yaml, super::tool::OpenApiFormat::Yaml)?;
+        Self::from_openapi(spec)
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the authentication used when calling the endpoint
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Check that the tool is described by an OpenAPI specification or an endpoint
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let has_endpoint = self.endpoint.as_deref().is_some_and(|e| !e.trim().is_empty());
+        if self.openapi_spec.is_none() && !has_endpoint {
+            return Err(crate::error::Error::InvalidInput(format!(
+                "Tool {} needs an OpenAPI specification or an endpoint",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Tool update request
 #[derive(Clone, Debug, Serialize)]
 pub struct ToolUpdateRequest {
@@ -801,6 +2095,91 @@ pub struct BatchMessageResult {
     pub error: Option<String>,
 }
 
+/// Skill execution request
+#[derive(Clone, Debug, Serialize)]
+pub struct SkillExecutionRequest {
+    /// Skill ID
+    pub skill_id: String,
+    /// Skill input parameters
+    pub parameters: HashMap<String, serde_json::Value>,
+    /// Execution context
+    pub context: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl SkillExecutionRequest {
+    /// Create a request for the given skill with no parameters
+    pub fn new(skill_id: impl Into<String>) -> Self {
+        Self {
+            skill_id: skill_id.into(),
+            parameters: HashMap::new(),
+            context: None,
+        }
+    }
+
+    /// Add an input parameter
+    pub fn with_parameter(mut self, name: impl Into<String>, value: serde_json::Value) -> Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
+}
+
+/// Skill execution result
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkillExecutionResult {
+    /// Skill ID
+    pub skill_id: String,
+    /// Execution status
+    pub status: ToolExecutionStatus,
+    /// Result data
+    #[serde(default, alias = "output")]
+    pub result: serde_json::Value,
+    /// Execution time in milliseconds
+    pub execution_time_ms: Option<u64>,
+    /// Error message (if failed)
+    pub error: Option<String>,
+}
+
+/// Outcome of one skill execution in a batch
+#[derive(Debug)]
+pub struct BatchSkillResult {
+    /// Skill ID from the request
+    pub skill_id: String,
+    /// Execution result or the error that prevented it
+    pub result: crate::error::Result<SkillExecutionResult>,
+}
+
+/// Options for `OrchestrateClient::run_skill_batch_with_config`
+#[derive(Clone, Debug)]
+pub struct BatchSkillConfig {
+    /// Keep running remaining items after one fails
+    pub continue_on_error: bool,
+    /// Time limit for each execution, not counting time spent waiting for a slot
+    pub timeout_per_item: Option<Duration>,
+}
+
+impl Default for BatchSkillConfig {
+    fn default() -> Self {
+        Self {
+            continue_on_error: true,
+            timeout_per_item: None,
+        }
+    }
+}
+
+impl BatchSkillConfig {
+    /// Set whether remaining items run after one fails
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// Set the time limit for each execution
+    pub fn with_timeout_per_item(mut self, timeout: Duration) -> Self {
+        self.timeout_per_item = Some(timeout);
+        self
+    }
+}
+
 /// Agent configuration for execution
 #[derive(Clone, Debug, Serialize)]
 pub struct AgentExecutionConfig {
@@ -838,19 +2217,93 @@ pub struct ChatWithDocsRequest {
     pub context: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// Source document cited in a chat with documents answer
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "UsedDocumentRepr")]
+pub struct UsedDocument {
+    /// Document ID
+    pub document_id: String,
+    /// Document title
+    pub title: String,
+    /// Relevance of the document to the answer
+    pub relevance_score: f32,
+    /// Excerpt of the document that supports the answer
+    pub excerpt: Option<String>,
+    /// Page number of the excerpt
+    pub page_number: Option<u32>,
+}
+
+/// Wire formats for `UsedDocument`: a bare document ID or a full citation object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UsedDocumentRepr {
+    Id(String),
+    Full {
+        #[serde(alias = "id")]
+        document_id: String,
+        #[serde(default)]
+        title: String,
+        #[serde(default, alias = "score")]
+        relevance_score: f32,
+        #[serde(default, alias = "text")]
+        excerpt: Option<String>,
+        #[serde(default, alias = "page")]
+        page_number: Option<u32>,
+    },
+}
+
+impl From<UsedDocumentRepr> for UsedDocument {
+    fn from(repr: UsedDocumentRepr) -> Self {
+        match repr {
+            UsedDocumentRepr::Id(document_id) => Self {
+                document_id,
+                title: String::new(),
+                relevance_score: 0.0,
+                excerpt: None,
+                page_number: None,
+            },
+            UsedDocumentRepr::Full {
+                document_id,
+                title,
+                relevance_score,
+                excerpt,
+                page_number,
+            } => Self {
+                document_id,
+                title,
+                relevance_score,
+                excerpt,
+                page_number,
+            },
+        }
+    }
+}
+
 /// Response from chat with documents
 #[derive(Clone, Debug, Deserialize)]
 pub struct ChatWithDocsResponse {
     /// The response message
     pub message: String,
-    /// Document references used
-    pub documents_used: Option<Vec<String>>,
+    /// Documents cited in the answer
+    pub documents_used: Option<Vec<UsedDocument>>,
     /// Confidence score
     pub confidence: Option<f64>,
     /// Additional metadata
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
+impl ChatWithDocsResponse {
+    /// Check whether the answer cites any source documents
+    pub fn has_citations(&self) -> bool {
+        !self.citations().is_empty()
+    }
+
+    /// Source documents cited in the answer
+    pub fn citations(&self) -> &[UsedDocument] {
+        self.documents_used.as_deref().unwrap_or(&[])
+    }
+}
+
 /// Status of chat with documents knowledge base
 #[derive(Clone, Debug, Deserialize)]
 pub struct ChatWithDocsStatus {
@@ -0,0 +1,118 @@
+//! Webhook registration operations
+
+use crate::error::{Error, Result};
+use super::types::{Webhook, WebhookRequest};
+use super::OrchestrateClient;
+use super::client::{parse_list_response, retry_request, send_error};
+use super::http_log::SendLogged;
+use futures::TryFutureExt;
+
+impl OrchestrateClient {
+    /// Register a webhook to receive Orchestrate events
+    pub async fn register_webhook(&self, request: WebhookRequest) -> Result<Webhook> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/webhooks", base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to register webhook: {} - {}",
+                status, error_text
+            )));
+        }
+
+        let webhook: Webhook = response
+            .json()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        Ok(webhook)
+    }
+
+    /// List registered webhooks
+    pub async fn list_webhooks(&self) -> Result<Vec<Webhook>> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/webhooks", base_url);
+
+        let response = retry_request(
+            || {
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", api_key))
+                    .header("Content-Type", "application/json")
+                    .send_logged()
+                    .map_err(send_error)
+            },
+            &self.retry_config,
+        )
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to list webhooks: {} - {}",
+                status, error_text
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        parse_list_response::<Webhook>(&text, "webhooks")
+    }
+
+    /// Delete a webhook
+    pub async fn delete_webhook(&self, webhook_id: &str) -> Result<()> {
+        let api_key = self.ensure_token_valid().await?;
+
+        let base_url = self.config.get_base_url();
+        let url = format!("{}/webhooks/{}", base_url, webhook_id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Content-Type", "application/json")
+            .send_logged()
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(Error::Api(format!(
+                "Failed to delete webhook {}: {} - {}",
+                webhook_id, status, error_text
+            )));
+        }
+
+        Ok(())
+    }
+}
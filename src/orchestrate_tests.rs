@@ -1,9 +1,9 @@
 //! Tests for WatsonX Orchestrate functionality
 
 use crate::{
-    OrchestrateClient, OrchestrateConfig, AssistantConfig,
+    OrchestrateClient, OrchestrateConfig, Region, AssistantConfig,
     VectorIndexConfig, IndexType, SimilarityMetric,
-    ChatRequest, Document, DocumentType, SearchRequest,
+    Agent, ChatRequest, Document, DocumentType, SearchRequest,
 };
 use std::collections::HashMap;
 
@@ -13,7 +13,7 @@ async fn test_orchestrate_client_creation() {
     let client = OrchestrateClient::new(config);
     
     assert_eq!(client.config().instance_id, "test-instance-id");
-    assert_eq!(client.config().region, "us-south");
+    assert_eq!(client.config().region, Region::UsSouth);
     assert!(!client.is_authenticated());
 }
 
@@ -117,7 +117,39 @@ async fn test_orchestrate_config_region_default() {
     let base_url = config.get_base_url();
     
     assert!(base_url.contains("us-south")); // default region
-    assert_eq!(config.region, "us-south");
+    assert_eq!(config.region, Region::UsSouth);
+}
+
+#[test]
+fn test_orchestrate_config_timeouts() {
+    let config = OrchestrateConfig::new("test-instance-123".to_string());
+    assert_eq!(config.connect_timeout, Some(std::time::Duration::from_secs(10)));
+    assert_eq!(config.request_timeout, Some(std::time::Duration::from_secs(300)));
+
+    let config = config
+        .with_connect_timeout(Some(std::time::Duration::from_secs(2)))
+        .with_request_timeout(None);
+    assert_eq!(config.connect_timeout, Some(std::time::Duration::from_secs(2)));
+    assert!(config.request_timeout.is_none());
+    let _client = OrchestrateClient::new(config);
+}
+
+#[tokio::test]
+async fn test_orchestrate_config_regions() {
+    let config = OrchestrateConfig::for_eu_de("test-instance-123".to_string());
+    assert_eq!(config.region, Region::EuDe);
+    assert_eq!(
+        config.get_base_url(),
+        "https://eu-de.watson-orchestrate.cloud.ibm.com/api/v1/"
+    );
+
+    let config = OrchestrateConfig::for_ap_north("test-instance-123".to_string());
+    assert!(config.get_base_url().contains(config.region.api_host()));
+
+    assert_eq!("eu-gb".parse::<Region>(), Ok(Region::UkSouth));
+    let err = "mars-north".parse::<Region>().unwrap_err();
+    assert!(err.contains("mars-north"));
+    assert!(err.contains("us-south"));
 }
 
 #[tokio::test]
@@ -130,3 +162,1278 @@ async fn test_orchestrate_retry_config_default() {
     assert_eq!(config.backoff_multiplier, 2.0);
     assert_eq!(config.retry_on_errors.len(), 2);
 }
+
+#[tokio::test]
+async fn test_orchestrate_config_with_api_key() {
+    let config = OrchestrateConfig::new("test-instance-123".to_string()).with_api_key("key-123");
+
+    assert_eq!(config.ibm_api_key, Some("key-123".to_string()));
+}
+
+#[tokio::test]
+async fn test_orchestrate_client_with_api_key() {
+    use crate::orchestrate::AuthMode;
+
+    let config = OrchestrateConfig::new("test-instance-123".to_string()).with_auth_mode(AuthMode::StaticToken);
+    let client = OrchestrateClient::new(config).with_api_key("key-123".to_string());
+
+    assert_eq!(client.config().ibm_api_key.as_deref(), Some("key-123"));
+    assert_eq!(client.config().auth_mode, AuthMode::Iam);
+    // No token is fetched until the first request
+    assert!(!client.is_authenticated());
+    assert!(client.token_expiry().is_none());
+
+    client.set_token("test-token".to_string());
+    assert!(client.token_expiry().is_some());
+}
+
+#[tokio::test]
+async fn test_jwt_token_holder_reads_exp_claim() {
+    use crate::orchestrate::client::JwtTokenHolder;
+    use base64::Engine;
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let make_token = |exp: u64| {
+        let claims = URL_SAFE_NO_PAD.encode(format!(r#"{{"exp":{}}}"#, exp));
+        format!("eyJhbGciOiJSUzI1NiJ9.{}.signature", claims)
+    };
+
+    let expiring = JwtTokenHolder::new(make_token(now + 60));
+    assert!(expiring.expires_within(Duration::from_secs(5 * 60)));
+
+    let fresh = JwtTokenHolder::new(make_token(now + 3600));
+    assert!(!fresh.expires_within(Duration::from_secs(5 * 60)));
+
+    // Opaque tokens fall back to the default IAM lifetime
+    let opaque = JwtTokenHolder::new("test-token".to_string());
+    assert!(!opaque.expires_within(Duration::from_secs(5 * 60)));
+}
+
+#[cfg(feature = "yaml")]
+#[tokio::test]
+async fn test_validate_skill_yaml() {
+    let valid = r#"
+name: summarize
+description: Summarize text
+skill_type: TextProcessing
+input_params: {}
+"#;
+    assert!(OrchestrateClient::validate_skill_yaml(valid).unwrap().is_empty());
+
+    let missing = "description: no name here\n";
+    let errors = OrchestrateClient::validate_skill_yaml(missing).unwrap();
+    assert!(errors.contains(&"name is required".to_string()));
+    assert!(errors.contains(&"skill_type is required".to_string()));
+
+    assert!(OrchestrateClient::validate_skill_yaml("name: [unclosed").is_err());
+}
+
+#[tokio::test]
+async fn test_list_options_and_page() {
+    use crate::{ListOptions, Page};
+
+    assert_eq!(ListOptions::new().to_query_string(), "");
+    assert_eq!(
+        ListOptions::new().with_limit(10).with_offset(20).to_query_string(),
+        "?limit=10&offset=20"
+    );
+
+    let page = Page { items: vec![1, 2], total: Some(5), limit: Some(2), offset: 0 };
+    assert!(page.has_more());
+    assert_eq!(page.next_options().unwrap().offset, Some(2));
+
+    let last = Page { items: vec![5], total: Some(5), limit: Some(2), offset: 4 };
+    assert!(!last.has_more());
+    assert!(last.next_options().is_none());
+
+    // Items deleted since `total` was counted
+    let stale = Page::<u32> { items: vec![], total: Some(5), limit: Some(2), offset: 2 };
+    assert!(!stale.has_more());
+    assert!(stale.next_options().is_none());
+}
+
+#[tokio::test]
+async fn test_collection_export_round_trip() {
+    use crate::{CollectionExport, CollectionStatus, DocumentCollection};
+
+    let export = CollectionExport {
+        collection: DocumentCollection {
+            id: "coll-1".to_string(),
+            name: "Docs".to_string(),
+            description: None,
+            status: CollectionStatus::Active,
+            created_at: None,
+            updated_at: None,
+            document_count: 0,
+            vector_index: None,
+            tags: None,
+            metadata: None,
+        },
+        documents: vec![],
+    };
+
+    let path = std::env::temp_dir().join(format!("watsonx-export-{}.json", uuid::Uuid::new_v4()));
+    export.to_json_file(&path).unwrap();
+    let loaded = CollectionExport::from_json_file(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded.collection.id, "coll-1");
+    assert!(loaded.documents.is_empty());
+}
+
+#[tokio::test]
+async fn test_retry_request_respects_retry_on_errors() {
+    use crate::orchestrate::client::retry_request;
+    use crate::{Error, OrchestrateRetryConfig};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    let config = OrchestrateRetryConfig {
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+        ..OrchestrateRetryConfig::default()
+    };
+
+    let attempts = AtomicU32::new(0);
+    let result: crate::Result<reqwest::Response> = retry_request(
+        || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(Error::Network("connection reset".to_string()))
+        },
+        &config,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Network(_))));
+    assert_eq!(attempts.load(Ordering::SeqCst), config.max_attempts);
+
+    let attempts = AtomicU32::new(0);
+    let result: crate::Result<reqwest::Response> = retry_request(
+        || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(Error::Authentication("invalid token".to_string()))
+        },
+        &config,
+    )
+    .await;
+    assert!(matches!(result, Err(Error::Authentication(_))));
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
+
+/// Client for `server` with a fixed token and retries that only wait a millisecond
+fn mock_orchestrate_client(server: &crate::mock_server::MockServer, retry_on_errors: &[&str]) -> OrchestrateClient {
+    use crate::OrchestrateRetryConfig;
+    use std::time::Duration;
+
+    let mut config = OrchestrateConfig::new("test-instance".to_string());
+    config.base_url = server.url().to_string();
+    OrchestrateClient::new(config)
+        .with_token("test-token".to_string())
+        .with_retry_config(OrchestrateRetryConfig {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            retry_on_errors: retry_on_errors.iter().map(|kind| kind.to_string()).collect(),
+            ..OrchestrateRetryConfig::default()
+        })
+}
+
+#[tokio::test]
+async fn test_retry_request_retries_rate_limited_response() {
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    let server = MockServer::start(vec![
+        MockResponse::json(429, json!({"error": "slow down"})),
+        MockResponse::json(200, json!({"tags": ["prod"]})),
+    ]);
+    let client = mock_orchestrate_client(&server, &["rate_limit"]);
+
+    assert_eq!(client.list_agent_tags("agent-1").await.unwrap(), vec!["prod"]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|r| r.method == "GET" && r.path == "/agents/agent-1/tags"));
+}
+
+#[tokio::test]
+async fn test_retry_request_reports_persistent_status_errors() {
+    use crate::Error;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    let server = MockServer::with_handler(|_| MockResponse::json(429, json!({"error": "slow down"})));
+    let client = mock_orchestrate_client(&server, &["rate_limit"]);
+    assert!(matches!(client.list_agent_tags("agent-1").await, Err(Error::RateLimit(_))));
+    assert_eq!(server.requests().len(), 3);
+
+    // 5xx is an api_error, which this policy does not retry
+    let server = MockServer::with_handler(|_| MockResponse::json(503, json!({"error": "down"})));
+    let client = mock_orchestrate_client(&server, &["rate_limit"]);
+    assert!(matches!(client.list_agent_tags("agent-1").await, Err(Error::HttpStatus { status: 503, .. })));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_send_message_reports_quota_exceeded() {
+    use crate::Error;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    let quota = json!({"error": {"code": "quota_exceeded", "message": "Monthly message quota reached"}});
+    let server = MockServer::with_handler(move |_| MockResponse::json(429, quota.clone()));
+
+    // Without an idempotency key the message is sent once
+    let client = mock_orchestrate_client(&server, &["rate_limit"]);
+    let error = client.send_message("agent-1", "hi", None, None).await.unwrap_err();
+    assert!(matches!(&error, Error::OrchestrateApi(api_error) if api_error.is_quota_exceeded()));
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(server.requests()[0].path, "/runs/stream");
+
+    // The structured error survives exhausted retries
+    let client = mock_orchestrate_client(&server, &["rate_limit"]).with_idempotency_keys(true);
+    let error = client.send_message("agent-1", "hi", None, None).await.unwrap_err();
+    assert!(matches!(&error, Error::OrchestrateApi(api_error) if api_error.is_quota_exceeded()));
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_agent_tag_requests_encode_tag() {
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    let server = MockServer::with_handler(|_| MockResponse::json(200, json!({})));
+    let client = mock_orchestrate_client(&server, &[]);
+    client.add_agent_tag("agent-1", "team/a b").await.unwrap();
+    client.remove_agent_tag("agent-1", "team/a b").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!((requests[0].method.as_str(), requests[1].method.as_str()), ("POST", "DELETE"));
+    for request in &requests {
+        assert_eq!(request.path, "/agents/agent-1/tags/team%2Fa%20b");
+        assert_eq!(request.header("X-Instance-ID"), Some("test-instance"));
+    }
+}
+
+#[tokio::test]
+async fn test_agent_collection_link_requests() {
+    use crate::Error;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    let server = MockServer::start(vec![
+        MockResponse::json(200, json!({})),
+        MockResponse::json(204, json!({})),
+        MockResponse::json(404, json!({"error": "not found"})),
+    ]);
+    let client = mock_orchestrate_client(&server, &[]);
+    client.link_collection_to_agent("agent-1", "kb-1").await.unwrap();
+    client.unlink_collection_from_agent("agent-1", "kb-1").await.unwrap();
+    assert!(matches!(
+        client.unlink_collection_from_agent("agent-1", "kb-2").await,
+        Err(Error::Api(message)) if message.contains("kb-2")
+    ));
+
+    let requests = server.requests();
+    let calls: Vec<(&str, &str)> = requests.iter().map(|r| (r.method.as_str(), r.path.as_str())).collect();
+    assert_eq!(
+        calls,
+        vec![
+            ("POST", "/agents/agent-1/knowledge_bases/kb-1"),
+            ("DELETE", "/agents/agent-1/knowledge_bases/kb-1"),
+            ("DELETE", "/agents/agent-1/knowledge_bases/kb-2"),
+        ]
+    );
+    assert!(requests.iter().all(|r| r.header("Authorization") == Some("Bearer test-token")));
+}
+
+#[tokio::test]
+async fn test_request_timeout_applies_to_streaming_requests() {
+    use crate::Error;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+    use std::time::Duration;
+
+    let server = MockServer::with_handler(|request| {
+        std::thread::sleep(Duration::from_millis(300));
+        if request.path.ends_with("/tags") {
+            MockResponse::json(200, json!({"tags": ["prod"]}))
+        } else {
+            MockResponse::text(200, "text/event-stream", "")
+        }
+    });
+    let mut config = OrchestrateConfig::new("test-instance".to_string())
+        .with_request_timeout(Some(Duration::from_millis(100)));
+    config.base_url = server.url().to_string();
+    let client = OrchestrateClient::new(config).with_token("test-token".to_string());
+
+    // Management calls are not bound by the streaming timeout
+    assert_eq!(client.list_agent_tags("agent-1").await.unwrap(), vec!["prod"]);
+    assert!(matches!(
+        client.stream_message_typed("agent-1", "hi", None).await,
+        Err(Error::Timeout(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_send_message_retries_only_with_idempotency_key() {
+    use crate::Error;
+    use crate::mock_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    let server = MockServer::start(vec![
+        MockResponse::json(503, json!({"error": "down"})),
+        MockResponse::text(200, "text/event-stream", ""),
+    ]);
+    let client = mock_orchestrate_client(&server, &["api_error"]);
+    assert!(matches!(
+        client.send_message("agent-1", "hi", None, None).await,
+        Err(Error::HttpStatus { status: 503, .. })
+    ));
+    assert_eq!(server.requests().len(), 1);
+
+    let server = MockServer::start(vec![
+        MockResponse::json(503, json!({"error": "down"})),
+        MockResponse::text(200, "text/event-stream", ""),
+    ]);
+    let client = mock_orchestrate_client(&server, &["api_error"]).with_idempotency_keys(true);
+    let result = client.send_message("agent-1", "hi", None, None).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/runs/stream");
+    assert_eq!(requests[0].json()["agent_id"], "agent-1");
+    let key = result.idempotency_key.unwrap();
+    assert!(requests.iter().all(|r| r.header("X-Idempotency-Key") == Some(key.as_str())));
+
+    // Streamed messages get a generated key too, and are retried with it
+    let server = MockServer::start(vec![
+        MockResponse::json(503, json!({"error": "down"})),
+        MockResponse::text(200, "text/event-stream", ""),
+    ]);
+    let client = mock_orchestrate_client(&server, &["api_error"]).with_idempotency_keys(true);
+    client.stream_message_typed("agent-1", "hi", None).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/runs/stream");
+    let key = requests[0].header("X-Idempotency-Key").unwrap();
+    assert!(key.starts_with("agent-1-"));
+    assert_eq!(requests[1].header("X-Idempotency-Key"), Some(key));
+}
+
+#[tokio::test]
+async fn test_orchestrate_stream_event_parse_line() {
+    use crate::{OrchestrateEventType, OrchestrateStreamEvent};
+
+    let delta = OrchestrateStreamEvent::parse_line(
+        r#"{"event":"message.delta","data":{"thread_id":"t-1","run_id":"r-1","delta":{"content":[{"text":"Hel"}]}}}"#,
+    )
+    .unwrap();
+    assert_eq!(delta.event_type, OrchestrateEventType::MessageDelta);
+    assert_eq!(delta.delta.as_deref(), Some("Hel"));
+    assert_eq!(delta.thread_id.as_deref(), Some("t-1"));
+    assert_eq!(delta.run_id.as_deref(), Some("r-1"));
+
+    let completed = OrchestrateStreamEvent::parse_line(r#"{"event":"run.completed","data":{}}"#).unwrap();
+    assert_eq!(completed.event_type, OrchestrateEventType::RunCompleted);
+    assert!(completed.delta.is_none());
+
+    let tool = OrchestrateStreamEvent::parse_line(r#"{"event":"run.step.tool_calls","data":{}}"#).unwrap();
+    assert_eq!(tool.event_type, OrchestrateEventType::ToolCall);
+
+    assert!(OrchestrateStreamEvent::parse_line("").is_none());
+    assert!(OrchestrateStreamEvent::parse_line("not json").is_none());
+}
+
+#[tokio::test]
+async fn test_send_message_result_from_stream_text() {
+    use crate::SendMessageResult;
+
+    let text = [
+        r#"{"event":"run.created","data":{"id":"run-1","thread_id":"t-1"}}"#,
+        r#"{"event":"tool_call.created","data":{"id":"call-1","name":"weather","arguments":"{\"city\":\"Paris\"}"}}"#,
+        r#"{"event":"tool_call.completed","data":{"id":"call-1","result":{"temp":21}}}"#,
+        r#"{"event":"message.created","data":{"thread_id":"t-1","message":{"content":[{"text":"It is 21C"}]}}}"#,
+        r#"{"event":"run.completed","data":{"duration_ms":1200}}"#,
+    ]
+    .join("\n");
+
+    let result = SendMessageResult::from_stream_text(&text, None);
+    assert_eq!(result.answer, "It is 21C");
+    assert_eq!(result.thread_id.as_deref(), Some("t-1"));
+    assert_eq!(result.run_id.as_deref(), Some("run-1"));
+    assert_eq!(result.duration_ms, Some(1200));
+    assert_eq!(result.tool_calls.len(), 1);
+    assert_eq!(result.tool_calls[0].tool_name, "weather");
+    assert_eq!(result.tool_calls[0].parameters["city"], "Paris");
+    assert_eq!(result.tool_calls[0].result, Some(serde_json::json!({"temp": 21})));
+}
+
+#[tokio::test]
+async fn test_chat_with_docs_response_citations() {
+    use crate::ChatWithDocsResponse;
+
+    let response: ChatWithDocsResponse = serde_json::from_str(
+        r#"{
+            "message": "Rust is memory safe.",
+            "documents_used": [
+                {"document_id": "doc-1", "title": "Rust Book", "relevance_score": 0.92, "excerpt": "ownership", "page_number": 4},
+                "doc-2"
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert!(response.has_citations());
+    let citations = response.citations();
+    assert_eq!(citations.len(), 2);
+    assert_eq!(citations[0].title, "Rust Book");
+    assert_eq!(citations[0].page_number, Some(4));
+    assert_eq!(citations[1].document_id, "doc-2");
+
+    let response: ChatWithDocsResponse = serde_json::from_str(r#"{"message": "No sources"}"#).unwrap();
+    assert!(!response.has_citations());
+}
+
+#[tokio::test]
+async fn test_batch_search_documents_isolates_failures() {
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    let queries: Vec<SearchRequest> = ["refunds", "shipping", "warranty"]
+        .iter()
+        .map(|query| SearchRequest {
+            query: query.to_string(),
+            limit: Some(3),
+            threshold: None,
+            filters: None,
+        })
+        .collect();
+
+    let results = client
+        .batch_search_documents_with_concurrency("coll-1", &queries, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2].query, "warranty");
+    assert!(results.iter().all(|r| !r.is_success() && r.results.is_empty()));
+}
+
+#[tokio::test]
+async fn test_batch_search_documents_requests() {
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::Error;
+    use serde_json::json;
+
+    let server = MockServer::with_handler(|request| {
+        let query = request.json()["query"].as_str().unwrap_or_default().to_string();
+        if query == "broken" {
+            return MockResponse::json(500, json!({"error": "index unavailable"}));
+        }
+        MockResponse::json(200, json!({
+            "results": [{
+                "document_id": format!("doc-{}", query),
+                "title": "FAQ",
+                "content_snippet": format!("About {}", query),
+                "similarity_score": 0.9,
+                "metadata": {}
+            }],
+            "total_results": 1,
+            "metadata": {}
+        }))
+    });
+    let client = mock_orchestrate_client(&server, &[]);
+    let query = |text: &str| SearchRequest {
+        query: text.to_string(),
+        limit: Some(3),
+        threshold: None,
+        filters: None,
+    };
+
+    let results = client
+        .batch_search_documents_with_concurrency("kb-1", &[query("refunds"), query("broken"), query("shipping")], 2)
+        .await
+        .unwrap();
+    let queries: Vec<&str> = results.iter().map(|r| r.query.as_str()).collect();
+    assert_eq!(queries, vec!["refunds", "broken", "shipping"]);
+    assert_eq!(results[0].results[0].document_id, "doc-refunds");
+    assert_eq!(results[2].results[0].content_snippet, "About shipping");
+    assert!(results[0].is_success() && results[2].is_success());
+    assert!(matches!(&results[1].error, Some(Error::Api(message)) if message.contains("index unavailable")));
+    assert!(results[1].results.is_empty());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    for request in &requests {
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/collections/kb-1/search"));
+        assert_eq!(request.json()["limit"], 3);
+    }
+}
+
+#[tokio::test]
+async fn test_collection_filter_matches() {
+    use crate::{CollectionFilter, CollectionStatus, DocumentCollection};
+
+    let collection = DocumentCollection {
+        id: "coll-1".to_string(),
+        name: "HR Policies".to_string(),
+        description: None,
+        status: CollectionStatus::Active,
+        created_at: None,
+        updated_at: None,
+        document_count: 12,
+        vector_index: None,
+        tags: Some(vec!["hr".to_string(), "internal".to_string()]),
+        metadata: None,
+    };
+
+    assert!(CollectionFilter::new().matches(&collection));
+    assert!(CollectionFilter::new()
+        .with_status(CollectionStatus::Active)
+        .with_name_contains("policies")
+        .with_tags(vec!["hr".to_string()])
+        .with_has_documents(true)
+        .matches(&collection));
+    assert!(!CollectionFilter::new().with_status(CollectionStatus::Processing).matches(&collection));
+    assert!(!CollectionFilter::new().with_tags(vec!["finance".to_string()]).matches(&collection));
+    assert!(!CollectionFilter::new().with_has_documents(false).matches(&collection));
+}
+
+#[tokio::test]
+async fn test_tool_rate_limiter() {
+    use crate::orchestrate::rate_limit::TokenBucket;
+    use std::time::Duration;
+
+    let mut limiter = TokenBucket::new(2.0);
+    assert_eq!(limiter.reserve(), Duration::ZERO);
+    assert_eq!(limiter.reserve(), Duration::ZERO);
+    let wait = limiter.reserve();
+    assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()))
+        .with_tool_rate_limit("weather", 5.0);
+    let stats = client.tool_rate_limiter_stats("weather").unwrap();
+    assert_eq!(stats.max_rps, 5.0);
+    assert_eq!(stats.total_acquired, 0);
+    assert!(client.tool_rate_limiter_stats("search").is_none());
+}
+
+#[tokio::test]
+async fn test_tool_execution_result_helpers() {
+    use crate::{ToolExecutionResult, ToolExecutionStatus};
+
+    #[derive(serde::Deserialize)]
+    struct Weather {
+        temp: f64,
+    }
+
+    let result: ToolExecutionResult = serde_json::from_str(
+        r#"{"tool_id":"weather","status":"success","output":{"temp":21.5},"execution_time_ms":250,"error":null}"#,
+    )
+    .unwrap();
+    assert_eq!(result.status, ToolExecutionStatus::Success);
+    assert_eq!(result.parse_output::<Weather>().unwrap().temp, 21.5);
+    assert_eq!(result.elapsed(), Some(std::time::Duration::from_millis(250)));
+    assert!(result.output_as_str().is_none());
+    assert!(result.parse_output::<String>().is_err());
+
+    let failed: ToolExecutionResult = serde_json::from_str(
+        r#"{"tool_id":"calc","status":"failed","result":42,"execution_time_ms":null,"error":"boom"}"#,
+    )
+    .unwrap();
+    assert_eq!(failed.status, ToolExecutionStatus::Failure);
+    assert!(!failed.status.is_success());
+    assert_eq!(failed.output_as_number(), Some(42.0));
+    assert!(failed.elapsed().is_none());
+}
+
+#[test]
+fn test_tool_execution_status_unknown() {
+    use crate::{ToolExecutionResult, ToolExecutionStatus};
+
+    let result: ToolExecutionResult =
+        serde_json::from_str(r#"{"tool_id":"weather","status":"awaiting_approval","output":null}"#).unwrap();
+    assert_eq!(result.status, ToolExecutionStatus::Unknown);
+    assert!(!result.status.is_success());
+}
+
+#[tokio::test]
+async fn test_tool_filter_and_client_side_paging() {
+    use crate::{ListOptions, Page, Tool, ToolFilter, ToolType};
+
+    let tool = |id: &str, name: &str, tool_type: ToolType, enabled: bool| Tool {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: None,
+        tool_type: Some(tool_type),
+        config: None,
+        enabled,
+        version: None,
+    };
+    let tools = vec![
+        tool("1", "Weather API", ToolType::Api, true),
+        tool("2", "Weather DB", ToolType::Database, true),
+        tool("3", "Legacy weather API", ToolType::Api, false),
+        tool("4", "Calculator", ToolType::Api, true),
+    ];
+
+    let filter = ToolFilter::new()
+        .with_name_contains("weather")
+        .with_tool_type(ToolType::Api)
+        .with_enabled_only(true);
+    let matching: Vec<_> = tools.iter().filter(|t| filter.matches(t)).collect();
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].id, "1");
+
+    let page = Page::from_items(tools, &ListOptions::new().with_limit(3).with_offset(2));
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].id, "3");
+    assert_eq!(page.total, Some(4));
+    assert!(!page.has_more());
+}
+
+#[tokio::test]
+async fn test_thread_info_metadata() {
+    use crate::ThreadInfo;
+
+    let thread: ThreadInfo = serde_json::from_str(
+        r#"{"thread_id":"t-1","agent_id":"a-1","title":null,"created_at":null,"updated_at":null,"message_count":3,"metadata":{"customer":"acme"}}"#,
+    )
+    .unwrap();
+    assert_eq!(thread.metadata.unwrap()["customer"], "acme");
+
+    let bare: ThreadInfo = serde_json::from_str(r#"{"thread_id":"t-2"}"#).unwrap();
+    assert!(bare.metadata.is_none());
+}
+
+#[tokio::test]
+async fn test_skill_version_deserialization() {
+    use crate::SkillVersion;
+
+    let versions: Vec<SkillVersion> = serde_json::from_str(
+        r#"[{"id":"v1","created_at":"2024-01-01T00:00:00Z","active":false,"changelog":"Initial"},{"version_id":"v2","created_at":"2024-02-01T00:00:00Z","is_active":true}]"#,
+    )
+    .unwrap();
+    assert_eq!(versions[0].version_id, "v1");
+    assert!(!versions[0].is_active);
+    assert_eq!(versions[0].changelog.as_deref(), Some("Initial"));
+    assert!(versions[1].is_active);
+    assert!(versions[1].changelog.is_none());
+}
+
+#[tokio::test]
+async fn test_message_payload_builder_with_attachments() {
+    use crate::{AttachmentData, MessageAttachment, MessagePayload};
+
+    let payload = MessagePayload::builder("agent-1", "Review this")
+        .with_thread_id(Some("t-1".to_string()))
+        .with_attachment(MessageAttachment::from_bytes("notes.txt", "text/plain", b"hello"))
+        .with_attachment(MessageAttachment::from_url("cv.pdf", "application/pdf", "https://example.com/cv.pdf"))
+        .build();
+    assert_eq!(payload.attachments[0].data, AttachmentData::Base64("aGVsbG8=".to_string()));
+
+    let json = serde_json::to_value(&payload).unwrap();
+    assert_eq!(json["message"]["role"], "user");
+    assert_eq!(json["thread_id"], "t-1");
+    assert_eq!(json["attachments"][0]["data"]["base64"], "aGVsbG8=");
+    assert_eq!(json["attachments"][1]["data"]["url"], "https://example.com/cv.pdf");
+
+    let plain = serde_json::to_value(MessagePayload::builder("agent-1", "Hi").build()).unwrap();
+    assert!(plain.get("attachments").is_none());
+}
+
+#[tokio::test]
+async fn test_import_tools_from_openapi_dir_reports_per_file_errors() {
+    use crate::{CreateToolRequest, Error};
+
+    let request = CreateToolRequest::from_openapi(serde_json::json!({
+        "openapi": "3.0.0",
+        "info": {"title": "Weather", "description": "Weather lookups"}
+    }))
+    .unwrap();
+    assert_eq!(request.name, "Weather");
+    assert_eq!(request.description.as_deref(), Some("Weather lookups"));
+    assert!(CreateToolRequest::from_openapi(serde_json::json!({"openapi": "3.0.0"})).is_err());
+
+    let dir = std::env::temp_dir().join(format!("watsonx-openapi-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a_broken.json"), "{not json").unwrap();
+    std::fs::write(dir.join("b_untitled.json"), r#"{"openapi":"3.0.0","info":{}}"#).unwrap();
+    std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance-id".to_string()));
+    let results = client.import_tools_from_openapi_dir(&dir).await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(results[0], Err(Error::Serialization(_))));
+    assert!(matches!(results[1], Err(Error::InvalidInput(_))));
+
+    assert!(client.import_tools_from_openapi_dir("/nonexistent/openapi").await.is_err());
+}
+
+#[tokio::test]
+async fn test_create_tool_request_validation() {
+    use crate::{CreateToolRequest, Error};
+
+    // Without a specification or an endpoint, nothing is sent
+    let mut empty = CreateToolRequest::new("Weather", crate::ToolType::Api, "");
+    empty.endpoint = None;
+    assert!(matches!(empty.validate(), Err(Error::InvalidInput(_))));
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance-id".to_string()));
+    assert!(matches!(client.create_tool(empty).await, Err(Error::InvalidInput(_))));
+    assert!(CreateToolRequest::new("Weather", crate::ToolType::Api, "https://example.com/weather").validate().is_ok());
+
+    #[cfg(feature = "yaml")]
+    {
+        let request = CreateToolRequest::from_spec_yaml("openapi: 3.0.0\ninfo:\n  title: Weather\n").unwrap();
+        assert_eq!(request.name, "Weather");
+        assert_eq!(request.openapi_spec.unwrap()["openapi"], "3.0.0");
+    }
+}
+
+#[test]
+fn test_agent_deserialization() {
+    let minimal: Agent = serde_json::from_value(serde_json::json!({
+        "id": "agent-1",
+        "display_name": "Helper"
+    }))
+    .unwrap();
+    assert_eq!(minimal.agent_id, "agent-1");
+    assert!(minimal.description.is_none());
+    assert!(minimal.tool_ids.is_none());
+
+    let full: Agent = serde_json::from_value(serde_json::json!({
+        "id": "agent-2",
+        "display_name": "Researcher",
+        "description": "Finds things",
+        "status": "active",
+        "llm": "watsonx/ibm/granite-3-8b-instruct",
+        "created_at": "2024-01-01T00:00:00Z",
+        "updated_at": "2024-01-02T00:00:00Z",
+        "tool_ids": ["tool-1", "tool-2"],
+        "skill_ids": ["skill-1"],
+        "tags": ["team-search", "prod"],
+        "style": "default"
+    }))
+    .unwrap();
+    assert_eq!(full.description.as_deref(), Some("Finds things"));
+    assert_eq!(full.status.as_deref(), Some("active"));
+    assert_eq!(full.model_id.as_deref(), Some("watsonx/ibm/granite-3-8b-instruct"));
+    assert_eq!(full.tool_ids.unwrap().len(), 2);
+    assert_eq!(full.skill_ids.unwrap(), vec!["skill-1".to_string()]);
+    assert_eq!(full.tags.unwrap(), vec!["team-search".to_string(), "prod".to_string()]);
+    assert!(minimal.tags.is_none());
+}
+
+#[test]
+fn test_create_agent_request_serialization() {
+    let body = serde_json::to_value(crate::CreateAgentRequest::new("Helper")).unwrap();
+    assert_eq!(body, serde_json::json!({"display_name": "Helper"}));
+
+    let search = crate::SearchRequest {
+        query: String::new(),
+        limit: Some(5),
+        threshold: Some(0.7),
+        filters: None,
+    };
+    let request = crate::CreateAgentRequest::new("Policy Bot")
+        .with_instructions("Answer from the policy handbook")
+        .with_knowledge_base(vec!["hr-policies".to_string()])
+        .with_knowledge_base_search_config(search);
+    let body = serde_json::to_value(&request).unwrap();
+    assert_eq!(body["instructions"], "Answer from the policy handbook");
+    assert_eq!(body["knowledge_base_collection_ids"], serde_json::json!(["hr-policies"]));
+    assert_eq!(body["knowledge_base_search_config"]["limit"], 5);
+}
+
+#[test]
+fn test_update_agent_request_serialization() {
+    let body = serde_json::to_value(crate::UpdateAgentRequest::new()).unwrap();
+    assert_eq!(body, serde_json::json!({}));
+
+    let request = crate::UpdateAgentRequest::new()
+        .with_name("Policy Bot v2")
+        .with_tool_ids(vec!["tool-1".to_string()]);
+    let body = serde_json::to_value(&request).unwrap();
+    assert_eq!(body, serde_json::json!({"display_name": "Policy Bot v2", "tool_ids": ["tool-1"]}));
+}
+
+#[test]
+fn test_update_skill_request_serialization() {
+    let request = crate::UpdateSkillRequest {
+        description: Some("Summarize support tickets".to_string()),
+        openapi_spec: Some(serde_json::json!({"openapi": "3.0.0"})),
+        ..Default::default()
+    };
+    let body = serde_json::to_value(&request).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"description": "Summarize support tickets", "openapi_spec": {"openapi": "3.0.0"}})
+    );
+}
+
+#[test]
+fn test_run_tool_calls_parsing() {
+    let wrapped = serde_json::json!({
+        "tool_calls": [
+            {"id": "call-1", "name": "weather", "arguments": "{\"city\":\"Paris\"}", "output": "sunny"},
+            {"tool_call_id": "call-2", "tool_name": "search", "parameters": {"q": "rust"}}
+        ]
+    });
+    let calls = crate::ToolCall::list_from_response(&wrapped);
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].tool_name, "weather");
+    assert_eq!(calls[0].parameters["city"], "Paris");
+    assert_eq!(calls[0].result, Some(serde_json::json!("sunny")));
+    assert_eq!(calls[1].id, "call-2");
+    assert!(calls[1].result.is_none());
+
+    let bare = serde_json::json!([{"id": "call-3", "name": "lookup"}]);
+    assert_eq!(crate::ToolCall::list_from_response(&bare).len(), 1);
+    assert!(crate::ToolCall::list_from_response(&serde_json::json!({})).is_empty());
+}
+
+#[test]
+fn test_message_payload_previous_messages() {
+    let history = vec![
+        crate::Message { role: "user".to_string(), content: "Hi".to_string() },
+        crate::Message { role: "assistant".to_string(), content: "Hello!".to_string() },
+    ];
+    let payload = crate::MessagePayload::builder("agent-1", "How are you?")
+        .with_previous_messages(history)
+        .build();
+    let body = serde_json::to_value(&payload).unwrap();
+    assert_eq!(body["message"]["content"], "How are you?");
+    assert_eq!(body["previous_messages"].as_array().unwrap().len(), 2);
+    assert_eq!(body["previous_messages"][1]["role"], "assistant");
+
+    let body = serde_json::to_value(crate::MessagePayload::builder("agent-1", "Hi").build()).unwrap();
+    assert!(body.get("previous_messages").is_none());
+}
+
+#[test]
+fn test_message_idempotency_key() {
+    let payload = crate::MessagePayload::builder("agent-1", "Hi").build();
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    assert!(client.message_idempotency_key(&payload).is_none());
+
+    let client = client.with_idempotency_keys(true);
+    let key = client.message_idempotency_key(&payload).unwrap();
+    assert!(key.starts_with("agent-1-"));
+    assert_ne!(client.message_idempotency_key(&payload).unwrap(), key);
+
+    let payload = crate::MessagePayload::builder("agent-1", "Hi")
+        .with_idempotency_key("my-key")
+        .build();
+    assert_eq!(client.message_idempotency_key(&payload).as_deref(), Some("my-key"));
+    let body = serde_json::to_value(&payload).unwrap();
+    assert!(body.get("idempotency_key").is_none());
+}
+
+#[test]
+fn test_create_thread_request_serialization() {
+    let body = serde_json::to_value(crate::CreateThreadRequest::new()).unwrap();
+    assert_eq!(body, serde_json::json!({}));
+
+    let request = crate::CreateThreadRequest::new()
+        .with_agent_id("agent-1")
+        .with_metadata("customer", serde_json::json!("acme"))
+        .with_initial_context("locale", serde_json::json!("en-US"));
+    let body = serde_json::to_value(&request).unwrap();
+    assert_eq!(body["agent_id"], "agent-1");
+    assert_eq!(body["metadata"]["customer"], "acme");
+    assert_eq!(body["initial_context"]["locale"], "en-US");
+}
+
+#[tokio::test]
+async fn test_thread_rate_limiter() {
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()))
+        .with_thread_rate_limiter(5.0);
+
+    client.acquire_thread_rate_limit(None).await;
+    for _ in 0..5 {
+        client.acquire_thread_rate_limit(Some("thread-a")).await;
+    }
+    client.acquire_thread_rate_limit(Some("thread-b")).await;
+
+    let started = std::time::Instant::now();
+    client.acquire_thread_rate_limit(Some("thread-a")).await;
+    assert!(started.elapsed() >= std::time::Duration::from_millis(150));
+
+    let stats = client.thread_rate_limiter_stats("thread-a").unwrap();
+    assert_eq!(stats.total_acquired, 6);
+    assert_eq!(client.thread_rate_limiter_stats("thread-b").unwrap().total_acquired, 1);
+
+    client.thread_rate_limiter_remove("thread-a");
+    assert!(client.thread_rate_limiter_stats("thread-a").is_none());
+
+    let unlimited = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    unlimited.acquire_thread_rate_limit(Some("thread-a")).await;
+    assert!(unlimited.thread_rate_limiter_stats("thread-a").is_none());
+}
+
+#[test]
+fn test_webhook_types() {
+    let request = crate::WebhookRequest::new(
+        "https://example.com/hooks",
+        vec![crate::WebhookEventType::RunCompleted, crate::WebhookEventType::RunFailed],
+    )
+    .with_secret("s3cret");
+    let body = serde_json::to_value(&request).unwrap();
+    assert_eq!(body["events"], serde_json::json!(["run_completed", "run_failed"]));
+    assert_eq!(body["secret"], "s3cret");
+
+    let payload: crate::WebhookPayload = serde_json::from_str(
+        r#"{"event_type":"message_created","thread_id":"thread-1","data":{"content":"Hi"}}"#,
+    )
+    .unwrap();
+    assert_eq!(payload.event, crate::WebhookEventType::MessageCreated);
+    assert_eq!(payload.thread_id.as_deref(), Some("thread-1"));
+    assert_eq!(payload.data["content"], "Hi");
+
+    let payload: crate::WebhookPayload =
+        serde_json::from_str(r#"{"event_type":"agent_deleted","data":{}}"#).unwrap();
+    assert_eq!(payload.event, crate::WebhookEventType::Unknown);
+
+    let webhook: crate::Webhook = serde_json::from_str(
+        r#"{"id":"hook-1","url":"https://example.com/hooks","events":["run_failed","agent_deleted"]}"#,
+    )
+    .unwrap();
+    assert_eq!(webhook.events, vec![crate::WebhookEventType::RunFailed, crate::WebhookEventType::Unknown]);
+}
+
+#[test]
+fn test_webhook_verify_signature() {
+    // HMAC-SHA256("key", "The quick brown fox jumps over the lazy dog")
+    let payload = b"The quick brown fox jumps over the lazy dog";
+    let signature = "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8";
+
+    assert!(crate::Webhook::verify_signature(payload, signature, "key"));
+    assert!(crate::Webhook::verify_signature(payload, &format!("sha256={}", signature), "key"));
+    assert!(!crate::Webhook::verify_signature(payload, signature, "other-key"));
+    assert!(!crate::Webhook::verify_signature(b"tampered", signature, "key"));
+    assert!(!crate::Webhook::verify_signature(payload, "not-hex", "key"));
+}
+
+#[tokio::test]
+async fn test_run_skill_batch() {
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    let requests = vec![
+        crate::SkillExecutionRequest::new("skill-a").with_parameter("city", serde_json::json!("Paris")),
+        crate::SkillExecutionRequest::new("skill-b"),
+    ];
+
+    // Without credentials every item fails before any request is sent
+    let results = client.run_skill_batch(requests.clone(), 1).await.unwrap();
+    let ids: Vec<&str> = results.iter().map(|r| r.skill_id.as_str()).collect();
+    assert_eq!(ids, vec!["skill-a", "skill-b"]);
+    assert!(results.iter().all(|r| matches!(r.result, Err(crate::Error::Authentication(_)))));
+
+    let config = crate::BatchSkillConfig::default().with_continue_on_error(false);
+    let err = client.run_skill_batch_with_config(requests.clone(), 2, &config).await.unwrap_err();
+    assert!(matches!(err, crate::Error::Authentication(_)));
+
+    assert!(matches!(
+        client.run_skill_batch(requests, 0).await,
+        Err(crate::Error::InvalidInput(_))
+    ));
+    assert!(client.run_skill_batch(Vec::new(), 4).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_run_skill_batch_requests() {
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::{BatchSkillConfig, Error, SkillExecutionRequest, ToolExecutionStatus};
+    use serde_json::json;
+
+    let server = MockServer::with_handler(|request| {
+        if request.path == "/skills/skill-b/execute" {
+            return MockResponse::json(500, json!({"error": "skill crashed"}));
+        }
+        let body = request.json();
+        MockResponse::json(200, json!({
+            "skill_id": request.path.trim_start_matches("/skills/").trim_end_matches("/execute"),
+            "status": "succeeded",
+            "output": {"echo": body["parameters"]["city"]},
+            "execution_time_ms": 12
+        }))
+    });
+    let client = mock_orchestrate_client(&server, &[]);
+    let requests = vec![
+        SkillExecutionRequest::new("skill-a").with_parameter("city", json!("Paris")),
+        SkillExecutionRequest::new("skill-b"),
+        SkillExecutionRequest::new("skill-c").with_parameter("city", json!("Oslo")),
+    ];
+
+    let results = client.run_skill_batch(requests.clone(), 1).await.unwrap();
+    let ids: Vec<&str> = results.iter().map(|r| r.skill_id.as_str()).collect();
+    assert_eq!(ids, vec!["skill-a", "skill-b", "skill-c"]);
+    let first = results[0].result.as_ref().unwrap();
+    assert_eq!(first.status, ToolExecutionStatus::Success);
+    assert_eq!(first.result, json!({"echo": "Paris"}));
+    assert_eq!(first.execution_time_ms, Some(12));
+    assert!(matches!(&results[1].result, Err(Error::Api(message)) if message.contains("skill crashed")));
+    assert_eq!(results[2].result.as_ref().unwrap().result, json!({"echo": "Oslo"}));
+
+    // With one slot, items are sent in submission order
+    let requests_sent = server.requests();
+    let paths: Vec<&str> = requests_sent.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(paths, vec!["/skills/skill-a/execute", "/skills/skill-b/execute", "/skills/skill-c/execute"]);
+    assert!(requests_sent.iter().all(|r| r.method == "POST"));
+    assert_eq!(requests_sent[0].json()["parameters"], json!({"city": "Paris"}));
+
+    let config = BatchSkillConfig::default().with_continue_on_error(false);
+    let error = client.run_skill_batch_with_config(requests, 1, &config).await.unwrap_err();
+    assert!(matches!(error, Error::Api(_)));
+}
+
+#[tokio::test]
+async fn test_message_builder_modes() {
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    let request: crate::OrchestrateMessageRequest = crate::MessagePayload::builder("agent-1", "Hello")
+        .with_thread_id(Some("thread-1".to_string()))
+        .build();
+
+    let builder = client.message(request.clone());
+    assert_eq!(builder.request().agent_id, "agent-1");
+    assert_eq!(builder.request().thread_id.as_deref(), Some("thread-1"));
+
+    // Every mode shares the request and fails the same way without credentials
+    assert!(matches!(
+        client.message(request.clone()).non_streaming().await,
+        Err(crate::Error::Authentication(_))
+    ));
+    assert!(matches!(
+        client.message(request.clone()).streaming(|_| Ok(())).await,
+        Err(crate::Error::Authentication(_))
+    ));
+    assert!(matches!(
+        client.message(request).channel().await,
+        Err(crate::Error::Authentication(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_message_builder_modes_against_mock_server() {
+    use crate::mock_server::{MockResponse, MockServer};
+
+    const RUN: &str = concat!(
+        r#"{"event":"run.created","data":{"id":"run-1","thread_id":"thread-1"}}"#, "\n",
+        r#"{"event":"message.delta","data":{"thread_id":"thread-1","delta":{"content":[{"text":"Hel"}]}}}"#, "\n",
+        r#"{"event":"message.delta","data":{"thread_id":"thread-1","delta":{"content":[{"text":"lo"}]}}}"#, "\n",
+        r#"{"event":"message.created","data":{"thread_id":"thread-2","message":{"content":[{"text":"Hello"}]}}}"#, "\n",
+        r#"{"event":"run.completed","data":{"duration_ms":40}}"#, "\n",
+    );
+    let server = MockServer::with_handler(|_| MockResponse::text(200, "text/event-stream", RUN));
+    let client = mock_orchestrate_client(&server, &[]);
+    let request: crate::OrchestrateMessageRequest = crate::MessagePayload::builder("agent-1", "Hello")
+        .with_thread_id(Some("thread-1".to_string()))
+        .build();
+
+    let result = client.message(request.clone()).non_streaming().await.unwrap();
+    assert_eq!(result.answer, "Hello");
+    assert_eq!(result.run_id.as_deref(), Some("run-1"));
+    assert_eq!(result.duration_ms, Some(40));
+
+    let mut deltas = Vec::new();
+    let thread_id = client
+        .message(request.clone())
+        .streaming(|delta| {
+            deltas.push(delta);
+            Ok(())
+        })
+        .await
+        .unwrap();
+    assert_eq!(deltas, vec!["Hel", "lo"]);
+    assert_eq!(thread_id.as_deref(), Some("thread-2"));
+
+    let (mut receiver, handle) = client.message(request).channel().await.unwrap();
+    let mut deltas = Vec::new();
+    while let Some(delta) = receiver.recv().await {
+        deltas.push(delta);
+    }
+    assert_eq!(deltas, vec!["Hel", "lo"]);
+    assert_eq!(handle.await.unwrap().unwrap().as_deref(), Some("thread-2"));
+
+    // Every mode posts the same payload
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    for sent in &requests {
+        assert_eq!((sent.method.as_str(), sent.path.as_str()), ("POST", "/runs/stream"));
+        assert_eq!(sent.json()["agent_id"], "agent-1");
+        assert_eq!(sent.json()["thread_id"], "thread-1");
+    }
+    assert_eq!(requests[0].json()["message"], requests[1].json()["message"]);
+}
+
+#[test]
+fn test_agent_capabilities() {
+    let agent: Agent = serde_json::from_value(serde_json::json!({
+        "id": "agent-1",
+        "display_name": "Helper",
+        "llm": "ibm/granite-3-8b-instruct",
+        "instructions": "Be brief.",
+        "supported_languages": ["en", "fr"],
+        "tool_ids": ["tool-1"]
+    }))
+    .unwrap();
+    let tool: crate::Tool = serde_json::from_value(serde_json::json!({
+        "id": "tool-1",
+        "name": "weather",
+        "description": null,
+        "version": null
+    }))
+    .unwrap();
+
+    let capabilities = crate::AgentCapabilities::new(agent, vec![tool], Vec::new());
+    assert_eq!(capabilities.model_id, "ibm/granite-3-8b-instruct");
+    assert_eq!(capabilities.system_prompt.as_deref(), Some("Be brief."));
+    assert!(capabilities.max_context_tokens.is_none());
+    assert!(capabilities.has_tool("weather"));
+    assert!(capabilities.has_tool("tool-1"));
+    assert!(!capabilities.has_skill("search"));
+    assert!(capabilities.supports_language("FR"));
+    assert!(!capabilities.supports_language("de"));
+}
+
+#[test]
+fn test_parse_list_response() {
+    use crate::orchestrate::client::parse_list_response;
+
+    let bare = parse_list_response::<Agent>(r#"[{"id":"a1","display_name":"One"}]"#, "agents").unwrap();
+    assert_eq!(bare[0].agent_id, "a1");
+    let wrapped = parse_list_response::<Agent>(r#"{"agents":[]}"#, "agents").unwrap();
+    assert!(wrapped.is_empty());
+    assert!(parse_list_response::<Agent>("[]", "agents").unwrap().is_empty());
+
+    // Unexpected shapes are errors, not empty lists
+    for body in [r#"{"items":[]}"#, r#"{"error":"unauthorized"}"#, "<html></html>", r#"{"agents":"none"}"#] {
+        let err = parse_list_response::<Agent>(body, "agents").unwrap_err();
+        assert!(matches!(err, crate::Error::Serialization(ref msg) if msg.contains(body)), "{}", body);
+    }
+}
+
+#[test]
+fn test_docs_stream_decoder() {
+    use crate::orchestrate::chat::DocsStreamDecoder;
+
+    let mut decoder = DocsStreamDecoder::default();
+    let lines = [
+        "event: message.delta",
+        r#"data: {"delta": {"content": [{"text": "Rust "}]}}"#,
+        "",
+        "event: run.step",
+        r#"data: {"content": [{"text": "ignored"}]}"#,
+        "",
+        r#"data: {"event": "message.delta", "data": {"delta": {"content": [{"text": "is "}]}}}"#,
+        r#"{"event": "message.delta", "data": {"delta": {"content": [{"text": "safe"}]}}}"#,
+        r#"{"event": "run.completed", "data": {"run_id": "run-1"}}"#,
+        "data: [DONE]",
+    ];
+
+    let text: String = lines.iter().filter_map(|line| decoder.decode_line(line)).collect();
+    assert_eq!(text, "Rust is safe");
+}
+
+#[tokio::test]
+async fn test_auth_modes() {
+    use crate::error::Error;
+    use crate::AuthMode;
+
+    let config = OrchestrateConfig::cp4d_url(
+        "127.0.0.1:1".to_string(),
+        "admin".to_string(),
+        "cp4d-key".to_string(),
+    );
+    assert_eq!(config.get_base_url(), "https://127.0.0.1:1/api/v1/");
+    assert!(matches!(&config.auth_mode, AuthMode::Cp4d { username, .. } if username == "admin"));
+    let client = OrchestrateClient::new(config);
+    assert!(matches!(client.connect().await, Err(Error::Network(_))));
+
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    assert!(matches!(client.connect().await, Err(Error::Configuration(_))));
+    assert!(matches!(client.connect_cp4d().await, Err(Error::Configuration(_))));
+
+    let config = OrchestrateConfig::new("test-instance".to_string()).with_auth_mode(AuthMode::StaticToken);
+    let client = OrchestrateClient::new(config);
+    assert!(matches!(client.connect().await, Err(Error::Authentication(_))));
+    client.set_token("static-token".to_string());
+    assert!(client.connect().await.is_ok());
+    assert_eq!(client.ensure_token_valid().await.unwrap(), "static-token");
+}
+
+#[tokio::test]
+async fn test_iter_collection_documents_stops_after_error() {
+    use futures::StreamExt;
+
+    let client = OrchestrateClient::new(OrchestrateConfig::new("test-instance".to_string()));
+    let results: Vec<_> = client.iter_collection_documents("collection-1").collect().await;
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(crate::error::Error::Authentication(_))));
+}
+
+#[tokio::test]
+async fn test_collection_document_listing_requests() {
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::ListOptions;
+    use futures::TryStreamExt;
+    use serde_json::json;
+
+    fn document(id: &str) -> serde_json::Value {
+        json!({"id": id, "title": id, "content": "text", "metadata": {}, "document_type": "Text"})
+    }
+
+    let server = MockServer::with_handler(|request| {
+        if request.path.contains("offset=2") {
+            MockResponse::json(200, json!([document("doc-3")]))
+        } else {
+            MockResponse::json(200, json!({"documents": [document("doc-1"), document("doc-2")], "total": 3}))
+        }
+    });
+    let client = mock_orchestrate_client(&server, &[]);
+
+    let page = client
+        .list_collection_documents("collection-1", ListOptions::new().with_limit(2))
+        .await
+        .unwrap();
+    let ids: Vec<&str> = page.items.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(ids, vec!["doc-1", "doc-2"]);
+    assert_eq!(page.total, Some(3));
+    assert_eq!(page.limit, Some(2));
+    assert_eq!(page.offset, 0);
+    assert!(page.has_more());
+
+    let documents: Vec<_> = client.iter_collection_documents("collection-1").try_collect().await.unwrap();
+    let ids: Vec<&str> = documents.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(ids, vec!["doc-1", "doc-2", "doc-3"]);
+
+    let requests = server.requests();
+    let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/collections/collection-1/documents?limit=2",
+            "/collections/collection-1/documents?limit=100",
+            "/collections/collection-1/documents?limit=100&offset=2",
+        ]
+    );
+    assert!(requests.iter().all(|r| r.method == "GET"));
+    assert_eq!(requests[0].header("authorization"), Some("Bearer test-token"));
+}
+
+#[test]
+fn test_reindex_job_deserialization() {
+    use crate::{ReindexJob, ReindexStatus};
+
+    let job: ReindexJob =
+        serde_json::from_str(r#"{"id": "job-1", "status": "running", "estimated_duration_secs": 90}"#).unwrap();
+    assert_eq!(job.job_id, "job-1");
+    assert_eq!(job.status, ReindexStatus::InProgress);
+    assert!(!job.status.is_terminal());
+    assert_eq!(job.estimated_duration_secs, Some(90));
+
+    let job: ReindexJob = serde_json::from_str(r#"{"job_id": "job-1", "status": "completed"}"#).unwrap();
+    assert!(job.status.is_terminal());
+    assert!(job.estimated_duration_secs.is_none());
+}
@@ -0,0 +1,317 @@
+//! Zero-shot text classification
+//!
+//! `WatsonxClient::classify` asks the model to pick from a fixed set of labels and maps its
+//! answer back onto those labels, tolerating differences in case and extra words.
+
+use crate::client::WatsonxClient;
+use crate::error::{Error, Result};
+use crate::models::DEFAULT_MODEL;
+use crate::types::{DecodingMethod, GenerationConfig};
+
+/// Default single-label prompt; `{labels}` and `{text}` are replaced when building it
+pub const DEFAULT_CLASSIFICATION_TEMPLATE: &str = "Classify the text into exactly one of these labels: {labels}.\nAnswer with the label followed by your confidence between 0 and 1 in parentheses, e.g. \"label (0.8)\".\n\nText: {text}\nLabel:";
+
+/// Default multi-label prompt; `{labels}` and `{text}` are replaced when building it
+pub const DEFAULT_MULTI_LABEL_TEMPLATE: &str = "Classify the text into every one of these labels that applies: {labels}.\nAnswer with the matching labels separated by commas, each followed by your confidence between 0 and 1 in parentheses, e.g. \"label (0.8), other (0.6)\".\n\nText: {text}\nLabels:";
+
+/// Settings for `WatsonxClient::classify`
+#[derive(Clone, Debug)]
+pub struct ClassificationConfig {
+    /// Model used for classification
+    pub model_id: String,
+    /// Allow more than one label to be chosen
+    pub multi_label: bool,
+    /// Custom prompt with `{labels}` and `{text}` placeholders
+    pub prompt_template: Option<String>,
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        Self {
+            model_id: DEFAULT_MODEL.to_string(),
+            multi_label: false,
+            prompt_template: None,
+        }
+    }
+}
+
+impl ClassificationConfig {
+    /// Create a single-label config using the default model
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the model
+    pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+
+    /// Allow more than one label to be chosen
+    pub fn with_multi_label(mut self, multi_label: bool) -> Self {
+        self.multi_label = multi_label;
+        self
+    }
+
+    /// Use a custom prompt with `{labels}` and `{text}` placeholders
+    pub fn with_prompt_template(mut self, template: impl Into<String>) -> Self {
+        self.prompt_template = Some(template.into());
+        self
+    }
+
+    /// Build the classification prompt for `text`
+    pub fn prompt(&self, text: &str, labels: &[&str]) -> String {
+        let template = match (&self.prompt_template, self.multi_label) {
+            (Some(template), _) => template.as_str(),
+            (None, false) => DEFAULT_CLASSIFICATION_TEMPLATE,
+            (None, true) => DEFAULT_MULTI_LABEL_TEMPLATE,
+        };
+        template
+            .replace("{labels}", &labels.join(", "))
+            .replace("{text}", text.trim())
+    }
+
+    /// Generation settings: greedy decoding with room for every label when multi-label
+    fn generation_config(&self, label_count: usize) -> GenerationConfig {
+        let max_tokens = if self.multi_label {
+            20 * label_count as u32 + 20
+        } else {
+            30
+        };
+        GenerationConfig::default()
+            .with_model(self.model_id.clone())
+            .with_decoding_method(DecodingMethod::Greedy)
+            .with_max_tokens(max_tokens)
+    }
+}
+
+/// How closely the model output matched the chosen label
+///
+/// Ordered from weakest to strongest, so the minimum over several labels is the weakest match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchConfidence {
+    /// The output only contained the label, or was contained in it
+    Fuzzy,
+    /// The output matched the label apart from letter case
+    CaseInsensitive,
+    /// The output was exactly the label
+    Exact,
+}
+
+/// Outcome of `WatsonxClient::classify`
+#[derive(Clone, Debug)]
+pub struct ClassificationResult {
+    /// The chosen label (the first one when multi-label)
+    pub label: String,
+    /// Every chosen label, in the order the model gave them
+    pub labels: Vec<String>,
+    /// Confidence reported by the model for `label`, if it gave one
+    pub confidence: Option<f32>,
+    /// Weakest match between the output and the chosen labels
+    pub match_confidence: MatchConfidence,
+    /// Unprocessed model output
+    pub raw_output: String,
+}
+
+impl ClassificationResult {
+    /// Map model output onto `labels`
+    ///
+    /// Returns `Error::Serialization` if no label can be recognized in the output.
+    pub fn parse(raw_output: &str, labels: &[&str], multi_label: bool) -> Result<Self> {
+        let answer = raw_output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        let candidates: Vec<&str> = if multi_label {
+            answer.split([',', ';']).collect()
+        } else {
+            vec![answer]
+        };
+
+        let mut chosen: Vec<(String, Option<f32>, MatchConfidence)> = Vec::new();
+        for candidate in candidates {
+            let (candidate, confidence) = split_confidence(candidate);
+            if let Some((label, quality)) = match_label(candidate, labels) {
+                if !chosen.iter().any(|(l, _, _)| l == label) {
+                    chosen.push((label.to_string(), confidence, quality));
+                }
+            }
+        }
+
+        let Some((label, confidence, _)) = chosen.first().cloned() else {
+            return Err(Error::Serialization(format!(
+                "Model output {:?} does not match any of the labels: {}",
+                raw_output.trim(),
+                labels.join(", ")
+            )));
+        };
+        let match_confidence = chosen
+            .iter()
+            .map(|(_, _, quality)| *quality)
+            .min()
+            .unwrap_or(MatchConfidence::Fuzzy);
+
+        Ok(Self {
+            label,
+            labels: chosen.into_iter().map(|(label, _, _)| label).collect(),
+            confidence,
+            match_confidence,
+            raw_output: raw_output.to_string(),
+        })
+    }
+}
+
+/// Split a trailing confidence such as `(0.8)`, `: 0.8` or `80%` off a label
+fn split_confidence(candidate: &str) -> (&str, Option<f32>) {
+    let candidate = candidate.trim();
+    let Some((label, last)) = candidate.rsplit_once([' ', ':', '(', '-', '=']) else {
+        return (candidate, None);
+    };
+    let number = last.trim_matches(|c: char| c == '(' || c == ')' || c == '.' || c.is_whitespace());
+    let confidence = match number.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|p| p / 100.0),
+        None => number.parse::<f32>().ok(),
+    };
+    match confidence.filter(|c| (0.0..=1.0).contains(c)) {
+        Some(confidence) => (
+            label.trim_end_matches(|c: char| {
+                c == '(' || c == ':' || c == '-' || c == '=' || c.is_whitespace()
+            }),
+            Some(confidence),
+        ),
+        None => (candidate, None),
+    }
+}
+
+/// Find the label named by `candidate`, preferring exact over case-insensitive over fuzzy matches
+fn match_label<'a>(candidate: &str, labels: &[&'a str]) -> Option<(&'a str, MatchConfidence)> {
+    let candidate = candidate
+        .trim()
+        .trim_matches(|c: char| c == '"' || c == '\'' || c == '.' || c == '*');
+    if candidate.is_empty() {
+        return None;
+    }
+    if let Some(label) = labels.iter().find(|label| **label == candidate) {
+        return Some((label, MatchConfidence::Exact));
+    }
+    if let Some(label) = labels
+        .iter()
+        .find(|label| label.eq_ignore_ascii_case(candidate))
+    {
+        return Some((label, MatchConfidence::CaseInsensitive));
+    }
+
+    // Prefer the longest label mentioned in the output, so "very positive" beats "positive"
+    let lower = candidate.to_lowercase();
+    labels
+        .iter()
+        .filter(|label| lower.contains(&label.to_lowercase()))
+        .max_by_key(|label| label.len())
+        .or_else(|| {
+            labels
+                .iter()
+                .find(|label| label.to_lowercase().contains(&lower))
+        })
+        .map(|label| (*label, MatchConfidence::Fuzzy))
+}
+
+impl WatsonxClient {
+    /// Classify `text` into one (or, with `multi_label`, several) of `labels`
+    ///
+    /// Returns `Error::InvalidInput` if `labels` is empty and `Error::Serialization` if the
+    /// model's answer names none of the labels.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{ClassificationConfig, WatsonxClient, WatsonxConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let result = client
+    ///     .classify("The battery died after a day", &["positive", "negative", "neutral"], &ClassificationConfig::new())
+    ///     .await?;
+    /// println!("{} ({:?})", result.label, result.confidence);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn classify(
+        &self,
+        text: &str,
+        labels: &[&str],
+        config: &ClassificationConfig,
+    ) -> Result<ClassificationResult> {
+        if labels.is_empty() {
+            return Err(Error::InvalidInput(
+                "At least one label is required for classification".to_string(),
+            ));
+        }
+
+        let output = self
+            .generate_text(
+                &config.prompt(text, labels),
+                &config.generation_config(labels.len()),
+            )
+            .await?;
+        ClassificationResult::parse(&output.text, labels, config.multi_label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABELS: &[&str] = &["positive", "very positive", "negative"];
+
+    #[test]
+    fn test_classification_parse_single_label() {
+        let result =
+            ClassificationResult::parse(" negative (0.85)\nExplanation: ...", LABELS, false)
+                .unwrap();
+        assert_eq!(result.label, "negative");
+        assert_eq!(result.confidence, Some(0.85));
+        assert_eq!(result.match_confidence, MatchConfidence::Exact);
+
+        let result = ClassificationResult::parse("Positive: 90%", LABELS, false).unwrap();
+        assert_eq!(result.label, "positive");
+        assert_eq!(result.confidence, Some(0.9));
+        assert_eq!(result.match_confidence, MatchConfidence::CaseInsensitive);
+
+        let result =
+            ClassificationResult::parse("The sentiment is very positive.", LABELS, false).unwrap();
+        assert_eq!(result.label, "very positive");
+        assert!(result.confidence.is_none());
+        assert_eq!(result.match_confidence, MatchConfidence::Fuzzy);
+
+        assert!(matches!(
+            ClassificationResult::parse("unsure", LABELS, false),
+            Err(Error::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn test_classification_parse_multi_label() {
+        let result =
+            ClassificationResult::parse("negative (0.7), Positive (0.4), negative", LABELS, true)
+                .unwrap();
+        assert_eq!(result.labels, vec!["negative", "positive"]);
+        assert_eq!(result.label, "negative");
+        assert_eq!(result.confidence, Some(0.7));
+        assert_eq!(result.match_confidence, MatchConfidence::CaseInsensitive);
+    }
+
+    #[test]
+    fn test_classification_prompt() {
+        let config = ClassificationConfig::new().with_prompt_template("{text} -> {labels}");
+        assert_eq!(config.prompt(" great ", &["a", "b"]), "great -> a, b");
+        assert!(
+            ClassificationConfig::new()
+                .with_multi_label(true)
+                .prompt("x", &["a"])
+                .contains("every one of these labels")
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Ready-made prompts for common tasks
+//!
+//! These build the prompt and generation settings for a task so callers don't have to
+//! craft them by hand. Summarization is provided by `WatsonxClient::summarize` and
+//! zero-shot classification by `WatsonxClient::classify`.
+
+pub mod classification;
+
+pub use classification::{ClassificationConfig, ClassificationResult, MatchConfidence};
+
+use crate::client::WatsonxClient;
+use crate::error::Result;
+use crate::types::{DecodingMethod, GenerationConfig, GenerationResult};
+
+/// Default summarization prompt; `{instructions}` and `{text}` are replaced when building it
+pub const DEFAULT_SUMMARY_TEMPLATE: &str = "{instructions}\n\nText:\n{text}\n\nSummary:\n";
+
+/// Shape of the summary produced by `WatsonxClient::summarize`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SummarizationStyle {
+    /// A bulleted list of the main ideas
+    Bullet,
+    /// A single paragraph of at most this many sentences
+    Paragraph(u8),
+    /// An abstract in the style of a technical paper
+    TechnicalAbstract,
+    /// Exactly this many key points, one per line
+    KeyPoints(u8),
+}
+
+impl SummarizationStyle {
+    /// Instructions describing this style to the model
+    pub fn instructions(&self) -> String {
+        match *self {
+            Self::Bullet => {
+                "Summarize the text below as a bulleted list of its main ideas, one \"- \" bullet per line.".to_string()
+            }
+            Self::Paragraph(max_sentences) => format!(
+                "Summarize the text below in a single paragraph of at most {} sentences.",
+                max_sentences.max(1)
+            ),
+            Self::TechnicalAbstract => "Write a technical abstract of the text below, covering the problem, the approach, and the results or conclusions.".to_string(),
+            Self::KeyPoints(n) => format!(
+                "List the {} most important points of the text below, numbered, one per line.",
+                n.max(1)
+            ),
+        }
+    }
+
+    /// Token budget for a summary in this style
+    pub fn max_tokens(&self) -> u32 {
+        match *self {
+            Self::Bullet => 300,
+            Self::Paragraph(max_sentences) => 60 * u32::from(max_sentences.max(1)),
+            Self::TechnicalAbstract => 350,
+            Self::KeyPoints(n) => 50 * u32::from(n.max(1)),
+        }
+    }
+}
+
+/// Settings for `WatsonxClient::summarize_with_config`
+#[derive(Clone, Debug)]
+pub struct SummarizationConfig {
+    /// Shape of the summary
+    pub style: SummarizationStyle,
+    /// Language to write the summary in (defaults to the language of the text)
+    pub language: Option<String>,
+    /// Topic or aspect the summary should concentrate on
+    pub focus: Option<String>,
+    /// Model to use instead of the default generation model
+    pub model_id: Option<String>,
+    /// Prompt template with `{instructions}` and `{text}` placeholders
+    pub template: String,
+}
+
+impl SummarizationConfig {
+    /// Create a config for the given style
+    pub fn new(style: SummarizationStyle) -> Self {
+        Self {
+            style,
+            language: None,
+            focus: None,
+            model_id: None,
+            template: DEFAULT_SUMMARY_TEMPLATE.to_string(),
+        }
+    }
+
+    /// Write the summary in this language
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Concentrate the summary on this topic
+    pub fn with_focus(mut self, focus: impl Into<String>) -> Self {
+        self.focus = Some(focus.into());
+        self
+    }
+
+    /// Use this model instead of the default
+    pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = Some(model_id.into());
+        self
+    }
+
+    /// Use a custom prompt template with `{instructions}` and `{text}` placeholders
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+
+    /// Build the summarization prompt for `text`
+    pub fn prompt(&self, text: &str) -> String {
+        let mut instructions = self.style.instructions();
+        if let Some(focus) = &self.focus {
+            instructions.push_str(&format!(" Focus on {}.", focus));
+        }
+        if let Some(language) = &self.language {
+            instructions.push_str(&format!(" Write the summary in {}.", language));
+        }
+        instructions.push_str(" Only use information from the text.");
+
+        self.template
+            .replace("{instructions}", &instructions)
+            .replace("{text}", text.trim())
+    }
+
+    /// Generation settings for this style: greedy decoding within the style's token budget
+    pub fn generation_config(&self) -> GenerationConfig {
+        let config = GenerationConfig::default()
+            .with_decoding_method(DecodingMethod::Greedy)
+            .with_max_tokens(self.style.max_tokens());
+        match &self.model_id {
+            Some(model_id) => config.with_model(model_id.clone()),
+            None => config,
+        }
+    }
+}
+
+impl From<SummarizationStyle> for SummarizationConfig {
+    fn from(style: SummarizationStyle) -> Self {
+        Self::new(style)
+    }
+}
+
+impl WatsonxClient {
+    /// Summarize `text` in the given style
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{SummarizationStyle, WatsonxClient, WatsonxConfig};
+    ///
+    /// # async fn example(report: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let summary = client.summarize(report, SummarizationStyle::KeyPoints(3)).await?;
+    /// println!("{}", summary.text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn summarize(&self, text: &str, style: SummarizationStyle) -> Result<GenerationResult> {
+        self.summarize_with_config(text, &SummarizationConfig::new(style)).await
+    }
+
+    /// Summarize `text` with explicit language, focus, model and template settings
+    pub async fn summarize_with_config(
+        &self,
+        text: &str,
+        config: &SummarizationConfig,
+    ) -> Result<GenerationResult> {
+        self.generate_text(&config.prompt(text), &config.generation_config()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarization_prompt() {
+        let config = SummarizationConfig::new(SummarizationStyle::Paragraph(2))
+            .with_focus("security")
+            .with_language("French");
+        let prompt = config.prompt("  The quick brown fox.\n");
+        assert!(prompt.starts_with("Summarize the text below in a single paragraph of at most 2 sentences."));
+        assert!(prompt.contains("Focus on security. Write the summary in French."));
+        assert!(prompt.ends_with("Text:\nThe quick brown fox.\n\nSummary:\n"));
+
+        let custom = SummarizationConfig::new(SummarizationStyle::Bullet).with_template("{text} => {instructions}");
+        assert!(custom.prompt("fox").starts_with("fox => Summarize"));
+    }
+
+    #[test]
+    fn test_summarization_generation_config() {
+        assert_eq!(SummarizationStyle::KeyPoints(4).max_tokens(), 200);
+        assert_eq!(SummarizationStyle::Paragraph(0).max_tokens(), 60);
+
+        let config = SummarizationConfig::new(SummarizationStyle::TechnicalAbstract).with_model("ibm/granite-13b");
+        let generation = config.generation_config();
+        assert_eq!(generation.max_tokens, 350);
+        assert_eq!(generation.model_id, "ibm/granite-13b");
+        assert_eq!(
+            SummarizationConfig::new(SummarizationStyle::Bullet).generation_config().model_id,
+            GenerationConfig::default().model_id
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Quality assessment strategies for generated text
+//!
+//! `QualityAssessor` scores generated text on a 0.0-1.0 scale. Two implementations
+//! are provided: `HeuristicQualityAssessor` (offline, keyword and length based) and
+//! `LlmQualityAssessor` (asks a judge model to rate the text).
+
+use crate::client::WatsonxClient;
+use crate::error::{Error, Result};
+use crate::models::models;
+use crate::types::{DecodingMethod, GenerationConfig};
+use std::future::Future;
+
+/// A strategy for scoring generated text between 0.0 and 1.0
+pub trait QualityAssessor {
+    /// Score `text` as a response to `prompt`
+    fn assess(&self, text: &str, prompt: &str) -> impl Future<Output = Result<f32>> + Send;
+}
+
+/// Offline heuristic based on length, word count and error keywords
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HeuristicQualityAssessor;
+
+impl QualityAssessor for HeuristicQualityAssessor {
+    fn assess(&self, text: &str, _prompt: &str) -> impl Future<Output = Result<f32>> + Send {
+        let score = heuristic_score(text);
+        async move { Ok(score) }
+    }
+}
+
+/// Model-based assessor that asks a judge model for a 0-10 rating
+#[derive(Clone)]
+pub struct LlmQualityAssessor {
+    client: WatsonxClient,
+    judge_config: GenerationConfig,
+}
+
+impl LlmQualityAssessor {
+    /// Create an assessor using Granite 3.3 8B Instruct as the judge
+    pub fn new(client: WatsonxClient) -> Self {
+        let judge_config = GenerationConfig::default()
+            .with_model(models::GRANITE_3_3_8B_INSTRUCT)
+            .with_decoding_method(DecodingMethod::Greedy)
+            .with_max_tokens(8);
+        Self { client, judge_config }
+    }
+
+    /// Set the generation config used for the judge model
+    pub fn with_judge_config(mut self, judge_config: GenerationConfig) -> Self {
+        self.judge_config = judge_config;
+        self
+    }
+}
+
+impl QualityAssessor for LlmQualityAssessor {
+    fn assess(&self, text: &str, prompt: &str) -> impl Future<Output = Result<f32>> + Send {
+        let text = text.to_string();
+        let prompt = prompt.to_string();
+        async move {
+            self.client
+                .assess_quality_llm(&text, &prompt, &self.judge_config)
+                .await
+        }
+    }
+}
+
+/// Heuristic quality score used by `WatsonxClient::assess_quality`
+pub(crate) fn heuristic_score(text: &str) -> f32 {
+    let mut score = 0.0;
+    let mut max_score = 0.0;
+
+    // Check if result is not empty and reasonable length
+    max_score += 0.3;
+    let trimmed = text.trim();
+    if !trimmed.is_empty() && trimmed.len() > 8 && trimmed.len() < 200 {
+        score += 0.3;
+    }
+
+    // Check for common patterns
+    max_score += 0.2;
+    let common_patterns = [
+        "the", "and", "or", "but", "in", "on", "at", "to", "for", "of", "with", "by",
+    ];
+    if common_patterns.iter().any(|pattern| text.to_lowercase().contains(pattern)) {
+        score += 0.2;
+    }
+
+    // Check if it doesn't contain obvious errors
+    max_score += 0.2;
+    let error_indicators = ["error", "failed", "invalid", "unknown", "not found"];
+    if !error_indicators
+        .iter()
+        .any(|indicator| text.to_lowercase().contains(indicator))
+    {
+        score += 0.2;
+    }
+
+    // Check for proper sentence structure
+    max_score += 0.15;
+    let sentence_count = text.split('.').filter(|s| !s.trim().is_empty()).count();
+    if sentence_count > 0 {
+        score += 0.15;
+    }
+
+    // Check for reasonable word count
+    max_score += 0.15;
+    let word_count = text.split_whitespace().count();
+    if word_count > 3 && word_count < 100 {
+        score += 0.15;
+    }
+
+    if max_score > 0.0 {
+        score / max_score
+    } else {
+        0.0
+    }
+}
+
+/// Build the meta-prompt asking the judge model for a rating
+pub(crate) fn judge_prompt(text: &str, prompt: &str) -> String {
+    format!(
+        "You are an impartial judge. Rate the quality of the response to the prompt below \
+         for factual accuracy, coherence and relevance on a scale from 0 to 10.\n\
+         Reply with only the number.\n\n\
+         Prompt:\n{}\n\nResponse:\n{}\n\nRating:",
+        prompt.trim(),
+        text.trim()
+    )
+}
+
+/// Parse the first number in the judge's reply as a 0-10 rating
+pub(crate) fn parse_judge_score(reply: &str) -> Result<f32> {
+    let start = reply
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or_else(|| Error::Api(format!("Judge response did not contain a score: {}", reply.trim())))?;
+    let number: String = reply[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let rating: f32 = number
+        .trim_end_matches('.')
+        .parse()
+        .map_err(|_| Error::Api(format!("Judge response did not contain a score: {}", reply.trim())))?;
+
+    Ok(rating.clamp(0.0, 10.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_judge_score() {
+        assert_eq!(parse_judge_score("8").unwrap(), 8.0);
+        assert_eq!(parse_judge_score(" Rating: 7.5/10").unwrap(), 7.5);
+        assert_eq!(parse_judge_score("9.").unwrap(), 9.0);
+        assert_eq!(parse_judge_score("42").unwrap(), 10.0);
+        assert!(parse_judge_score("excellent").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_assessor() {
+        let assessor = HeuristicQualityAssessor;
+        let good = assessor
+            .assess("This is a well-formed sentence with proper grammar.", "prompt")
+            .await
+            .unwrap();
+        assert!(good > 0.5);
+        assert!(assessor.assess("error", "prompt").await.unwrap() < 0.5);
+    }
+}
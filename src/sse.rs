@@ -2,10 +2,15 @@
 //!
 //! This module provides reusable functions for parsing SSE streams from WatsonX API responses.
 
+use crate::config::SseBufferConfig;
 use crate::error::{Error, Result};
-use futures::StreamExt;
+use crate::types::StopReason;
+use futures::{Stream, StreamExt};
 use reqwest::Response;
 use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use tokio::time::Instant;
 
 /// Parse SSE stream and extract text content
 ///
@@ -22,57 +27,206 @@ use serde_json::Value;
 /// Complete accumulated text from all SSE data events
 pub async fn parse_sse_stream<F>(
     response: Response,
-    mut callback: Option<F>,
+    callback: Option<F>,
 ) -> Result<String>
 where
     F: FnMut(&str),
 {
-    let mut answer = String::new();
-    let mut stream = response.bytes_stream();
-    let mut buffer = String::new();
+    parse_sse_stream_with_limits(response, callback, &SseBufferConfig::default()).await
+}
 
-    // Process stream chunks in real-time
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| {
+/// Parse SSE stream and extract text content, bounding memory use
+///
+/// Returns `Error::Api` if a single line grows beyond `limits.max_line_bytes`. When the
+/// accumulated text reaches `limits.max_total_bytes`, the text is truncated at that size
+/// and returned without reading the rest of the stream.
+pub async fn parse_sse_stream_with_limits<F>(
+    response: Response,
+    callback: Option<F>,
+    limits: &SseBufferConfig,
+) -> Result<String>
+where
+    F: FnMut(&str),
+{
+    let output = parse_sse_chunks(response_chunks(response), callback, limits).await?;
+    Ok(output.text)
+}
+
+/// Text accumulated from an SSE stream, with the final stop reason if one was reported
+#[derive(Debug, Default)]
+pub(crate) struct SseOutput {
+    pub(crate) text: String,
+    pub(crate) stop_reason: Option<StopReason>,
+}
+
+/// Raw byte chunks of a streaming response body
+pub(crate) type ByteChunks = Pin<Box<dyn Stream<Item = Result<Vec<u8>>> + Send>>;
+
+/// Map a response body into a stream of byte chunks
+pub(crate) fn response_chunks(response: Response) -> ByteChunks {
+    Box::pin(response.bytes_stream().map(|chunk| {
+        chunk.map(|bytes| bytes.to_vec()).map_err(|e| {
             Error::Network(format!(
                 "Failed to read SSE stream chunk: {}. Check your network connection.",
                 e
             ))
-        })?;
-        let text = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&text);
+        })
+    }))
+}
+
+/// Wait for the first chunk of a stream, failing with `Error::Timeout` if none arrives by `deadline`
+///
+/// The returned stream yields the first chunk followed by the rest of `chunks`.
+pub(crate) async fn first_chunk_by(mut chunks: ByteChunks, deadline: Instant) -> Result<ByteChunks> {
+    let first = tokio::select! {
+        chunk = chunks.next() => chunk,
+        _ = tokio::time::sleep_until(deadline) => return Err(no_tokens_received()),
+    };
+    Ok(Box::pin(futures::stream::iter(first).chain(chunks)))
+}
+
+/// Read `chunks` through a bounded buffer of `capacity` chunks
+///
+/// Returns a pump future that reads the response and the stream fed by it. The pump must
+/// be polled alongside the stream; it waits whenever the buffer is full, which stops reading
+/// the response body until the consumer catches up. It finishes once the response ends, a
+/// read fails, or the stream is dropped.
+fn bounded_chunks(
+    mut chunks: ByteChunks,
+    capacity: usize,
+) -> (impl Future<Output = ()>, impl Stream<Item = Result<Vec<u8>>>) {
+    let (sender, receiver) = tokio::sync::mpsc::channel(capacity.max(1));
+
+    let pump = async move {
+        while let Some(chunk) = chunks.next().await {
+            let failed = chunk.is_err();
+            if sender.send(chunk).await.is_err() || failed {
+                break;
+            }
+        }
+    };
+    let buffered = futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+
+    (pump, buffered)
+}
+
+/// Error returned when a stream produces nothing before its first token timeout
+pub(crate) fn no_tokens_received() -> Error {
+    Error::Timeout("No tokens received before the first token timeout".to_string())
+}
+
+/// Like `parse_sse_chunks`, reading at most `capacity` chunks ahead of the callback
+///
+/// Reading stops as soon as parsing finishes, so an error does not drain the rest of the body.
+pub(crate) async fn parse_bounded_sse_chunks<F>(
+    chunks: ByteChunks,
+    capacity: usize,
+    callback: Option<F>,
+    limits: &SseBufferConfig,
+) -> Result<SseOutput>
+where
+    F: FnMut(&str),
+{
+    let (pump, buffered) = bounded_chunks(chunks, capacity);
+    let parse = parse_sse_chunks(buffered, callback, limits);
+    tokio::pin!(pump, parse);
+
+    tokio::select! {
+        output = &mut parse => output,
+        () = &mut pump => parse.await,
+    }
+}
+
+/// Accumulate text from a stream of raw SSE bytes, enforcing buffer limits
+pub(crate) async fn parse_sse_chunks<S, F>(
+    chunks: S,
+    mut callback: Option<F>,
+    limits: &SseBufferConfig,
+) -> Result<SseOutput>
+where
+    S: Stream<Item = Result<Vec<u8>>>,
+    F: FnMut(&str),
+{
+    let mut answer = String::new();
+    let mut stop_reason = None;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut chunks = std::pin::pin!(chunks);
+
+    // Append a text chunk, returning false once the total limit has been reached
+    let mut push = |answer: &mut String, text: &str| -> bool {
+        let text = match limits.max_total_bytes {
+            Some(max) if answer.len() + text.len() >= max => {
+                let mut end = max.saturating_sub(answer.len());
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                &text[..end]
+            }
+            _ => text,
+        };
+        answer.push_str(text);
+        if let Some(ref mut cb) = callback {
+            cb(text);
+        }
+        limits.max_total_bytes.is_none_or(|max| answer.len() < max)
+    };
+
+    // Process stream chunks in real-time
+    while let Some(chunk) = chunks.next().await {
+        buffer.extend_from_slice(&chunk?);
 
         // Process complete lines from buffer
-        while let Some(newline_pos) = buffer.find('\n') {
-            let line = buffer[..newline_pos].to_string();
-            buffer = buffer[newline_pos + 1..].to_string();
+        while let Some(newline_pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline_pos).collect();
+            if line.len() > limits.max_line_bytes {
+                return Err(line_too_large(limits.max_line_bytes));
+            }
 
-            if let Some(text_chunk) = parse_sse_line(&line)? {
-                answer.push_str(&text_chunk);
-                if let Some(ref mut cb) = callback {
-                    cb(&text_chunk);
+            let Some(data) = parse_sse_data(&String::from_utf8_lossy(&line)) else {
+                continue;
+            };
+            stop_reason = extract_stop_reason(&data).or(stop_reason);
+            if let Some(text_chunk) = extract_text_from_json(&data)? {
+                if !push(&mut answer, &text_chunk) {
+                    tracing::warn!(
+                        "SSE response truncated at {} bytes",
+                        limits.max_total_bytes.unwrap_or_default()
+                    );
+                    return Ok(SseOutput { text: answer, stop_reason });
                 }
             }
         }
+
+        if buffer.len() > limits.max_line_bytes {
+            return Err(line_too_large(limits.max_line_bytes));
+        }
     }
 
     // Process any remaining data in buffer
-    if !buffer.is_empty() {
-        if let Some(text_chunk) = parse_sse_line(&buffer)? {
-            answer.push_str(&text_chunk);
-            if let Some(ref mut cb) = callback {
-                cb(&text_chunk);
-            }
+    if let Some(data) = parse_sse_data(&String::from_utf8_lossy(&buffer)) {
+        stop_reason = extract_stop_reason(&data).or(stop_reason);
+        if let Some(text_chunk) = extract_text_from_json(&data)? {
+            push(&mut answer, &text_chunk);
         }
     }
 
-    Ok(answer)
+    Ok(SseOutput { text: answer, stop_reason })
+}
+
+/// Error returned when a single SSE line exceeds `max_line_bytes`
+pub(crate) fn line_too_large(max_line_bytes: usize) -> Error {
+    Error::Api(format!(
+        "SSE line too large: exceeds {} bytes",
+        max_line_bytes
+    ))
 }
 
-/// Parse a single SSE line and extract text content if it's a data event
+/// Parse the JSON payload of an SSE data line
 ///
-/// Returns `None` for non-data lines or empty data, `Some(text)` for valid data events.
-pub(crate) fn parse_sse_line(line: &str) -> Result<Option<String>> {
+/// Returns `None` for empty, id, event and non-data lines, done markers, and malformed JSON.
+fn parse_sse_data(line: &str) -> Option<Value> {
     let trimmed = line.trim();
 
     // Skip empty lines, id lines, and event type lines
@@ -80,37 +234,39 @@ pub(crate) fn parse_sse_line(line: &str) -> Result<Option<String>> {
         || trimmed.starts_with("id:")
         || trimmed.starts_with("event:")
     {
-        return Ok(None);
+        return None;
     }
 
     // Process data lines
-    if trimmed.starts_with("data:") {
-        let json_data = if trimmed.starts_with("data: ") {
-            &trimmed[6..]
-        } else {
-            &trimmed[5..]
-        };
+    let json_data = trimmed.strip_prefix("data:")?;
 
-        // Skip empty data or done markers
-        let trimmed_data = json_data.trim();
-        if trimmed_data.is_empty() || trimmed_data == "[DONE]" {
-            return Ok(None);
-        }
+    // Skip empty data or done markers
+    let trimmed_data = json_data.trim();
+    if trimmed_data.is_empty() || trimmed_data == "[DONE]" {
+        return None;
+    }
 
-        // Try to parse as JSON and extract text
-        match serde_json::from_str::<Value>(trimmed_data) {
-            Ok(data) => extract_text_from_json(&data),
-            Err(e) => {
-                // Log warning but don't fail - some SSE chunks may be malformed
-                eprintln!("Warning: Failed to parse SSE data line: {}. Skipping.", e);
-                Ok(None)
-            }
+    // Try to parse as JSON
+    match serde_json::from_str::<Value>(trimmed_data) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            // Log warning but don't fail - some SSE chunks may be malformed
+            eprintln!("Warning: Failed to parse SSE data line: {}. Skipping.", e);
+            None
         }
-    } else {
-        Ok(None)
     }
 }
 
+/// Extract the stop reason from a text generation event, ignoring `not_finished`
+fn extract_stop_reason(data: &Value) -> Option<StopReason> {
+    data.get("results")?
+        .as_array()?
+        .first()?
+        .get("stop_reason")?
+        .as_str()
+        .and_then(StopReason::from_api)
+}
+
 /// Extract text content from JSON data structure
 ///
 /// Handles different response formats:
@@ -262,38 +418,38 @@ mod tests {
     use serde_json::json;
 
     #[test]
-    fn test_parse_sse_line_empty() {
-        assert!(parse_sse_line("").unwrap().is_none());
-        assert!(parse_sse_line("   ").unwrap().is_none());
+    fn test_parse_sse_data_empty() {
+        assert!(parse_sse_data("").is_none());
+        assert!(parse_sse_data("   ").is_none());
     }
 
     #[test]
-    fn test_parse_sse_line_non_data() {
-        assert!(parse_sse_line("id: 123").unwrap().is_none());
-        assert!(parse_sse_line("event: message").unwrap().is_none());
-        assert!(parse_sse_line(": comment").unwrap().is_none());
+    fn test_parse_sse_data_non_data() {
+        assert!(parse_sse_data("id: 123").is_none());
+        assert!(parse_sse_data("event: message").is_none());
+        assert!(parse_sse_data(": comment").is_none());
     }
 
     #[test]
-    fn test_parse_sse_line_done() {
-        assert!(parse_sse_line("data: [DONE]").unwrap().is_none());
-        assert!(parse_sse_line("data:  [DONE]").unwrap().is_none());
+    fn test_parse_sse_data_done() {
+        assert!(parse_sse_data("data: [DONE]").is_none());
+        assert!(parse_sse_data("data:  [DONE]").is_none());
     }
 
     #[test]
-    fn test_parse_sse_line_text_generation_format() {
+    fn test_parse_sse_data_text_generation_format() {
         let json = json!({
             "results": [{
                 "generated_text": "Hello, world!"
             }]
         });
         let line = format!("data: {}", json);
-        let result = parse_sse_line(&line).unwrap();
+        let result = extract_text_from_json(&parse_sse_data(&line).unwrap()).unwrap();
         assert_eq!(result, Some("Hello, world!".to_string()));
     }
 
     #[test]
-    fn test_parse_sse_line_chat_completion_delta() {
+    fn test_parse_sse_data_chat_completion_delta() {
         let json = json!({
             "choices": [{
                 "delta": {
@@ -302,12 +458,12 @@ mod tests {
             }]
         });
         let line = format!("data: {}", json);
-        let result = parse_sse_line(&line).unwrap();
+        let result = extract_text_from_json(&parse_sse_data(&line).unwrap()).unwrap();
         assert_eq!(result, Some("Hello".to_string()));
     }
 
     #[test]
-    fn test_parse_sse_line_chat_completion_message() {
+    fn test_parse_sse_data_chat_completion_message() {
         let json = json!({
             "choices": [{
                 "message": {
@@ -316,7 +472,7 @@ mod tests {
             }]
         });
         let line = format!("data: {}", json);
-        let result = parse_sse_line(&line).unwrap();
+        let result = extract_text_from_json(&parse_sse_data(&line).unwrap()).unwrap();
         assert_eq!(result, Some("Complete response".to_string()));
     }
 
@@ -366,12 +522,117 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_parse_sse_chunks_limits() {
+        let data = |text: &str| format!("data: {}\n", json!({"results": [{"generated_text": text}]}));
+        let chunks = |lines: Vec<String>| futures::stream::iter(lines.into_iter().map(|l| Ok(l.into_bytes())));
+        let no_callback: Option<fn(&str)> = None;
+
+        let limits = SseBufferConfig::default().with_max_total_bytes(8);
+        let text = parse_sse_chunks(chunks(vec![data("Hello"), data(" world"), data("!")]), no_callback, &limits)
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "Hello wo");
+
+        let limits = SseBufferConfig::default().with_max_line_bytes(16);
+        let err = parse_sse_chunks(chunks(vec![data("a long line of text")]), no_callback, &limits)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("SSE line too large"));
+
+        let text = parse_sse_chunks(chunks(vec![data("Hel"), data("lo")]), no_callback, &SseBufferConfig::default())
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_parse_sse_chunks_stop_reason() {
+        let data = |text: &str, reason: &str| {
+            format!("data: {}\n", json!({"results": [{"generated_text": text, "stop_reason": reason}]}))
+        };
+        let chunks = |lines: Vec<String>| futures::stream::iter(lines.into_iter().map(|l| Ok(l.into_bytes())));
+        let no_callback: Option<fn(&str)> = None;
+
+        let output = parse_sse_chunks(
+            chunks(vec![data("Hel", "not_finished"), data("lo", "max_tokens")]),
+            no_callback,
+            &SseBufferConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.text, "Hello");
+        assert_eq!(output.stop_reason, Some(StopReason::MaxTokens));
+
+        let output = parse_sse_chunks(chunks(vec![data("Hi", "not_finished")]), no_callback, &SseBufferConfig::default())
+            .await
+            .unwrap();
+        assert!(output.stop_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_first_chunk_by() {
+        let deadline = || Instant::now() + std::time::Duration::from_millis(50);
+
+        let hung: ByteChunks = Box::pin(futures::stream::pending());
+        let err = first_chunk_by(hung, deadline()).await.err().unwrap();
+        assert!(matches!(err, Error::Timeout(msg) if msg.starts_with("No tokens received")));
+
+        let ready: ByteChunks = Box::pin(futures::stream::iter(vec![Ok(b"a".to_vec()), Ok(b"b".to_vec())]));
+        let chunks: Vec<Vec<u8>> = first_chunk_by(ready, deadline())
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks, vec![b"a".to_vec(), b"b".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn test_bounded_chunks() {
+        let chunks: ByteChunks = Box::pin(futures::stream::iter(
+            (0..5).map(|i| Ok(format!("data: {{\"results\":[{{\"generated_text\":\"{}\"}}]}}\n", i).into_bytes())),
+        ));
+        let (pump, buffered) = bounded_chunks(chunks, 1);
+        let no_callback: Option<fn(&str)> = None;
+        let limits = SseBufferConfig::default();
+
+        let (_, output) = tokio::join!(pump, parse_sse_chunks(buffered, no_callback, &limits));
+        assert_eq!(output.unwrap().text, "01234");
+
+        // Dropping the consumer early lets the pump finish
+        let chunks: ByteChunks = Box::pin(futures::stream::iter((0..100).map(|_| Ok(b"x".to_vec()))));
+        let (pump, buffered) = bounded_chunks(chunks, 2);
+        drop(buffered);
+        pump.await;
+    }
+
+    #[tokio::test]
+    async fn test_parse_bounded_sse_chunks_stops_reading_on_error() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let reads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&reads);
+        let chunks: ByteChunks = Box::pin(futures::stream::repeat_with(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![b'x'; 64])
+        }));
+        let no_callback: Option<fn(&str)> = None;
+        let limits = SseBufferConfig::default().with_max_line_bytes(256);
+
+        let error = parse_bounded_sse_chunks(chunks, 4, no_callback, &limits).await.err().unwrap();
+        assert!(matches!(error, Error::Api(message) if message.contains("SSE line too large")));
+        // Only the line that overflowed plus the buffer ahead of it were read
+        assert!(reads.load(Ordering::SeqCst) <= 5 + 4 + 1);
+    }
+
     #[test]
-    fn test_parse_sse_line_malformed_json() {
+    fn test_parse_sse_data_malformed_json() {
         let line = "data: {invalid json}";
-        // Should not panic, just return None or log warning
-        let result = parse_sse_line(line);
-        // Result should be Ok(None) or handle gracefully
-        assert!(result.is_ok());
+        // Malformed chunks are skipped rather than failing the stream
+        assert!(parse_sse_data(line).is_none());
     }
 }
@@ -0,0 +1,238 @@
+//! Typed streams over WatsonX streaming responses
+//!
+//! `WatsonxStream<T>` turns a streaming HTTP response into a `Stream<Item = Result<T>>`
+//! of decoded chunks, so callers can use `futures::StreamExt` or `tokio_stream::StreamExt`
+//! combinators (`timeout`, `take_while`, ...) instead of callbacks.
+
+use crate::error::{Error, Result};
+use futures::{Stream, StreamExt};
+use reqwest::Response;
+use serde_json::Value;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A chunk of generated text from the text generation stream
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationChunk {
+    /// Generated text in this chunk
+    pub text: String,
+    /// Number of tokens generated so far (if reported)
+    pub generated_token_count: Option<u32>,
+    /// Number of input tokens (if reported)
+    pub input_token_count: Option<u32>,
+    /// Stop reason (set on the final chunk)
+    pub stop_reason: Option<String>,
+}
+
+impl GenerationChunk {
+    /// Parse a single SSE line, returning `None` for non-data or malformed lines
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let data = sse_data(line)?;
+        let result = data.get("results")?.as_array()?.first()?;
+        let count = |name: &str| result.get(name).and_then(|v| v.as_u64()).map(|v| v as u32);
+
+        Some(Self {
+            text: result.get("generated_text")?.as_str()?.to_string(),
+            generated_token_count: count("generated_token_count"),
+            input_token_count: count("input_token_count"),
+            stop_reason: result
+                .get("stop_reason")
+                .and_then(|v| v.as_str())
+                .filter(|reason| *reason != "not_finished")
+                .map(str::to_string),
+        })
+    }
+}
+
+/// A chunk of assistant content from the chat completion stream
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatChunk {
+    /// Content delta in this chunk
+    pub content: String,
+    /// Finish reason (set on the final chunk)
+    pub finish_reason: Option<String>,
+}
+
+impl ChatChunk {
+    /// Parse a single SSE line, returning `None` for non-data or malformed lines
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let data = sse_data(line)?;
+        let choice = data.get("choices")?.as_array()?.first()?;
+        let content = choice
+            .get("delta")
+            .or_else(|| choice.get("message"))
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let finish_reason = choice
+            .get("finish_reason")
+            .and_then(|r| r.as_str())
+            .map(str::to_string);
+
+        if content.is_empty() && finish_reason.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            content,
+            finish_reason,
+        })
+    }
+}
+
+/// An event from the Orchestrate run stream
+pub type OrchestrateChunk = crate::orchestrate::OrchestrateStreamEvent;
+
+/// Extract the JSON payload of an SSE `data:` line
+fn sse_data(line: &str) -> Option<Value> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+    if data.is_empty() || data == "[DONE]" {
+        return None;
+    }
+    serde_json::from_str(data).ok()
+}
+
+/// Stream of decoded chunks from a streaming HTTP response
+pub struct WatsonxStream<T> {
+    inner: Pin<Box<dyn Stream<Item = Result<Vec<u8>>> + Send>>,
+    buffer: Vec<u8>,
+    max_line_bytes: Option<usize>,
+    finished: bool,
+    _chunk: PhantomData<fn() -> T>,
+}
+
+impl<T> WatsonxStream<T> {
+    /// Wrap a successful streaming response
+    pub fn from_response(response: Response) -> Self {
+        let inner = response
+            .bytes_stream()
+            .map(|chunk| chunk.map(|bytes| bytes.to_vec()).map_err(|e| Error::Network(e.to_string())));
+        Self::from_chunks(Box::pin(inner))
+    }
+
+    /// Wrap a stream of raw response body chunks
+    pub(crate) fn from_chunks(inner: Pin<Box<dyn Stream<Item = Result<Vec<u8>>> + Send>>) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            max_line_bytes: None,
+            finished: false,
+            _chunk: PhantomData,
+        }
+    }
+
+    /// Fail with `Error::Api` once a single line grows beyond `max_line_bytes`
+    ///
+    /// Without a limit, a response that never sends a newline is buffered in full.
+    pub fn with_max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = Some(max_line_bytes);
+        self
+    }
+
+    /// Error for a line of `len` bytes, if it exceeds the line limit
+    fn line_limit_error(&self, len: usize) -> Option<Error> {
+        self.max_line_bytes
+            .filter(|max| len > *max)
+            .map(crate::sse::line_too_large)
+    }
+
+    /// Stop the stream with an error
+    fn fail(&mut self, error: Error) -> Poll<Option<Result<T>>> {
+        self.finished = true;
+        self.buffer.clear();
+        Poll::Ready(Some(Err(error)))
+    }
+
+    /// Decode the next complete line from the buffer, reading more bytes as needed
+    fn poll_decoded(&mut self, cx: &mut Context<'_>, parse: fn(&str) -> Option<T>) -> Poll<Option<Result<T>>> {
+        loop {
+            if let Some(newline_pos) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=newline_pos).collect();
+                if let Some(error) = self.line_limit_error(line.len()) {
+                    return self.fail(error);
+                }
+                if let Some(chunk) = std::str::from_utf8(&line).ok().and_then(parse) {
+                    return Poll::Ready(Some(Ok(chunk)));
+                }
+                continue;
+            }
+
+            if let Some(error) = self.line_limit_error(self.buffer.len()) {
+                return self.fail(error);
+            }
+
+            if self.finished {
+                let rest = std::mem::take(&mut self.buffer);
+                return Poll::Ready(std::str::from_utf8(&rest).ok().and_then(parse).map(Ok));
+            }
+
+            match self.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(bytes))) => self.buffer.extend_from_slice(&bytes),
+                Poll::Ready(Some(Err(e))) => return self.fail(e),
+                Poll::Ready(None) => self.finished = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Implement `Stream` for `WatsonxStream<T>` using each chunk type's `parse_line`
+macro_rules! impl_stream_for {
+    ($($chunk:ty),* $(,)?) => {
+        $(
+            impl Stream for WatsonxStream<$chunk> {
+                type Item = Result<$chunk>;
+
+                fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+                    self.get_mut().poll_decoded(cx, <$chunk>::parse_line)
+                }
+            }
+        )*
+    };
+}
+
+impl_stream_for!(GenerationChunk, ChatChunk, OrchestrateChunk);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generation_chunk_parse_line() {
+        let chunk = GenerationChunk::parse_line(
+            r#"data: {"results":[{"generated_text":"Hello","generated_token_count":2,"input_token_count":5,"stop_reason":"not_finished"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.text, "Hello");
+        assert_eq!(chunk.generated_token_count, Some(2));
+        assert_eq!(chunk.input_token_count, Some(5));
+        assert!(chunk.stop_reason.is_none());
+
+        assert!(GenerationChunk::parse_line("event: message").is_none());
+        assert!(GenerationChunk::parse_line("data: [DONE]").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_max_line_bytes() {
+        let line = r#"data: {"results":[{"generated_text":"Hi"}]}"#;
+        let chunks = futures::stream::iter([Ok(format!("{}\n", line).into_bytes())])
+            .chain(futures::stream::repeat_with(|| Ok(vec![b'x'; 64])));
+        let mut stream = WatsonxStream::<GenerationChunk>::from_chunks(Box::pin(chunks)).with_max_line_bytes(256);
+
+        assert_eq!(stream.next().await.unwrap().unwrap().text, "Hi");
+        assert!(matches!(stream.next().await, Some(Err(Error::Api(_)))));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[test]
+    fn test_chat_chunk_parse_line() {
+        let chunk = ChatChunk::parse_line(r#"data: {"choices":[{"delta":{"content":"Hi"}}]}"#).unwrap();
+        assert_eq!(chunk.content, "Hi");
+
+        let last = ChatChunk::parse_line(r#"data: {"choices":[{"delta":{},"finish_reason":"stop"}]}"#).unwrap();
+        assert_eq!(last.content, "");
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+    }
+}
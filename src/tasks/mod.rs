@@ -0,0 +1,11 @@
+//! Higher-level language tasks built on text generation
+//!
+//! Each task builds its own prompt and turns the model output into typed results.
+//! Named entity recognition is provided by `WatsonxClient::extract_entities` and
+//! translation by `WatsonxClient::translate`.
+
+pub mod ner;
+pub mod translation;
+
+pub use ner::{EntityType, ExtractedEntity};
+pub use translation::{Formality, TranslationConfig, TranslationResult};
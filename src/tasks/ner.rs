@@ -0,0 +1,261 @@
+//! Named entity recognition
+//!
+//! `WatsonxClient::extract_entities` asks the model for the entities as JSON, retrying when
+//! the output cannot be parsed, and locates each entity in the source text.
+
+use crate::client::WatsonxClient;
+use crate::error::{Error, Result};
+use crate::types::{DecodingMethod, GenerationConfig, JsonGenerationConfig};
+use serde::Deserialize;
+
+/// Token budget for the JSON list of entities
+const NER_MAX_TOKENS: u32 = 1000;
+
+/// Kind of entity to extract
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EntityType {
+    /// Names of people
+    Person,
+    /// Companies, institutions and other organizations
+    Organization,
+    /// Cities, countries, addresses and other places
+    Location,
+    /// Dates and times
+    Date,
+    /// Amounts of money and other quantities
+    Amount,
+    /// Any other kind of entity, described by its name
+    Custom(String),
+}
+
+impl EntityType {
+    /// Name of this type as used in the prompt and the model output
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Person => "person",
+            Self::Organization => "organization",
+            Self::Location => "location",
+            Self::Date => "date",
+            Self::Amount => "amount",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Find the requested type whose name matches `name`, ignoring case
+    fn matching<'a>(name: &str, entity_types: &'a [EntityType]) -> Option<&'a EntityType> {
+        entity_types
+            .iter()
+            .find(|entity_type| entity_type.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// An entity found in the text
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtractedEntity {
+    /// Kind of entity
+    pub entity_type: EntityType,
+    /// The entity as it appears in the text
+    pub text: String,
+    /// Byte offset where the entity starts, if it was found verbatim in the text
+    pub start_pos: Option<usize>,
+    /// Byte offset just past the end of the entity, if it was found verbatim in the text
+    pub end_pos: Option<usize>,
+}
+
+/// Shape of the JSON the model is asked to produce
+#[derive(Debug, Deserialize)]
+struct EntityList {
+    entities: Vec<RawEntity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEntity {
+    #[serde(rename = "type")]
+    entity_type: String,
+    text: String,
+}
+
+/// JSON Schema for the entity list, restricting `type` to the requested names
+fn entity_schema(entity_types: &[EntityType]) -> serde_json::Value {
+    let names: Vec<&str> = entity_types.iter().map(EntityType::name).collect();
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "entities": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "type": { "type": "string", "enum": names },
+                        "text": { "type": "string" }
+                    },
+                    "required": ["type", "text"]
+                }
+            }
+        },
+        "required": ["entities"]
+    })
+}
+
+/// Build the extraction prompt for `text`
+fn entity_prompt(text: &str, entity_types: &[EntityType]) -> String {
+    let names: Vec<&str> = entity_types.iter().map(EntityType::name).collect();
+    format!(
+        "Extract every entity of these types from the text: {}.\nCopy each entity exactly as it is written in the text, in order of appearance.\n\nText:\n{}",
+        names.join(", "),
+        text.trim()
+    )
+}
+
+/// Turn the model's entity list into `ExtractedEntity` values
+///
+/// Entities of types that were not requested are dropped. Positions are found by searching
+/// the text, continuing after the previous occurrence so repeated entities get distinct spans.
+fn locate_entities(
+    text: &str,
+    raw: Vec<RawEntity>,
+    entity_types: &[EntityType],
+) -> Vec<ExtractedEntity> {
+    let mut found: Vec<(String, usize)> = Vec::new();
+
+    raw.into_iter()
+        .filter_map(|entity| {
+            let entity_type = EntityType::matching(&entity.entity_type, entity_types)?.clone();
+            let entity_text = entity.text.trim().to_string();
+            if entity_text.is_empty() {
+                return None;
+            }
+
+            let search_from = found
+                .iter()
+                .rev()
+                .find(|(previous, _)| *previous == entity_text)
+                .map_or(0, |(_, end)| *end);
+            let start_pos = text[search_from..]
+                .find(&entity_text)
+                .map(|offset| search_from + offset)
+                .or_else(|| text.find(&entity_text));
+            let end_pos = start_pos.map(|start| start + entity_text.len());
+            if let Some(end) = end_pos {
+                found.push((entity_text.clone(), end));
+            }
+
+            Some(ExtractedEntity {
+                entity_type,
+                text: entity_text,
+                start_pos,
+                end_pos,
+            })
+        })
+        .collect()
+}
+
+impl WatsonxClient {
+    /// Extract entities of the given types from `text`
+    ///
+    /// The model is asked for JSON and re-prompted when its output does not parse; after the
+    /// default number of repair attempts this returns `Error::Serialization`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{EntityType, WatsonxClient, WatsonxConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let entities = client
+    ///     .extract_entities(
+    ///         "IBM opened an office in Nairobi on 3 May 2024.",
+    ///         &[EntityType::Organization, EntityType::Location, EntityType::Date],
+    ///     )
+    ///     .await?;
+    /// for entity in entities {
+    ///     println!("{}: {} at {:?}", entity.entity_type.name(), entity.text, entity.start_pos);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn extract_entities(
+        &self,
+        text: &str,
+        entity_types: &[EntityType],
+    ) -> Result<Vec<ExtractedEntity>> {
+        if entity_types.is_empty() {
+            return Err(Error::InvalidInput(
+                "At least one entity type is required for extraction".to_string(),
+            ));
+        }
+
+        let json_config = JsonGenerationConfig::new(entity_schema(entity_types));
+        let config = GenerationConfig::default()
+            .with_decoding_method(DecodingMethod::Greedy)
+            .with_max_tokens(NER_MAX_TOKENS);
+        let list: EntityList = self
+            .generate_json_with_config(&entity_prompt(text, entity_types), &json_config, &config)
+            .await?;
+        Ok(locate_entities(text, list.entities, entity_types))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_entities() {
+        let text = "Ana met Ana Lopez at Acme in Paris.";
+        let types = [
+            EntityType::Person,
+            EntityType::Custom("Company".to_string()),
+        ];
+        let list: EntityList = serde_json::from_str(
+            r#"{"entities": [
+                {"type": "Person", "text": "Ana"},
+                {"type": "person", "text": "Ana"},
+                {"type": "company", "text": " Acme "},
+                {"type": "location", "text": "Paris"},
+                {"type": "person", "text": "Bob"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let entities = locate_entities(text, list.entities, &types);
+        assert_eq!(entities.len(), 4);
+        assert_eq!(
+            (entities[0].start_pos, entities[0].end_pos),
+            (Some(0), Some(3))
+        );
+        assert_eq!(
+            (entities[1].start_pos, entities[1].end_pos),
+            (Some(8), Some(11))
+        );
+        assert_eq!(
+            entities[2].entity_type,
+            EntityType::Custom("Company".to_string())
+        );
+        assert_eq!(
+            (entities[2].text.as_str(), entities[2].start_pos),
+            ("Acme", Some(21))
+        );
+        assert_eq!(
+            (entities[3].text.as_str(), entities[3].start_pos),
+            ("Bob", None)
+        );
+    }
+
+    #[test]
+    fn test_entity_schema_and_prompt() {
+        let types = [EntityType::Date, EntityType::Amount];
+        let schema = entity_schema(&types);
+        assert_eq!(
+            schema["properties"]["entities"]["items"]["properties"]["type"]["enum"],
+            serde_json::json!(["date", "amount"])
+        );
+        assert!(
+            entity_prompt(" Paid $5 today ", &types)
+                .starts_with("Extract every entity of these types from the text: date, amount.")
+        );
+    }
+}
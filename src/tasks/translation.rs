@@ -0,0 +1,351 @@
+//! Translation
+//!
+//! `WatsonxClient::translate` builds a translation prompt from ISO 639-1 language codes and,
+//! when no source language is given, asks the model to report the one it detected.
+
+use crate::client::WatsonxClient;
+use crate::error::{Error, Result};
+use crate::models::DEFAULT_MODEL;
+use crate::types::{DecodingMethod, GenerationConfig};
+
+/// Default translation prompt; `{instructions}` and `{text}` are replaced when building it
+pub const DEFAULT_TRANSLATION_TEMPLATE: &str = "{instructions}\n\nText:\n{text}\n\nOutput:\n";
+
+/// Language codes accepted by `WatsonxClient::translate`, with the names used in prompts
+pub const SUPPORTED_LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+/// Name of the language with this code, ignoring case and any script or region suffix such as `-BR`
+///
+/// Suffixes must be well-formed: a four-letter script (`zh-Hant`), a two-letter or
+/// three-digit region (`pt-BR`, `es-419`), or a script followed by a region.
+pub fn language_name(code: &str) -> Option<&'static str> {
+    let mut subtags = code.trim().split(['-', '_']);
+    let base = subtags.next().unwrap_or_default();
+    let suffix: Vec<&str> = subtags.collect();
+    let is_script = |subtag: &str| subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic());
+    let is_region = |subtag: &str| {
+        (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+            || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+    };
+    let valid_suffix = match suffix.as_slice() {
+        [] => true,
+        [subtag] => is_script(subtag) || is_region(subtag),
+        [script, region] => is_script(script) && is_region(region),
+        _ => false,
+    };
+    if !valid_suffix {
+        return None;
+    }
+
+    SUPPORTED_LANGUAGES
+        .iter()
+        .find(|(supported, _)| supported.eq_ignore_ascii_case(base))
+        .map(|(_, name)| *name)
+}
+
+/// Validate a language code, returning the language name
+fn require_language(code: &str) -> Result<&'static str> {
+    language_name(code).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Unsupported language code '{}'; expected an ISO 639-1 code such as 'en' or 'fr'",
+            code
+        ))
+    })
+}
+
+/// Register of the translation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Formality {
+    /// Polite, formal register
+    Formal,
+    /// Casual, familiar register
+    Informal,
+}
+
+/// Settings for `WatsonxClient::translate_with_config`
+#[derive(Clone, Debug)]
+pub struct TranslationConfig {
+    /// Model used for translation
+    pub model_id: String,
+    /// Register of the translation (defaults to that of the source text)
+    pub formality: Option<Formality>,
+    /// Subject area, such as "legal" or "medical", to guide terminology
+    pub domain: Option<String>,
+    /// Prompt template with `{instructions}` and `{text}` placeholders
+    pub template: String,
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            model_id: DEFAULT_MODEL.to_string(),
+            formality: None,
+            domain: None,
+            template: DEFAULT_TRANSLATION_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl TranslationConfig {
+    /// Create a config using the default model and template
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the model
+    pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+
+    /// Set the register of the translation
+    pub fn with_formality(mut self, formality: Formality) -> Self {
+        self.formality = Some(formality);
+        self
+    }
+
+    /// Set the subject area of the text
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Use a custom prompt template with `{instructions}` and `{text}` placeholders
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+
+    /// Build the translation prompt for `text`
+    ///
+    /// Returns `Error::InvalidInput` if either language code is unsupported.
+    pub fn prompt(
+        &self,
+        text: &str,
+        target_language: &str,
+        source_language: Option<&str>,
+    ) -> Result<String> {
+        let target = require_language(target_language)?;
+        let mut instructions = match source_language {
+            Some(source) => format!(
+                "Translate the text below from {} to {}.",
+                require_language(source)?,
+                target
+            ),
+            None => format!(
+                "Translate the text below to {}. On the first line, write only the ISO 639-1 code of the text's original language in square brackets, e.g. [fr], then write the translation on the following lines.",
+                target
+            ),
+        };
+        if let Some(domain) = &self.domain {
+            instructions.push_str(&format!(
+                " The text is from the {} domain; use its usual terminology.",
+                domain
+            ));
+        }
+        match self.formality {
+            Some(Formality::Formal) => instructions.push_str(" Use a formal register."),
+            Some(Formality::Informal) => instructions.push_str(" Use an informal register."),
+            None => {}
+        }
+        instructions.push_str(" Output only the translation, without notes or explanations.");
+
+        Ok(self
+            .template
+            .replace("{instructions}", &instructions)
+            .replace("{text}", text.trim()))
+    }
+
+    /// Generation settings: greedy decoding with a budget proportional to the text length
+    fn generation_config(&self, text: &str) -> GenerationConfig {
+        let max_tokens = (text.chars().count() as u32).clamp(100, 4000);
+        GenerationConfig::default()
+            .with_model(self.model_id.clone())
+            .with_decoding_method(DecodingMethod::Greedy)
+            .with_max_tokens(max_tokens)
+    }
+}
+
+/// Outcome of `WatsonxClient::translate`
+#[derive(Clone, Debug)]
+pub struct TranslationResult {
+    /// The translated text
+    pub text: String,
+    /// Code of the source language: the one given by the caller, or the one the model
+    /// reported, if it reported a supported code
+    pub source_language: Option<String>,
+    /// Code of the target language
+    pub target_language: String,
+    /// Number of characters in the source text
+    pub source_chars: usize,
+    /// Number of characters in the translation
+    pub translated_chars: usize,
+}
+
+/// Split a leading `[xx]` language tag off model output, if it names a supported language
+///
+/// The translation may follow the tag on the same line or on the next one.
+fn split_language_tag(output: &str) -> (Option<String>, &str) {
+    let output = output.trim_start();
+    let tagged = output
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .filter(|(tag, _)| language_name(tag).is_some() && !tag.contains(char::is_whitespace));
+    match tagged {
+        Some((tag, rest)) => (Some(tag.to_lowercase()), rest.trim_start()),
+        None => (None, output),
+    }
+}
+
+impl WatsonxClient {
+    /// Translate `text` into `target_language`, detecting the source language if not given
+    ///
+    /// Languages are ISO 639-1 codes such as `"en"` or `"pt-BR"`; see `SUPPORTED_LANGUAGES`.
+    /// Unsupported codes return `Error::InvalidInput` before any request is made.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use watsonx_rs::{WatsonxClient, WatsonxConfig};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WatsonxClient::new(WatsonxConfig::from_env()?)?;
+    /// client.connect().await?;
+    ///
+    /// let result = client.translate("Où est la gare ?", "en", None).await?;
+    /// println!("{} (from {:?})", result.text, result.source_language);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn translate(
+        &self,
+        text: &str,
+        target_language: &str,
+        source_language: Option<&str>,
+    ) -> Result<TranslationResult> {
+        self.translate_with_config(
+            text,
+            target_language,
+            source_language,
+            &TranslationConfig::default(),
+        )
+        .await
+    }
+
+    /// Translate `text` with explicit model, formality, domain and template settings
+    pub async fn translate_with_config(
+        &self,
+        text: &str,
+        target_language: &str,
+        source_language: Option<&str>,
+        config: &TranslationConfig,
+    ) -> Result<TranslationResult> {
+        let prompt = config.prompt(text, target_language, source_language)?;
+        let output = self
+            .generate_text(&prompt, &config.generation_config(text))
+            .await?;
+
+        let (detected, translated) = match source_language {
+            Some(source) => (Some(source.to_lowercase()), output.text.as_str()),
+            None => split_language_tag(&output.text),
+        };
+        let translated = translated.trim().to_string();
+
+        Ok(TranslationResult {
+            source_language: detected,
+            target_language: target_language.to_lowercase(),
+            source_chars: text.trim().chars().count(),
+            translated_chars: translated.chars().count(),
+            text: translated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translation_prompt() {
+        let config = TranslationConfig::new()
+            .with_formality(Formality::Formal)
+            .with_domain("legal");
+        let prompt = config.prompt(" Hallo ", "FR", Some("de")).unwrap();
+        assert!(prompt.starts_with(
+            "Translate the text below from German to French. The text is from the legal domain"
+        ));
+        assert!(prompt.contains("Use a formal register."));
+        assert!(prompt.ends_with("Text:\nHallo\n\nOutput:\n"));
+
+        assert!(
+            config
+                .prompt("x", "pt-BR", None)
+                .unwrap()
+                .contains("square brackets")
+        );
+        assert!(matches!(
+            config.prompt("x", "klingon", None),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            config.prompt("x", "en", Some("xx")),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_split_language_tag() {
+        assert_eq!(
+            split_language_tag("[FR]\nWhere is the station?"),
+            (Some("fr".to_string()), "Where is the station?")
+        );
+        assert_eq!(
+            split_language_tag("[pt-BR] Onde fica a estação?"),
+            (Some("pt-br".to_string()), "Onde fica a estação?")
+        );
+        // Only the bracketed form is a tag, so a translation starting with a code is kept
+        assert_eq!(split_language_tag(" de\nHello"), (None, "de\nHello"));
+        assert_eq!(split_language_tag("It is [fr]"), (None, "It is [fr]"));
+        assert_eq!(split_language_tag("[xx] Hello"), (None, "[xx] Hello"));
+        assert_eq!(split_language_tag("Hello there"), (None, "Hello there"));
+    }
+
+    #[test]
+    fn test_language_name() {
+        assert_eq!(language_name("FR"), Some("French"));
+        assert_eq!(language_name("pt-BR"), Some("Portuguese"));
+        assert_eq!(language_name("es_419"), Some("Spanish"));
+        assert_eq!(language_name("zh-Hant-TW"), Some("Chinese"));
+        assert_eq!(language_name("en-garbage"), None);
+        assert_eq!(language_name("en-"), None);
+        assert_eq!(language_name("en-US-x"), None);
+        assert_eq!(language_name("klingon"), None);
+    }
+}
@@ -14,7 +14,10 @@ mod snapshot_tests {
 
     #[test]
     fn test_quality_assessment_snapshot() {
-        let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
+        let config = WatsonxConfig::new(
+            "test_key".to_string(),
+            "12345678-1234-1234-1234-123456789abc".to_string(),
+        );
         let client = WatsonxClient::new(config).unwrap();
 
         let test_cases = vec![
@@ -1,10 +1,16 @@
 //! Core types for WatsonX operations
 
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // Token constants are defined in models.rs to avoid conflicts
 
+/// Default number of response chunks buffered ahead of a streaming callback
+const DEFAULT_STREAM_BUFFER_SIZE: usize = 16;
+
+/// `X-Ratelimit-Reset` values at or above this are Unix timestamps rather than seconds to wait
+const UNIX_TIMESTAMP_THRESHOLD_SECS: f64 = 1_000_000_000.0;
+
 /// Configuration for text generation requests
 #[derive(Clone, Debug, Serialize)]
 pub struct GenerationConfig {
@@ -20,10 +26,35 @@ pub struct GenerationConfig {
     pub top_p: Option<f32>,
     /// Stop sequences to halt generation
     pub stop_sequences: Vec<String>,
-    /// Temperature for generation (not used in current API)
+    /// Temperature for generation (only sent when sampling)
+    ///
+    /// Setting a temperature while `decoding_method` is `Greedy` switches the request to
+    /// sampling, since the API ignores temperature under greedy decoding.
     pub temperature: Option<f32>,
     /// Repetition penalty
     pub repetition_penalty: Option<f32>,
+    /// Decoding method (greedy or sampling)
+    pub decoding_method: DecodingMethod,
+    /// Fall back to greedy decoding when sampling with a near-zero temperature
+    pub auto_switch_to_greedy: bool,
+    /// Return per-token log probabilities with this many top alternatives per token
+    pub return_logprobs: Option<u32>,
+    /// Skip a streamed chunk that repeats the whole answer so far when non-zero (0, the default, disables)
+    pub stream_dedup_window: usize,
+    /// API version for this request, overriding `WatsonxConfig::api_version`
+    pub api_version_override: Option<String>,
+    /// Random seed for reproducible sampling (only used with `DecodingMethod::Sample`)
+    pub seed: Option<u64>,
+    /// Fail streaming requests that receive no tokens within this time of dispatch
+    pub first_token_timeout: Option<Duration>,
+    /// Response chunks read ahead of a slow `generate_text_stream` callback before reading pauses
+    pub stream_buffer_size: usize,
+    /// What `generate_with_config` returns when `timeout` expires mid-generation
+    pub on_timeout: TimeoutBehavior,
+    /// Maximum size of a single line in the generation stream; larger lines fail with `Error::Api`
+    ///
+    /// When unset, `SseBufferConfig::max_line_bytes` of the client configuration applies.
+    pub max_sse_line_bytes: Option<usize>,
 }
 
 impl Default for GenerationConfig {
@@ -37,10 +68,23 @@ impl Default for GenerationConfig {
             stop_sequences: vec![],
             temperature: None,
             repetition_penalty: Some(1.1),
+            decoding_method: DecodingMethod::Greedy,
+            auto_switch_to_greedy: false,
+            return_logprobs: None,
+            stream_dedup_window: 0,
+            api_version_override: None,
+            seed: None,
+            first_token_timeout: None,
+            stream_buffer_size: DEFAULT_STREAM_BUFFER_SIZE,
+            on_timeout: TimeoutBehavior::default(),
+            max_sse_line_bytes: None,
         }
     }
 }
 
+/// Temperature used by `GenerationConfig::deterministic`
+const DETERMINISTIC_TEMPERATURE: f32 = 0.1;
+
 impl GenerationConfig {
     /// Create a config with maximum token support (128k)
     pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
@@ -66,12 +110,34 @@ impl GenerationConfig {
         }
     }
 
+    /// Create a config that gives reproducible output for the same prompt and seed
+    ///
+    /// Uses sampling with a low temperature and the given seed.
+    pub fn deterministic(model_id: &str, seed: u64) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            decoding_method: DecodingMethod::Sample,
+            temperature: Some(DETERMINISTIC_TEMPERATURE),
+            seed: Some(seed),
+            ..Default::default()
+        }
+    }
+
     /// Set the model ID
     pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
         self.model_id = model_id.into();
         self
     }
 
+    /// Set the random seed
+    ///
+    /// Seeds only take effect with `DecodingMethod::Sample`; greedy decoding is already
+    /// deterministic and the seed is not sent.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Set the timeout
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
@@ -101,6 +167,259 @@ impl GenerationConfig {
         self.repetition_penalty = Some(penalty);
         self
     }
+
+    /// Set temperature (used with `DecodingMethod::Sample`)
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Set the decoding method
+    pub fn with_decoding_method(mut self, decoding_method: DecodingMethod) -> Self {
+        self.decoding_method = decoding_method;
+        self
+    }
+
+    /// Switch to greedy decoding automatically when sampling with a near-zero temperature
+    pub fn with_auto_switch_to_greedy(mut self, enabled: bool) -> Self {
+        self.auto_switch_to_greedy = enabled;
+        self
+    }
+
+    /// Return per-token log probabilities in `GenerationResult::token_logprobs`
+    ///
+    /// `top_k` is the number of most likely alternative tokens reported for each
+    /// position (0 for none). Log probabilities are only returned by the non-streaming
+    /// `generate_text` endpoint.
+    pub fn with_return_logprobs(mut self, top_k: u32) -> Self {
+        self.return_logprobs = Some(top_k);
+        self
+    }
+
+    /// Set the stream deduplication window (0 disables deduplication)
+    ///
+    /// Any non-zero value drops a streamed chunk that resends the whole answer so far,
+    /// which some streams do in their final chunk. Other repeated text is always kept.
+    pub fn with_stream_dedup_window(mut self, window: usize) -> Self {
+        self.stream_dedup_window = window;
+        self
+    }
+
+    /// Use a specific API version for requests made with this config
+    pub fn with_api_version_override(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version_override = Some(api_version.into());
+        self
+    }
+
+    /// Set how long streaming requests wait for their first token before timing out
+    ///
+    /// Detects an unresponsive model well before the overall `timeout` expires.
+    pub fn with_first_token_timeout(mut self, first_token_timeout: Duration) -> Self {
+        self.first_token_timeout = Some(first_token_timeout);
+        self
+    }
+
+    /// Set how many response chunks are read ahead of a slow streaming callback
+    ///
+    /// Once the buffer is full, reading the response pauses until the callback catches up.
+    pub fn with_stream_buffer_size(mut self, size: usize) -> Self {
+        self.stream_buffer_size = size.max(1);
+        self
+    }
+
+    /// Set the maximum size of a single line in the generation stream
+    ///
+    /// Bounds the memory used when a malformed response never sends a newline. Overrides
+    /// `SseBufferConfig::max_line_bytes` for text generation.
+    pub fn with_max_sse_line_bytes(mut self, max_sse_line_bytes: usize) -> Self {
+        self.max_sse_line_bytes = Some(max_sse_line_bytes);
+        self
+    }
+
+    /// Set what happens when `timeout` expires after the model has started generating
+    pub fn with_on_timeout(mut self, on_timeout: TimeoutBehavior) -> Self {
+        self.on_timeout = on_timeout;
+        self
+    }
+
+    /// Resolve the API version for a request, validating any override
+    pub fn api_version<'a>(&'a self, default: &'a str) -> crate::error::Result<&'a str> {
+        resolve_api_version(self.api_version_override.as_deref(), default)
+    }
+
+    /// Decoding method implied by the config, treating a temperature as a request to sample
+    fn requested_decoding_method(&self) -> DecodingMethod {
+        match self.decoding_method {
+            DecodingMethod::Greedy if self.temperature.is_some() => DecodingMethod::Sample,
+            method => method,
+        }
+    }
+
+    /// Check whether sampling is configured with a temperature so low it behaves like greedy decoding
+    fn is_degenerate_sampling(&self) -> bool {
+        self.requested_decoding_method() == DecodingMethod::Sample
+            && self.temperature.is_some_and(|t| t <= MIN_SAMPLING_TEMPERATURE)
+    }
+
+    /// Decoding method actually sent to the API
+    ///
+    /// Returns `Sample` when a temperature is set, even if `decoding_method` is `Greedy`,
+    /// because the API ignores temperature under greedy decoding. Returns `Greedy` instead
+    /// of `Sample` when `auto_switch_to_greedy` is set and the temperature is at or below 0.01.
+    pub fn effective_decoding_method(&self) -> DecodingMethod {
+        if self.auto_switch_to_greedy && self.is_degenerate_sampling() {
+            DecodingMethod::Greedy
+        } else {
+            self.requested_decoding_method()
+        }
+    }
+
+    /// Validate the generation parameters
+    ///
+    /// Sampling with `temperature <= 0.01` collapses to picking the most likely token,
+    /// which is what greedy decoding does, but pays the extra cost of sampling. Users
+    /// often set a zero temperature expecting deterministic output; greedy decoding is
+    /// the right tool for that. This case logs a `tracing` warning, and is corrected
+    /// automatically when `auto_switch_to_greedy` is enabled.
+    ///
+    /// Returns `Error::InvalidInput` if the temperature is outside `0.0..=2.0`.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(crate::error::Error::InvalidInput(format!(
+                    "temperature must be between 0.0 and 2.0, got {}",
+                    temperature
+                )));
+            }
+        }
+
+        if let Some(api_version) = &self.api_version_override {
+            ApiVersion::try_new(api_version.as_str())?;
+        }
+
+        if self.is_degenerate_sampling() {
+            if self.auto_switch_to_greedy {
+                tracing::warn!(
+                    temperature = ?self.temperature,
+                    "sampling with temperature <= {} behaves like greedy decoding; switching to greedy",
+                    MIN_SAMPLING_TEMPERATURE
+                );
+            } else {
+                tracing::warn!(
+                    temperature = ?self.temperature,
+                    "sampling with temperature <= {} behaves like greedy decoding but is slower; use DecodingMethod::Greedy for deterministic output",
+                    MIN_SAMPLING_TEMPERATURE
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A WatsonX API version date (`YYYY-MM-DD`), sent as the `version` query parameter
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ApiVersion(String);
+
+impl ApiVersion {
+    /// Parse and validate an API version string
+    pub fn try_new(version: impl Into<String>) -> crate::error::Result<Self> {
+        let version = version.into();
+        let trimmed = version.trim();
+        let parts: Vec<&str> = trimmed.split('-').collect();
+        let valid = matches!(
+            parts.as_slice(),
+            [year, month, day]
+                if year.len() == 4
+                    && month.len() == 2
+                    && day.len() == 2
+                    && [*year, *month, *day].iter().all(|part| part.chars().all(|c| c.is_ascii_digit()))
+                    && (1..=12).contains(&month.parse::<u32>().unwrap_or(0))
+                    && (1..=31).contains(&day.parse::<u32>().unwrap_or(0))
+        );
+
+        if !valid {
+            return Err(crate::error::Error::InvalidInput(format!(
+                "invalid API version '{}': expected a date in YYYY-MM-DD format",
+                version
+            )));
+        }
+
+        Ok(Self(trimmed.to_string()))
+    }
+
+    /// Get the version string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for ApiVersion {
+    type Error = crate::error::Error;
+
+    fn try_from(version: String) -> crate::error::Result<Self> {
+        Self::try_new(version)
+    }
+}
+
+impl From<ApiVersion> for String {
+    fn from(version: ApiVersion) -> Self {
+        version.0
+    }
+}
+
+/// Pick the per-request API version override if set, otherwise the default
+fn resolve_api_version<'a>(api_version_override: Option<&'a str>, default: &'a str) -> crate::error::Result<&'a str> {
+    match api_version_override {
+        Some(api_version) => {
+            ApiVersion::try_new(api_version)?;
+            Ok(api_version.trim())
+        }
+        None => Ok(default),
+    }
+}
+
+/// Temperatures at or below this value make sampling equivalent to greedy decoding
+const MIN_SAMPLING_TEMPERATURE: f32 = 0.01;
+
+/// Outcome of a generation that exceeds `GenerationConfig::timeout`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeoutBehavior {
+    /// Return the text generated so far with `GenerationResult::truncated` set
+    ///
+    /// Falls back to `Error::Timeout` if no text arrived before the timeout.
+    #[default]
+    ReturnPartial,
+    /// Discard the partial text and return `Error::Timeout`
+    ReturnError,
+}
+
+/// Decoding strategy for text generation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecodingMethod {
+    /// Always pick the most likely next token (deterministic)
+    #[default]
+    Greedy,
+    /// Sample the next token using temperature, top-k, and top-p
+    Sample,
+}
+
+impl DecodingMethod {
+    /// API name of the decoding method
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecodingMethod::Greedy => "greedy",
+            DecodingMethod::Sample => "sample",
+        }
+    }
 }
 
 /// Result of a text generation request
@@ -112,10 +431,121 @@ pub struct GenerationResult {
     pub model_id: String,
     /// Number of tokens used (if available)
     pub tokens_used: Option<u32>,
+    /// Number of input (prompt) tokens consumed (if available)
+    pub input_tokens: Option<u32>,
+    /// Number of generated (output) tokens (if available)
+    pub output_tokens: Option<u32>,
     /// Quality score (if calculated)
     pub quality_score: Option<f32>,
     /// Request ID for tracking
     pub request_id: Option<String>,
+    /// Per-token log probabilities (if requested with `with_return_logprobs`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_logprobs: Option<Vec<TokenLogprob>>,
+    /// Why generation stopped (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<StopReason>,
+    /// Generation was cut off by `GenerationConfig::timeout`; `text` is partial
+    #[serde(default)]
+    pub truncated: bool,
+    /// Retry attempts made (set when the client has a retry policy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_stats: Option<RetryStats>,
+    /// Input and generated token counts (set when the API reported both)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsage>,
+}
+
+/// Tokens consumed by a generation request
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Prompt tokens
+    pub input_tokens: u32,
+    /// Generated tokens
+    pub generated_tokens: u32,
+}
+
+impl TokenUsage {
+    /// Input plus generated tokens
+    pub fn total_tokens(&self) -> u32 {
+        self.input_tokens + self.generated_tokens
+    }
+}
+
+/// Why the model stopped generating
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum StopReason {
+    /// Reached the `max_new_tokens` limit; the output may be truncated
+    MaxTokens,
+    /// The model produced its end-of-sequence token
+    EosToken,
+    /// A configured stop sequence was generated
+    StopSequence,
+    /// The request was cancelled
+    Cancelled,
+    /// The time limit was reached
+    TimeLimit,
+    /// A stop reason this crate does not know about
+    Unknown(String),
+}
+
+impl StopReason {
+    /// Parse a stop reason reported by the API, treating `not_finished` as none
+    pub(crate) fn from_api(reason: &str) -> Option<Self> {
+        match reason {
+            "" | "not_finished" => None,
+            other => Some(Self::from(other.to_string())),
+        }
+    }
+
+    /// The API string for this stop reason
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::MaxTokens => "max_tokens",
+            Self::EosToken => "eos_token",
+            Self::StopSequence => "stop_sequence",
+            Self::Cancelled => "cancelled",
+            Self::TimeLimit => "time_limit",
+            Self::Unknown(reason) => reason,
+        }
+    }
+}
+
+impl From<String> for StopReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "max_tokens" => Self::MaxTokens,
+            "eos_token" => Self::EosToken,
+            "stop_sequence" => Self::StopSequence,
+            "cancelled" => Self::Cancelled,
+            "time_limit" => Self::TimeLimit,
+            _ => Self::Unknown(reason),
+        }
+    }
+}
+
+impl From<StopReason> for String {
+    fn from(reason: StopReason) -> Self {
+        reason.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Log probability of a single generated token
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    /// Token text
+    pub token: String,
+    /// Log probability of the token
+    pub logprob: f32,
+    /// Most likely alternative tokens and their log probabilities
+    pub top_logprobs: Option<Vec<(String, f32)>>,
 }
 
 impl GenerationResult {
@@ -125,8 +555,15 @@ impl GenerationResult {
             text,
             model_id,
             tokens_used: None,
+            input_tokens: None,
+            output_tokens: None,
             quality_score: None,
             request_id: None,
+            token_logprobs: None,
+            stop_reason: None,
+            truncated: false,
+            retry_stats: None,
+            token_usage: None,
         }
     }
 
@@ -136,6 +573,19 @@ impl GenerationResult {
         self
     }
 
+    /// Set input and output token counts, updating `tokens_used` with their sum
+    ///
+    /// `token_usage` is set when both counts are known.
+    pub fn with_token_counts(mut self, input_tokens: Option<u32>, output_tokens: Option<u32>) -> Self {
+        self.input_tokens = input_tokens;
+        self.output_tokens = output_tokens;
+        self.tokens_used = Some(input_tokens.unwrap_or(0) + output_tokens.unwrap_or(0));
+        self.token_usage = input_tokens
+            .zip(output_tokens)
+            .map(|(input_tokens, generated_tokens)| TokenUsage { input_tokens, generated_tokens });
+        self
+    }
+
     /// Set the quality score
     pub fn with_quality_score(mut self, score: f32) -> Self {
         self.quality_score = Some(score);
@@ -147,6 +597,23 @@ impl GenerationResult {
         self.request_id = Some(request_id);
         self
     }
+
+    /// Set the per-token log probabilities
+    pub fn with_token_logprobs(mut self, token_logprobs: Vec<TokenLogprob>) -> Self {
+        self.token_logprobs = Some(token_logprobs);
+        self
+    }
+
+    /// Set the stop reason
+    pub fn with_stop_reason(mut self, stop_reason: Option<StopReason>) -> Self {
+        self.stop_reason = stop_reason;
+        self
+    }
+
+    /// Check whether generation stopped because it hit the token limit
+    pub fn was_truncated(&self) -> bool {
+        self.stop_reason == Some(StopReason::MaxTokens)
+    }
 }
 
 /// Configuration for retry attempts
@@ -158,10 +625,15 @@ pub struct RetryConfig {
     pub base_timeout: Duration,
     /// Quality threshold for accepting results
     pub quality_threshold: f32,
-    /// Delay between retries
+    /// Delay before the first retry
     pub retry_delay: Duration,
+    /// Factor the delay grows by after each failed attempt
+    pub backoff_multiplier: f64,
 }
 
+/// Longest delay between two retry attempts
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
 impl Default for RetryConfig {
     fn default() -> Self {
         Self {
@@ -169,6 +641,7 @@ impl Default for RetryConfig {
             base_timeout: Duration::from_secs(30),
             quality_threshold: 0.7,
             retry_delay: Duration::from_secs(1),
+            backoff_multiplier: 2.0,
         }
     }
 }
@@ -193,6 +666,85 @@ impl RetryConfig {
         self.retry_delay = delay;
         self
     }
+
+    /// Set the backoff multiplier
+    pub fn with_backoff_multiplier(mut self, multiplier: f64) -> Self {
+        self.backoff_multiplier = multiplier;
+        self
+    }
+
+    /// Delay after failed attempt number `attempt` (starting at 1), capped at 60 seconds
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = self.backoff_multiplier.max(1.0).powi(attempt.saturating_sub(1) as i32);
+        Duration::try_from_secs_f64(self.retry_delay.as_secs_f64() * factor)
+            .unwrap_or(MAX_RETRY_DELAY)
+            .min(MAX_RETRY_DELAY)
+    }
+}
+
+/// How many attempts a retried request took
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryStats {
+    /// Attempts made, including the one that succeeded
+    pub attempts: u32,
+    /// Total time spent waiting between attempts
+    pub total_wait: Duration,
+}
+
+/// Longest wait for a rate limit window to reset, guarding against garbage reset headers
+const MAX_RATE_LIMIT_RESET: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Rate limit budget reported by the `X-Ratelimit-*` response headers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitState {
+    /// Requests remaining in the current window
+    pub remaining: u32,
+    /// When the window resets
+    pub reset_at: Instant,
+}
+
+impl RateLimitState {
+    /// Time left until the rate limit window resets (zero if already reset)
+    pub fn time_until_reset(&self) -> Duration {
+        self.reset_at.saturating_duration_since(Instant::now())
+    }
+
+    /// Check whether the rate limit is used up until the window resets
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0 && self.time_until_reset() > Duration::ZERO
+    }
+
+    /// Read `X-Ratelimit-Remaining` and `X-Ratelimit-Reset` from response headers
+    ///
+    /// The reset value may be either seconds until the reset or a Unix timestamp in seconds;
+    /// waits longer than a day are treated as a day.
+    /// Returns `None` when `X-Ratelimit-Remaining` is missing or malformed.
+    pub(crate) fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+        let remaining = header("x-ratelimit-remaining")?.parse().ok()?;
+        let reset_secs = header("x-ratelimit-reset")
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|secs| secs.is_finite() && *secs > 0.0)
+            .map(|secs| {
+                // Values this large are absolute timestamps rather than a countdown
+                if secs >= UNIX_TIMESTAMP_THRESHOLD_SECS {
+                    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                    (secs - now.as_secs_f64()).max(0.0)
+                } else {
+                    secs
+                }
+            })
+            .unwrap_or(0.0);
+        let wait = Duration::try_from_secs_f64(reset_secs)
+            .unwrap_or(MAX_RATE_LIMIT_RESET)
+            .min(MAX_RATE_LIMIT_RESET);
+        let now = Instant::now();
+
+        Some(Self {
+            remaining,
+            reset_at: now.checked_add(wait).unwrap_or(now),
+        })
+    }
 }
 
 /// Information about an available model
@@ -214,6 +766,15 @@ pub struct ModelInfo {
     pub max_context_length: Option<u32>,
     /// Whether the model is available
     pub available: Option<bool>,
+    /// Minimum number of examples for few-shot prompting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_shot_size: Option<u32>,
+    /// Number of model parameters as reported by the API (e.g. "13b")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_params: Option<String>,
+    /// Prompt tuning parameters, for models that support tuning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub training_parameters: Option<serde_json::Value>,
 }
 
 impl ModelInfo {
@@ -228,6 +789,9 @@ impl ModelInfo {
             supported_tasks: None,
             max_context_length: None,
             available: None,
+            min_shot_size: None,
+            number_params: None,
+            training_parameters: None,
         }
     }
 
@@ -274,6 +838,157 @@ impl ModelInfo {
     }
 }
 
+/// Filter for `list_models_filtered`
+///
+/// `provider`, `task` and `available_only` are also sent to the API as query filters.
+#[derive(Clone, Debug, Default)]
+pub struct ModelFilter {
+    /// Only include models from this provider (case-insensitive)
+    pub provider: Option<String>,
+    /// Only include models supporting this task (e.g. "text_generation")
+    pub task: Option<String>,
+    /// Only include models marked as available
+    pub available_only: bool,
+    /// Case-insensitive substring matched against model ID, name and description
+    pub search: Option<String>,
+}
+
+impl ModelFilter {
+    /// Create an empty filter that matches every model
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter by provider
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Filter by supported task
+    pub fn with_task(mut self, task: impl Into<String>) -> Self {
+        self.task = Some(task.into());
+        self
+    }
+
+    /// Only include available models
+    pub fn with_available_only(mut self, available_only: bool) -> Self {
+        self.available_only = available_only;
+        self
+    }
+
+    /// Filter by a search term
+    pub fn with_search(mut self, search: impl Into<String>) -> Self {
+        self.search = Some(search.into());
+        self
+    }
+
+    /// Value of the models API `filters` query parameter, if any criterion maps to one
+    ///
+    /// Criteria are combined with `:and`. `search` has no API equivalent.
+    pub fn query_filters(&self) -> Option<String> {
+        let mut filters = Vec::new();
+        if let Some(provider) = &self.provider {
+            filters.push(format!("provider_{}", provider));
+        }
+        if let Some(task) = &self.task {
+            filters.push(format!("function_{}", task));
+        }
+        if self.available_only {
+            filters.push("lifecycle_available".to_string());
+        }
+
+        match filters.len() {
+            0 => None,
+            1 => filters.pop(),
+            _ => Some(format!("{}:and", filters.join(","))),
+        }
+    }
+
+    /// Check whether a model matches every criterion of this filter
+    pub fn matches(&self, model: &ModelInfo) -> bool {
+        if let Some(provider) = &self.provider {
+            if !model
+                .provider
+                .as_deref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(provider))
+            {
+                return false;
+            }
+        }
+
+        if let Some(task) = &self.task {
+            if !model
+                .supported_tasks
+                .as_ref()
+                .is_some_and(|tasks| tasks.iter().any(|t| t.eq_ignore_ascii_case(task)))
+            {
+                return false;
+            }
+        }
+
+        if self.available_only && model.available != Some(true) {
+            return false;
+        }
+
+        if let Some(search) = &self.search {
+            let search = search.to_lowercase();
+            let contains = |field: Option<&str>| field.is_some_and(|f| f.to_lowercase().contains(&search));
+            if !contains(Some(&model.model_id))
+                && !contains(model.name.as_deref())
+                && !contains(model.description.as_deref())
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// One page of results from `list_models_page`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ModelPage {
+    /// Models on this page
+    pub models: Vec<ModelInfo>,
+    /// Cursor for the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+    /// Total number of models across all pages
+    pub total_count: u32,
+}
+
+/// Field used to sort models in `list_models_sorted`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ModelSortField {
+    /// Sort by model ID
+    #[default]
+    ModelId,
+    /// Sort by provider, then model ID
+    Provider,
+    /// Sort by display name (falling back to model ID), then model ID
+    Name,
+}
+
+impl ModelSortField {
+    /// Sort models in place by this field; models missing the field sort last
+    pub fn sort(self, models: &mut [ModelInfo]) {
+        match self {
+            Self::ModelId => models.sort_by(|a, b| a.model_id.cmp(&b.model_id)),
+            Self::Provider => models.sort_by(|a, b| {
+                a.provider
+                    .is_none()
+                    .cmp(&b.provider.is_none())
+                    .then_with(|| a.provider.cmp(&b.provider))
+                    .then_with(|| a.model_id.cmp(&b.model_id))
+            }),
+            Self::Name => models.sort_by(|a, b| {
+                let name = |m: &ModelInfo| m.name.clone().unwrap_or_else(|| m.model_id.clone()).to_lowercase();
+                name(a).cmp(&name(b)).then_with(|| a.model_id.cmp(&b.model_id))
+            }),
+        }
+    }
+}
+
 /// Information about a generation attempt
 #[derive(Clone, Debug)]
 pub struct GenerationAttempt {
@@ -314,6 +1029,19 @@ impl GenerationAttempt {
     }
 }
 
+/// Best of several generation attempts, as returned by `generate_best_of`
+#[derive(Clone, Debug)]
+pub struct BestGenerationResult {
+    /// The attempt with the highest quality score
+    pub result: GenerationResult,
+    /// Quality score of `result`
+    pub quality_score: f32,
+    /// Whether `quality_score` reached `RetryConfig::quality_threshold`
+    pub threshold_met: bool,
+    /// Every attempt made, in order
+    pub all_attempts: Vec<GenerationAttempt>,
+}
+
 /// A single request in a batch generation operation
 #[derive(Clone, Debug)]
 pub struct BatchRequest {
@@ -487,6 +1215,237 @@ impl ChatMessage {
     }
 }
 
+/// How few-shot examples are laid out in a prompt
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ContextFormat {
+    /// `Q: ...` / `A: ...` pairs
+    #[default]
+    QAndA,
+    /// `Input: ...` / `Output: ...` pairs
+    InputOutput,
+    /// `User: ...` / `Assistant: ...` turns
+    Chat,
+    /// Custom template with `{input}` and `{output}` placeholders
+    Custom(String),
+}
+
+impl ContextFormat {
+    /// Render one example; the query is rendered with an empty output
+    fn render_pair(&self, input: &str, output: &str) -> String {
+        let (input_label, output_label) = match self {
+            ContextFormat::QAndA => ("Q:", "A:"),
+            ContextFormat::InputOutput => ("Input:", "Output:"),
+            ContextFormat::Chat => ("User:", "Assistant:"),
+            ContextFormat::Custom(template) => {
+                return template.replace("{input}", input).replace("{output}", output);
+            }
+        };
+        format!("{} {}\n{} {}", input_label, input, output_label, output)
+    }
+}
+
+/// Few-shot examples and instructions placed before a query
+#[derive(Clone, Debug, Default)]
+pub struct GenerationContext {
+    /// Example (input, output) pairs
+    pub examples: Vec<(String, String)>,
+    /// Instructions placed before the examples
+    pub system_prompt: Option<String>,
+    /// Layout of the examples and the query
+    pub format: ContextFormat,
+}
+
+impl GenerationContext {
+    /// Create an empty context with the given format
+    pub fn new(format: ContextFormat) -> Self {
+        Self {
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// Add an example
+    pub fn with_example(mut self, input: impl Into<String>, output: impl Into<String>) -> Self {
+        self.examples.push((input.into(), output.into()));
+        self
+    }
+
+    /// Set the instructions placed before the examples
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Render the prompt for `query`, ending where the model should continue
+    pub fn render(&self, query: &str) -> String {
+        let mut sections: Vec<String> = self.system_prompt.iter().cloned().collect();
+        sections.extend(
+            self.examples
+                .iter()
+                .map(|(input, output)| self.format.render_pair(input, output)),
+        );
+        sections.push(self.format.render_pair(query, "").trim_end().to_string());
+        sections.join("\n\n")
+    }
+}
+
+/// Default number of times `generate_json` asks the model to repair invalid output
+const DEFAULT_JSON_REPAIR_ATTEMPTS: u32 = 3;
+
+/// Configuration for structured JSON generation
+#[derive(Clone, Debug)]
+pub struct JsonGenerationConfig {
+    /// JSON Schema the output must follow
+    pub schema: serde_json::Value,
+    /// How many times to ask the model to fix output that fails to parse
+    pub repair_attempts: u32,
+}
+
+impl JsonGenerationConfig {
+    /// Create a config for the given schema with the default number of repair attempts
+    pub fn new(schema: serde_json::Value) -> Self {
+        Self {
+            schema,
+            repair_attempts: DEFAULT_JSON_REPAIR_ATTEMPTS,
+        }
+    }
+
+    /// Set the number of repair attempts
+    pub fn with_repair_attempts(mut self, repair_attempts: u32) -> Self {
+        self.repair_attempts = repair_attempts;
+        self
+    }
+}
+
+/// Target language for `generate_code`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgrammingLanguage {
+    /// Rust
+    Rust,
+    /// Python
+    Python,
+    /// JavaScript
+    JavaScript,
+    /// TypeScript
+    TypeScript,
+    /// Java
+    Java,
+    /// Go
+    Go,
+    /// SQL
+    Sql,
+    /// Any other language, by name
+    Other(String),
+}
+
+impl ProgrammingLanguage {
+    /// Human-readable name used in prompts
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Python => "Python",
+            Self::JavaScript => "JavaScript",
+            Self::TypeScript => "TypeScript",
+            Self::Java => "Java",
+            Self::Go => "Go",
+            Self::Sql => "SQL",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// Info strings a markdown code fence may use for this language
+    pub fn fence_tags(&self) -> Vec<String> {
+        let tags: &[&str] = match self {
+            Self::Rust => &["rust", "rs"],
+            Self::Python => &["python", "py"],
+            Self::JavaScript => &["javascript", "js"],
+            Self::TypeScript => &["typescript", "ts"],
+            Self::Java => &["java"],
+            Self::Go => &["go", "golang"],
+            Self::Sql => &["sql"],
+            Self::Other(name) => return vec![name.to_lowercase()],
+        };
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    /// Tokens that commonly appear in code written in this language
+    pub fn markers(&self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &["fn ", "let ", "->", "::", "impl ", "pub "],
+            Self::Python => &["def ", "import ", "self", "elif ", "None", "):"],
+            Self::JavaScript => &["function ", "const ", "=>", "let ", "console.", "==="],
+            Self::TypeScript => &["interface ", ": string", ": number", "=>", "const ", "export "],
+            Self::Java => &["public ", "class ", "static ", "void ", "new ", "System."],
+            Self::Go => &["func ", "package ", ":=", "fmt.", "err != nil", "import ("],
+            Self::Sql => &["SELECT ", "FROM ", "WHERE ", "JOIN ", "INSERT ", "GROUP BY"],
+            Self::Other(_) => &[],
+        }
+    }
+
+    /// Example solution used in the few-shot prompt for a sample spec
+    pub(crate) fn example(&self) -> Option<&'static str> {
+        match self {
+            Self::Rust => Some("fn add(a: i64, b: i64) -> i64 {\n    a + b\n}"),
+            Self::Python => Some("def add(a: int, b: int) -> int:\n    return a + b"),
+            Self::JavaScript => Some("function add(a, b) {\n  return a + b;\n}"),
+            Self::TypeScript => Some("function add(a: number, b: number): number {\n  return a + b;\n}"),
+            Self::Java => Some("public static long add(long a, long b) {\n    return a + b;\n}"),
+            Self::Go => Some("func add(a, b int) int {\n\treturn a + b\n}"),
+            Self::Sql => Some("SELECT a + b AS total\nFROM numbers;"),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+/// Settings for `generate_code`
+#[derive(Clone, Debug, Default)]
+pub struct CodeGenConfig {
+    /// Ask for unit tests alongside the code
+    pub include_tests: bool,
+    /// Ask for explanatory comments in the code
+    pub include_comments: bool,
+    /// Upper bound on the number of functions to write
+    pub max_functions: Option<u8>,
+}
+
+impl CodeGenConfig {
+    /// Create a config without tests, comments or a function limit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask for unit tests alongside the code
+    pub fn with_tests(mut self, include_tests: bool) -> Self {
+        self.include_tests = include_tests;
+        self
+    }
+
+    /// Ask for explanatory comments in the code
+    pub fn with_comments(mut self, include_comments: bool) -> Self {
+        self.include_comments = include_comments;
+        self
+    }
+
+    /// Limit the number of functions to write
+    pub fn with_max_functions(mut self, max_functions: u8) -> Self {
+        self.max_functions = Some(max_functions);
+        self
+    }
+}
+
+/// Result of `generate_code`
+#[derive(Clone, Debug)]
+pub struct CodeGenResult {
+    /// Full model output, including any markdown fences and prose
+    pub generated: String,
+    /// The code itself, with markdown fences and surrounding text removed
+    pub code: String,
+    /// How confident we are that `code` is in the requested language, from 0.0 to 1.0
+    pub language_confidence: f32,
+    /// The underlying generation result
+    pub result: GenerationResult,
+}
+
 /// Configuration for chat completion requests
 #[derive(Clone, Debug, Serialize)]
 pub struct ChatCompletionConfig {
@@ -506,6 +1465,10 @@ pub struct ChatCompletionConfig {
     pub stop_sequences: Vec<String>,
     /// Repetition penalty
     pub repetition_penalty: Option<f32>,
+    /// Number of independent completions to generate
+    pub n: Option<u32>,
+    /// API version for this request, overriding `WatsonxConfig::api_version`
+    pub api_version_override: Option<String>,
 }
 
 impl Default for ChatCompletionConfig {
@@ -519,6 +1482,8 @@ impl Default for ChatCompletionConfig {
             top_k: Some(50),
             stop_sequences: vec![],
             repetition_penalty: Some(1.1),
+            n: None,
+            api_version_override: None,
         }
     }
 }
@@ -571,13 +1536,43 @@ impl ChatCompletionConfig {
         self.repetition_penalty = Some(penalty);
         self
     }
+
+    /// Set the number of completions to generate
+    pub fn with_n(mut self, n: u32) -> Self {
+        self.n = Some(n.max(1));
+        self
+    }
+
+    /// Use a specific API version for requests made with this config
+    pub fn with_api_version_override(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version_override = Some(api_version.into());
+        self
+    }
+
+    /// Resolve the API version for a request, validating any override
+    pub fn api_version<'a>(&'a self, default: &'a str) -> crate::error::Result<&'a str> {
+        resolve_api_version(self.api_version_override.as_deref(), default)
+    }
+}
+
+/// A single completion choice returned by a chat completion request
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChatCompletionChoice {
+    /// Generated message
+    pub message: ChatMessage,
+    /// Finish reason (if available)
+    pub finish_reason: Option<String>,
+    /// Index of this choice in the response
+    pub index: u32,
 }
 
 /// Result of a chat completion request
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChatCompletionResult {
-    /// Generated message content
+    /// Generated message content (the first choice)
     pub message: ChatMessage,
+    /// All completion choices, in response order
+    pub choices: Vec<ChatCompletionChoice>,
     /// Model ID used for completion
     pub model_id: String,
     /// Number of prompt tokens used (if available)
@@ -586,7 +1581,7 @@ pub struct ChatCompletionResult {
     pub completion_tokens: Option<u32>,
     /// Total tokens used (if available)
     pub total_tokens: Option<u32>,
-    /// Finish reason (if available)
+    /// Finish reason of the first choice (if available)
     pub finish_reason: Option<String>,
     /// Request ID for tracking
     pub request_id: Option<String>,
@@ -596,6 +1591,11 @@ impl ChatCompletionResult {
     /// Create a new chat completion result
     pub fn new(message: ChatMessage, model_id: String) -> Self {
         Self {
+            choices: vec![ChatCompletionChoice {
+                message: message.clone(),
+                finish_reason: None,
+                index: 0,
+            }],
             message,
             model_id,
             prompt_tokens: None,
@@ -616,7 +1616,21 @@ impl ChatCompletionResult {
 
     /// Set finish reason
     pub fn with_finish_reason(mut self, reason: impl Into<String>) -> Self {
-        self.finish_reason = Some(reason.into());
+        let reason = reason.into();
+        if let Some(first) = self.choices.first_mut() {
+            first.finish_reason = Some(reason.clone());
+        }
+        self.finish_reason = Some(reason);
+        self
+    }
+
+    /// Set all completion choices; `message` and `finish_reason` follow the first choice
+    pub fn with_choices(mut self, choices: Vec<ChatCompletionChoice>) -> Self {
+        if let Some(first) = choices.first() {
+            self.message = first.message.clone();
+            self.finish_reason = first.finish_reason.clone();
+        }
+        self.choices = choices;
         self
     }
 
@@ -626,16 +1640,321 @@ impl ChatCompletionResult {
         self
     }
 
-    /// Get the content of the generated message
+    /// Get the content of the generated message (the first choice)
     pub fn content(&self) -> &str {
-        &self.message.content
+        self.choices
+            .first()
+            .map(|choice| choice.message.content.as_str())
+            .unwrap_or(&self.message.content)
+    }
+}
+
+/// A single conversation in a batch chat completion
+#[derive(Clone, Debug)]
+pub struct ChatCompletionBatchRequest {
+    /// Messages of the conversation
+    pub messages: Vec<ChatMessage>,
+    /// Optional identifier for tracking this conversation
+    pub id: Option<String>,
+    /// Configuration for this conversation (uses the batch default if None)
+    pub config_override: Option<ChatCompletionConfig>,
+}
+
+impl ChatCompletionBatchRequest {
+    /// Create a batch request for a conversation
+    pub fn new(messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages,
+            id: None,
+            config_override: None,
+        }
+    }
+
+    /// Set an identifier for this conversation
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Use a specific configuration for this conversation
+    pub fn with_config(mut self, config: ChatCompletionConfig) -> Self {
+        self.config_override = Some(config);
+        self
     }
 }
 
+/// Extra fields to include in an embeddings response
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingReturnOptions {
+    /// Echo each input text back alongside its embedding
+    pub input_text: bool,
+}
+
+/// Configuration for text embedding requests
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EmbeddingConfig {
+    /// Embedding model ID
+    pub model_id: String,
+    /// Truncate each input to this many tokens instead of failing on long inputs
+    pub truncate_input_tokens: Option<u32>,
+    /// Extra fields to return with the embeddings
+    pub return_options: Option<EmbeddingReturnOptions>,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            model_id: crate::models::DEFAULT_EMBEDDING_MODEL.to_string(),
+            truncate_input_tokens: None,
+            return_options: None,
+        }
+    }
+}
+
+impl EmbeddingConfig {
+    /// Set the model ID
+    pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+
+    /// Truncate each input to `tokens` tokens
+    pub fn with_truncate_input_tokens(mut self, tokens: u32) -> Self {
+        self.truncate_input_tokens = Some(tokens);
+        self
+    }
+
+    /// Set the return options
+    pub fn with_return_options(mut self, options: EmbeddingReturnOptions) -> Self {
+        self.return_options = Some(options);
+        self
+    }
+}
+
+/// Embeddings for a list of input texts
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EmbeddingResult {
+    /// One embedding vector per input, in input order
+    pub embeddings: Vec<Vec<f32>>,
+    /// Input texts echoed back (if requested with `EmbeddingReturnOptions::input_text`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_texts: Option<Vec<String>>,
+    /// Input tokens consumed by each request sent, in order
+    ///
+    /// The API reports one count per request of up to 10 texts, not per text.
+    pub input_token_counts: Vec<u32>,
+    /// Model ID used for the embeddings
+    pub model_id: String,
+    /// Model version (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_version: Option<String>,
+    /// When the last request was processed (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+impl EmbeddingResult {
+    /// Total input tokens consumed across all requests
+    pub fn total_input_tokens(&self) -> u32 {
+        self.input_token_counts.iter().sum()
+    }
+}
+
+/// Tokens of a text as counted by a model's tokenizer
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenizationResult {
+    /// Number of tokens in the text
+    pub token_count: u32,
+    /// The tokens (if returned)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<String>>,
+    /// The token IDs (if returned)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_ids: Option<Vec<u32>>,
+}
+
+/// Configuration for reranking requests
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RerankConfig {
+    /// Reranking model ID
+    pub model_id: String,
+    /// Return only the `top_n` most relevant documents
+    pub top_n: Option<usize>,
+    /// Truncate the query and each document to this many tokens
+    pub truncate_input_tokens: Option<u32>,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            model_id: crate::models::DEFAULT_RERANK_MODEL.to_string(),
+            top_n: None,
+            truncate_input_tokens: None,
+        }
+    }
+}
+
+impl RerankConfig {
+    /// Set the model ID
+    pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+
+    /// Return only the `top_n` most relevant documents
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.top_n = Some(top_n);
+        self
+    }
+
+    /// Truncate the query and each document to `tokens` tokens
+    pub fn with_truncate_input_tokens(mut self, tokens: u32) -> Self {
+        self.truncate_input_tokens = Some(tokens);
+        self
+    }
+}
+
+/// A document scored by a reranking request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RankedDocument {
+    /// Position of the document in the input list
+    pub index: usize,
+    /// Document text
+    pub text: String,
+    /// Relevance to the query (higher is more relevant)
+    pub relevance_score: f32,
+}
+
+/// Documents ordered by relevance to a query
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RerankResult {
+    /// Ranked documents, most relevant first
+    pub results: Vec<RankedDocument>,
+    /// Model ID used for reranking
+    pub model_id: String,
+    /// Input tokens consumed (if reported)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_token_count: Option<u32>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_api_version_override() {
+        assert_eq!(ApiVersion::try_new("2024-05-01").unwrap().as_str(), "2024-05-01");
+        assert!(ApiVersion::try_new("2024-13-01").is_err());
+        assert!(ApiVersion::try_new("v1").is_err());
+
+        let config = GenerationConfig::default();
+        assert_eq!(config.api_version("2023-05-29").unwrap(), "2023-05-29");
+
+        let config = config.with_api_version_override("2024-05-01");
+        assert_eq!(config.api_version("2023-05-29").unwrap(), "2024-05-01");
+        assert!(config.validate().is_ok());
+
+        let config = GenerationConfig::default().with_api_version_override("latest");
+        assert!(config.validate().is_err());
+
+        let chat = ChatCompletionConfig::default().with_api_version_override("2024-05-01");
+        assert_eq!(chat.api_version("2023-05-29").unwrap(), "2024-05-01");
+        let chat = ChatCompletionConfig::default().with_api_version_override("2024/05/01");
+        assert!(chat.api_version("2023-05-29").is_err());
+    }
+
+    #[test]
+    fn test_generation_config_sampling_validation() {
+        let config = GenerationConfig::default()
+            .with_decoding_method(DecodingMethod::Sample)
+            .with_temperature(0.0);
+        assert!(config.validate().is_ok());
+        assert_eq!(config.effective_decoding_method(), DecodingMethod::Sample);
+
+        let config = config.with_auto_switch_to_greedy(true);
+        assert_eq!(config.effective_decoding_method(), DecodingMethod::Greedy);
+
+        let config = config.with_temperature(0.7);
+        assert_eq!(config.effective_decoding_method(), DecodingMethod::Sample);
+
+        let config = GenerationConfig::default().with_temperature(3.0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_generation_context_render() {
+        let context = GenerationContext::new(ContextFormat::QAndA)
+            .with_system_prompt("Answer with a country.")
+            .with_example("Where is Paris?", "France");
+        assert_eq!(
+            context.render("Where is Rome?"),
+            "Answer with a country.\n\nQ: Where is Paris?\nA: France\n\nQ: Where is Rome?\nA:"
+        );
+
+        let context = GenerationContext::new(ContextFormat::Chat).with_example("Hi", "Hello!");
+        assert_eq!(context.render("Bye"), "User: Hi\nAssistant: Hello!\n\nUser: Bye\nAssistant:");
+
+        let context = GenerationContext::new(ContextFormat::Custom("{input} => {output}".to_string()))
+            .with_example("cat", "chat");
+        assert_eq!(context.render("dog"), "cat => chat\n\ndog =>");
+    }
+
+    #[test]
+    fn test_temperature_implies_sampling() {
+        assert_eq!(GenerationConfig::default().effective_decoding_method(), DecodingMethod::Greedy);
+
+        let config = GenerationConfig::default().with_temperature(0.7);
+        assert_eq!(config.decoding_method, DecodingMethod::Greedy);
+        assert_eq!(config.effective_decoding_method(), DecodingMethod::Sample);
+
+        let config = GenerationConfig::default()
+            .with_temperature(0.0)
+            .with_auto_switch_to_greedy(true);
+        assert_eq!(config.effective_decoding_method(), DecodingMethod::Greedy);
+    }
+
+    #[test]
+    fn test_model_filter_and_sort() {
+        let mut models = vec![
+            ModelInfo::new("meta-llama/llama-3".to_string())
+                .with_provider("Meta".to_string())
+                .with_supported_tasks(vec!["text_generation".to_string()])
+                .with_available(true),
+            ModelInfo::new("ibm/granite-13b".to_string())
+                .with_name("Granite 13B".to_string())
+                .with_provider("IBM".to_string())
+                .with_supported_tasks(vec!["text_generation".to_string(), "summarization".to_string()]),
+            ModelInfo::new("ibm/slate-125m".to_string())
+                .with_provider("IBM".to_string())
+                .with_supported_tasks(vec!["embedding".to_string()])
+                .with_available(true),
+        ];
+
+        let filter = ModelFilter::new().with_provider("ibm").with_task("text_generation");
+        let matched: Vec<_> = models.iter().filter(|m| filter.matches(m)).collect();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].model_id, "ibm/granite-13b");
+
+        let available = ModelFilter::new().with_available_only(true).with_search("SLATE");
+        assert_eq!(models.iter().filter(|m| available.matches(m)).count(), 1);
+        assert_eq!(available.query_filters().as_deref(), Some("lifecycle_available"));
+        assert!(ModelFilter::new().with_search("slate").query_filters().is_none());
+        assert_eq!(
+            ModelFilter::new().with_provider("IBM").with_task("text_generation").query_filters().as_deref(),
+            Some("provider_IBM,function_text_generation:and")
+        );
+
+        ModelSortField::ModelId.sort(&mut models);
+        assert_eq!(models[0].model_id, "ibm/granite-13b");
+
+        ModelSortField::Provider.sort(&mut models);
+        assert_eq!(models[2].provider.as_deref(), Some("Meta"));
+
+        ModelSortField::Name.sort(&mut models);
+        assert_eq!(models[0].model_id, "ibm/granite-13b");
+        assert_eq!(models[1].model_id, "ibm/slate-125m");
+    }
+
     #[test]
     fn test_chat_message_creation() {
         let msg = ChatMessage::new("user", "Hello");
@@ -740,4 +2059,74 @@ mod tests {
         let result = ChatCompletionResult::new(message, "model".to_string());
         assert_eq!(result.content(), "Test content");
     }
+
+    #[test]
+    fn test_chat_completion_result_multiple_choices() {
+        let config = ChatCompletionConfig::default().with_n(3);
+        assert_eq!(config.n, Some(3));
+
+        let choices = vec![
+            ChatCompletionChoice {
+                message: ChatMessage::assistant("First"),
+                finish_reason: Some("stop".to_string()),
+                index: 0,
+            },
+            ChatCompletionChoice {
+                message: ChatMessage::assistant("Second"),
+                finish_reason: Some("length".to_string()),
+                index: 1,
+            },
+        ];
+        let result = ChatCompletionResult::new(ChatMessage::assistant(""), "model".to_string())
+            .with_choices(choices);
+
+        assert_eq!(result.choices.len(), 2);
+        assert_eq!(result.content(), "First");
+        assert_eq!(result.message.content, "First");
+        assert_eq!(result.finish_reason, Some("stop".to_string()));
+        assert_eq!(result.choices[1].message.content, "Second");
+    }
+
+    #[test]
+    fn test_rate_limit_state_from_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        assert!(RateLimitState::from_headers(&headers).is_none());
+
+        headers.insert("X-Ratelimit-Remaining", HeaderValue::from_static("0"));
+        headers.insert("X-Ratelimit-Reset", HeaderValue::from_static("30"));
+        let state = RateLimitState::from_headers(&headers).unwrap();
+        assert_eq!(state.remaining, 0);
+        assert!(state.is_exhausted());
+        assert!(state.time_until_reset() > Duration::from_secs(29));
+        assert!(state.time_until_reset() <= Duration::from_secs(30));
+
+        let reset_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 60;
+        headers.insert("X-Ratelimit-Remaining", HeaderValue::from_static("12"));
+        headers.insert("X-Ratelimit-Reset", HeaderValue::from_str(&reset_at.to_string()).unwrap());
+        let state = RateLimitState::from_headers(&headers).unwrap();
+        assert_eq!(state.remaining, 12);
+        assert!(!state.is_exhausted());
+        assert!(state.time_until_reset() > Duration::from_secs(55));
+        assert!(state.time_until_reset() <= Duration::from_secs(60));
+
+        headers.insert("X-Ratelimit-Remaining", HeaderValue::from_static("0"));
+        headers.insert("X-Ratelimit-Reset", HeaderValue::from_static("1e30"));
+        let state = RateLimitState::from_headers(&headers).unwrap();
+        assert!(state.time_until_reset() <= MAX_RATE_LIMIT_RESET);
+        assert!(state.time_until_reset() > MAX_RATE_LIMIT_RESET - Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_retry_backoff_delay() {
+        let retry = RetryConfig::new(10).with_retry_delay(Duration::from_millis(500));
+        assert_eq!(retry.backoff_delay(1), Duration::from_millis(500));
+        assert_eq!(retry.backoff_delay(2), Duration::from_secs(1));
+        assert_eq!(retry.backoff_delay(4), Duration::from_secs(4));
+        assert_eq!(retry.backoff_delay(10), Duration::from_secs(60));
+
+        let flat = retry.with_backoff_multiplier(1.0);
+        assert_eq!(flat.backoff_delay(5), Duration::from_millis(500));
+    }
 }
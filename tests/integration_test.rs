@@ -2,6 +2,8 @@
 
 use watsonx_rs::{GenerationConfig, WatsonxClient, WatsonxConfig};
 
+const TEST_PROJECT_ID: &str = "12345678-1234-1234-1234-123456789abc";
+
 #[test]
 fn test_config_creation() {
     let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
@@ -14,7 +16,7 @@ fn test_config_creation() {
 fn test_config_from_env() {
     unsafe {
         std::env::set_var("WATSONX_API_KEY", "env_key");
-        std::env::set_var("WATSONX_PROJECT_ID", "env_project");
+        std::env::set_var("WATSONX_PROJECT_ID", TEST_PROJECT_ID);
     }
 
     let config = WatsonxConfig::from_env();
@@ -22,7 +24,7 @@ fn test_config_from_env() {
 
     let config = config.unwrap();
     assert_eq!(config.api_key, "env_key");
-    assert_eq!(config.project_id, "env_project");
+    assert_eq!(config.project_id, TEST_PROJECT_ID);
 
     unsafe {
         std::env::remove_var("WATSONX_API_KEY");
@@ -30,6 +32,41 @@ fn test_config_from_env() {
     }
 }
 
+#[cfg(feature = "env-file")]
+#[test]
+fn test_config_from_env_file() {
+    let dir = std::env::temp_dir().join(format!("watsonx-rs-env-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(".env");
+
+    std::fs::write(
+        &path,
+        format!(
+            "# credentials\n\nexport WATSONX_API_KEY=\"file_key\"\nWATSONX_PROJECT_ID='{}'\nWATSONX_API_VERSION=2024-05-01 # pinned\nWATSONX_GENERATE_TIMEOUT=300\n",
+            TEST_PROJECT_ID
+        ),
+    )
+    .unwrap();
+    let config = WatsonxConfig::from_env_file(&path).unwrap();
+    assert_eq!(config.api_key, "file_key");
+    assert_eq!(config.project_id, TEST_PROJECT_ID);
+    assert_eq!(config.api_version, "2024-05-01");
+    assert_eq!(config.generate_timeout_secs, 300);
+
+    std::fs::write(&path, "WATSONX_API_KEY=\"unterminated\n").unwrap();
+    let err = WatsonxConfig::from_env_file(&path).unwrap_err();
+    assert!(err.to_string().contains("line 1"));
+
+    std::fs::write(&path, "just some text\n").unwrap();
+    assert!(matches!(
+        WatsonxConfig::from_env_file(&path),
+        Err(watsonx_rs::Error::Configuration(_))
+    ));
+
+    assert!(WatsonxConfig::from_env_file(&dir.join("missing.env")).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
 #[test]
 fn test_config_missing_env_vars() {
     // Test that from_env returns error when vars are missing
@@ -45,7 +82,7 @@ fn test_config_missing_env_vars() {
 
 #[test]
 fn test_client_creation() {
-    let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
+    let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
 
     let client = WatsonxClient::new(config);
     assert!(client.is_ok());
@@ -93,7 +130,7 @@ fn test_max_tokens_limit() {
 
 #[test]
 fn test_model_id_accessor() {
-    let config = WatsonxConfig::new("test_key".to_string(), "test_project".to_string());
+    let config = WatsonxConfig::new("test_key".to_string(), TEST_PROJECT_ID.to_string());
 
     let client = WatsonxClient::new(config).unwrap();
     assert_eq!(client.model_id(), "ibm/granite-4-h-small");